@@ -0,0 +1,168 @@
+//! Companion proc-macros for [`debug-span`](https://docs.rs/debug-span).
+//!
+//! This crate is re-exported by `debug-span` behind the `macros` feature and should not be used
+//! directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, TokenTree};
+use quote::quote;
+
+/// Print the rendering of the macro invocation span to stderr, or to the file named by the
+/// `DEBUG_SPAN_OUT` environment variable.
+///
+/// The span and the source file are captured when the macro is expanded, the rendering itself
+/// is printed when the generated code runs. It is useful as a "where am I being expanded" probe
+/// for macro authors: put it inside a `macro_rules!` body or in code generated by a proc-macro
+/// and see which location the compiler attributes to it.
+///
+/// ```rust,ignore
+/// debug_span::debug_span_here!();
+/// ```
+///
+/// Prints something like:
+///
+/// ```text
+/// [src/main.rs:2:5]
+///  --> 2:4..2:30
+///   |
+/// 2 |     debug_span::debug_span_here!();
+///   |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+///   |
+/// ```
+#[proc_macro]
+pub fn debug_span_here(input: TokenStream) -> TokenStream {
+    if !input.is_empty() {
+        return quote! {
+            ::std::compile_error!("debug_span_here! does not accept any arguments");
+        }
+        .into();
+    }
+
+    let span = proc_macro::Span::call_site();
+    let start = span.start();
+    let end = span.end();
+    // proc_macro columns are 1-indexed, debug-span follows proc-macro2 and uses 0-indexed columns
+    let position = |line: usize, column: usize| {
+        quote! {
+            ::debug_span::Position::from_one_based(#line, #column)
+                .expect("proc_macro positions are 1-based")
+        }
+    };
+    let start = position(start.line(), start.column());
+    let end = position(end.line(), end.column());
+    let line_column_span = quote! {
+        ::debug_span::LineColumnSpan::from_positions(#start, #end)
+    };
+
+    let code = span
+        .local_file()
+        .and_then(|path| std::fs::read_to_string(path).ok());
+
+    let rendering = match code {
+        Some(code) => quote! {
+            ::debug_span::debug_span(
+                #line_column_span,
+                #code,
+            )
+        },
+        None => quote! {
+            ::std::format!(
+                "{}\n",
+                ::debug_span::Span::to_range(
+                    &#line_column_span,
+                ),
+            )
+        },
+    };
+
+    quote! {
+        ::debug_span::internal::eprint(&::std::format!(
+            "[{}:{}:{}]\n{}",
+            ::std::file!(),
+            ::std::line!(),
+            ::std::column!(),
+            #rendering,
+        ))
+    }
+    .into()
+}
+
+/// Instrument a proc-macro entry point: when the `DEBUG_SPANS` environment variable is set, its
+/// input source, the spans of its input tokens and the spans of its output tokens are printed
+/// to stderr, or to the file named by `DEBUG_SPAN_OUT`.
+///
+/// `DEBUG_SPANS=1` traces every instrumented entry point, `DEBUG_SPANS=derive_foo,bar` only the
+/// functions with these names. The last parameter of the function is traced as the input, i.e.
+/// the item of an attribute macro. Without `DEBUG_SPANS`, the function runs unchanged.
+///
+/// ```rust,ignore
+/// #[debug_span::debug_spans]
+/// #[proc_macro_derive(Foo)]
+/// pub fn derive_foo(input: TokenStream) -> TokenStream {
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn debug_spans(args: TokenStream, item: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return quote! {
+            ::std::compile_error!("#[debug_spans] does not accept any arguments");
+        }
+        .into();
+    }
+    match instrument(item.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(message) => quote! { ::std::compile_error!(#message); }.into(),
+    }
+}
+
+/// Wraps the body of a function between the start and the end of a `ProcMacroTrace`.
+fn instrument(item: proc_macro2::TokenStream) -> Result<proc_macro2::TokenStream, &'static str> {
+    const NOT_A_FUNCTION: &str = "#[debug_spans] expects a function";
+    let mut tokens = item.into_iter().collect::<Vec<_>>();
+    let body = match tokens.pop() {
+        Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => body,
+        _ => return Err(NOT_A_FUNCTION),
+    };
+    let fn_index = tokens
+        .iter()
+        .position(|token| matches!(token, TokenTree::Ident(ident) if ident == "fn"))
+        .ok_or(NOT_A_FUNCTION)?;
+    let (name, params) = match &tokens[fn_index + 1..] {
+        [TokenTree::Ident(name), TokenTree::Group(params), ..]
+            if params.delimiter() == Delimiter::Parenthesis =>
+        {
+            (name.clone(), params.clone())
+        }
+        _ => return Err(NOT_A_FUNCTION),
+    };
+    let input = last_param_binding(params.stream())
+        .ok_or("#[debug_spans] expects a function with a named input parameter")?;
+    let return_type = &tokens[fn_index + 3..];
+    let name = name.to_string();
+    Ok(quote! {
+        #(#tokens)* {
+            let __debug_spans_trace = ::debug_span::internal::ProcMacroTrace::start(#name, &#input);
+            let __debug_spans_output = (move || #(#return_type)* #body)();
+            if let ::std::option::Option::Some(trace) = __debug_spans_trace {
+                trace.finish(&__debug_spans_output);
+            }
+            __debug_spans_output
+        }
+    })
+}
+
+/// Returns the name bound by the last parameter, e.g. `item` in `args: TokenStream, mut item:
+/// TokenStream`.
+fn last_param_binding(params: proc_macro2::TokenStream) -> Option<proc_macro2::Ident> {
+    let params = params.into_iter().collect::<Vec<_>>();
+    let last = params
+        .split(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','))
+        .rfind(|param| !param.is_empty())?;
+    let pattern =
+        last.split(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ':'));
+    match pattern.into_iter().next()? {
+        [.., TokenTree::Ident(ident)] if ident != "_" => Some(ident.clone()),
+        _ => None,
+    }
+}
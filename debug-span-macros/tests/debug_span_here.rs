@@ -0,0 +1,14 @@
+#[test]
+fn test_debug_span_here() {
+    debug_span::debug_span_here!();
+}
+
+#[test]
+fn test_debug_span_here_in_macro_rules() {
+    macro_rules! probe {
+        () => {
+            debug_span::debug_span_here!()
+        };
+    }
+    probe!();
+}
@@ -0,0 +1,31 @@
+use proc_macro2::TokenStream;
+
+#[debug_span::debug_spans]
+fn expand(_args: TokenStream, mut item: TokenStream) -> TokenStream {
+    if item.is_empty() {
+        return TokenStream::new();
+    }
+    item.extend("impl Foo {}".parse::<TokenStream>().unwrap());
+    item
+}
+
+#[test]
+fn test_debug_spans() {
+    let item = "struct Foo;".parse::<TokenStream>().unwrap();
+    assert_eq!(
+        expand(TokenStream::new(), item.clone()).to_string(),
+        "struct Foo ; impl Foo { }"
+    );
+    assert!(expand(TokenStream::new(), TokenStream::new()).is_empty());
+
+    let path = std::env::temp_dir().join(format!("debug-spans-{}.txt", std::process::id()));
+    std::env::set_var("DEBUG_SPAN_OUT", &path);
+    std::env::set_var("DEBUG_SPANS", "other,expand");
+    let output = expand(TokenStream::new(), item);
+    std::env::remove_var("DEBUG_SPANS");
+    let trace = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.to_string(), "struct Foo ; impl Foo { }");
+    assert!(trace.contains("[debug_spans] expand\n"), "{}", trace);
+    assert!(trace.contains("\nFoo 1:7..1:10\n"), "{}", trace);
+}
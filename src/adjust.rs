@@ -0,0 +1,270 @@
+use crate::{LineColumnSpan, SourceCode, Span};
+
+/// Grow a span by `lines` lines and `columns` columns on both sides, clamped to the code.
+///
+/// The start moves up by `lines` and left by `columns`, the end moves down and right. Columns
+/// don't wrap to the neighbouring lines: they stop at the start or the end of the line.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{expand, find_span, span_text};
+///
+/// let code = "fn foo(a: i32) {}";
+/// let span = find_span("a: i32", code);
+/// assert_eq!(span_text(expand(span, code, 0, 1), code), "(a: i32)");
+/// ```
+pub fn expand(
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+    lines: usize,
+    columns: usize,
+) -> LineColumnSpan {
+    let (start_line, start_column) = clamp(
+        code,
+        span.start_line().saturating_sub(lines),
+        span.start_column().saturating_sub(columns),
+    );
+    let (end_line, end_column) = clamp(code, span.end_line() + lines, span.end_column() + columns);
+    LineColumnSpan::new(start_line, start_column, end_line, end_column)
+}
+
+/// Shrink a span by `lines` lines and `columns` columns on both sides, clamped to the code.
+///
+/// The opposite of [`expand`]. When the start moves past the end, the result is the empty span at
+/// the moved start.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{find_span, shrink, span_text};
+///
+/// let code = "fn foo(a: i32) {}";
+/// let span = find_span("(a: i32)", code);
+/// assert_eq!(span_text(shrink(span, code, 0, 1), code), "a: i32");
+/// ```
+pub fn shrink(
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+    lines: usize,
+    columns: usize,
+) -> LineColumnSpan {
+    let start = clamp(
+        code,
+        span.start_line() + lines,
+        span.start_column() + columns,
+    );
+    let end = clamp(
+        code,
+        span.end_line().saturating_sub(lines),
+        span.end_column().saturating_sub(columns),
+    );
+    let (end_line, end_column) = end.max(start);
+    LineColumnSpan::new(start.0, start.1, end_line, end_column)
+}
+
+/// Shrink a span so it doesn't start or end on whitespace, including line breaks.
+///
+/// A span covering only whitespace becomes the empty span at its first non-whitespace position,
+/// or at its end.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{trim_span, LineColumnSpan};
+///
+/// let code = "struct Foo {\n    a: i32,  \n}";
+/// let span = trim_span(LineColumnSpan::new(1, 12, 2, 13), code);
+/// assert_eq!(span, LineColumnSpan::new(2, 4, 2, 11));
+/// ```
+pub fn trim_span(span: impl Span, code: &(impl SourceCode + ?Sized)) -> LineColumnSpan {
+    let end = (span.end_line(), span.end_column());
+    let (mut line, mut column) = (span.start_line(), span.start_column());
+    while (line, column) < end {
+        match char_at(code, line, column) {
+            Some(c) if !c.is_whitespace() => break,
+            Some(_) => column += 1,
+            None => (line, column) = (line + 1, 0),
+        }
+    }
+    let start = (line, column).min(end);
+
+    let (mut line, mut column) = end;
+    while start < (line, column) {
+        if column == 0 {
+            line -= 1;
+            column = line_length(code, line);
+            continue;
+        }
+        match char_at(code, line, column - 1) {
+            Some(c) if !c.is_whitespace() => break,
+            _ => column -= 1,
+        }
+    }
+    LineColumnSpan::new(start.0, start.1, line, column)
+}
+
+/// Expand a span from the start of its start line to the end of its end line.
+///
+/// The line terminator of the end line is not included.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{to_full_lines, LineColumnSpan};
+///
+/// let code = "struct Foo {\n    a: i32,\n}";
+/// let span = to_full_lines(LineColumnSpan::new(2, 4, 2, 5), code);
+/// assert_eq!(span, LineColumnSpan::new(2, 0, 2, 11));
+/// ```
+pub fn to_full_lines(span: impl Span, code: &(impl SourceCode + ?Sized)) -> LineColumnSpan {
+    LineColumnSpan::new(
+        span.start_line(),
+        0,
+        span.end_line(),
+        line_length(code, span.end_line()),
+    )
+}
+
+/// The character at a position, `None` past the end of the line.
+fn char_at(code: &(impl SourceCode + ?Sized), line: usize, column: usize) -> Option<char> {
+    code.line(line)?.chars().nth(column)
+}
+
+/// Shift a span by `delta_lines` lines and `delta_columns` columns, and check that the result
+/// lies within `target`.
+///
+/// All lines are shifted by the same number of columns, see [`SpanMapper`](crate::SpanMapper)
+/// to remap columns differently. Returns `None` if a coordinate becomes negative, or the shifted
+/// span is out of range of `target`.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{span_text, translate, LineColumnSpan};
+///
+/// // a snippet embedded in a file, indented by 4 columns from line 3
+/// let file = "mod foo {\n\n    struct Foo;\n}";
+/// let span = LineColumnSpan::new(1, 7, 1, 10);
+/// let shifted = translate(span, 2, 4, file).unwrap();
+/// assert_eq!(span_text(shifted, file), "Foo");
+/// assert_eq!(translate(span, 5, 0, file), None);
+/// ```
+pub fn translate(
+    span: impl Span,
+    delta_lines: isize,
+    delta_columns: isize,
+    target: &(impl SourceCode + ?Sized),
+) -> Option<LineColumnSpan> {
+    let shifted = LineColumnSpan::new(
+        span.start_line().checked_add_signed(delta_lines)?,
+        span.start_column().checked_add_signed(delta_columns)?,
+        span.end_line().checked_add_signed(delta_lines)?,
+        span.end_column().checked_add_signed(delta_columns)?,
+    );
+    let start = (shifted.start_line, shifted.start_column);
+    let end = (shifted.end_line, shifted.end_column);
+    if start == clamp(target, start.0, start.1) && end == clamp(target, end.0, end.1) {
+        Some(shifted)
+    } else {
+        None
+    }
+}
+
+/// Clamp a position to the existing lines of the code and to the length of its line.
+fn clamp(code: &(impl SourceCode + ?Sized), line: usize, column: usize) -> (usize, usize) {
+    let line = line.clamp(1, code.line_count().max(1));
+    (line, column.min(line_length(code, line)))
+}
+
+/// The number of characters of a line, `0` for a missing line.
+fn line_length(code: &(impl SourceCode + ?Sized), line: usize) -> usize {
+    code.line(line).map_or(0, |line| line.chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        let field = LineColumnSpan::new(2, 4, 2, 10);
+        assert_eq!(expand(field, code, 1, 0), LineColumnSpan::new(1, 4, 3, 1));
+        assert_eq!(expand(field, code, 0, 8), LineColumnSpan::new(2, 0, 2, 11));
+        assert_eq!(expand(field, code, 5, 5), LineColumnSpan::new(1, 0, 3, 1));
+        insta::assert_snapshot!(crate::debug_span(expand(field, code, 1, 0), code), @r###"
+         --> 1:4..3:1
+          |
+          |     ┌───────────╮
+        1 | struct Foo {    │
+        2 |     a: i32,     │
+        3 | }               │
+          | └───────────────╯
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_shrink() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        let item = LineColumnSpan::new(1, 0, 3, 1);
+        assert_eq!(shrink(item, code, 1, 0), LineColumnSpan::new(2, 0, 2, 1));
+        let body = LineColumnSpan::new(1, 11, 3, 1);
+        assert_eq!(shrink(body, code, 0, 1), LineColumnSpan::new(1, 12, 3, 0));
+        assert_eq!(shrink(body, code, 1, 0), LineColumnSpan::new(2, 11, 2, 11));
+        let field = LineColumnSpan::new(2, 4, 2, 10);
+        assert_eq!(shrink(field, code, 0, 2), LineColumnSpan::new(2, 6, 2, 8));
+        assert_eq!(shrink(field, code, 0, 4), LineColumnSpan::new(2, 8, 2, 8));
+    }
+
+    #[test]
+    fn test_trim_span() {
+        let code = "struct Foo {\n    a: i32,  \n  \n}";
+        let span = LineColumnSpan::new(1, 12, 4, 0);
+        assert_eq!(trim_span(span, code), LineColumnSpan::new(2, 4, 2, 11));
+        let field = LineColumnSpan::new(2, 4, 2, 11);
+        assert_eq!(trim_span(field, code), field);
+        let blank = LineColumnSpan::new(2, 11, 3, 2);
+        assert_eq!(trim_span(blank, code), LineColumnSpan::new(3, 2, 3, 2));
+        let out_of_range = LineColumnSpan::new(4, 1, 6, 0);
+        assert_eq!(
+            trim_span(out_of_range, code),
+            LineColumnSpan::new(6, 0, 6, 0)
+        );
+    }
+
+    #[test]
+    fn test_to_full_lines() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        let span = LineColumnSpan::new(1, 7, 2, 5);
+        assert_eq!(to_full_lines(span, code), LineColumnSpan::new(1, 0, 2, 11));
+        insta::assert_snapshot!(crate::debug_span(to_full_lines(span, code), code), @r###"
+         --> 1:0..2:11
+          |
+          | ┌───────────────╮
+        1 | struct Foo {    │
+        2 |     a: i32,     │
+          |           └─────╯
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_translate() {
+        let code = "mod foo {\n    struct Foo;\n}";
+        let span = LineColumnSpan::new(1, 7, 1, 10);
+        assert_eq!(
+            translate(span, 1, 4, code),
+            Some(LineColumnSpan::new(2, 11, 2, 14))
+        );
+        assert_eq!(
+            translate(span, 0, -7, code),
+            Some(LineColumnSpan::new(1, 0, 1, 3))
+        );
+        assert_eq!(translate(span, 0, -8, code), None);
+        assert_eq!(translate(span, -1, 0, code), None);
+        assert_eq!(translate(span, 2, 0, code), None);
+        assert_eq!(translate(span, 1, 6, code), None);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{DebugSpanOptions, LineColumnSpan, SourceCode, Span};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+/// Asserts that two spans point to the same location in the source code.
+///
+/// Only the line and column coordinates are compared. On failure, the panic message lists the
+/// coordinates that differ and renders both spans over the code.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::assert_span_eq;
+///
+/// let input = "struct Foo;";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+/// let span = derive_input.ident.span();
+///
+/// assert_span_eq!(span, span, input);
+/// assert_span_eq!(span, span, input, "ident span of {}", "Foo");
+/// ```
+///
+/// A failing assertion panics with a message like:
+///
+/// ```text
+/// assertion `left == right` failed: spans differ
+///   left: 1:7..1:10
+///  right: 1:0..1:6
+///   diff: start column 7 != 0, end column 10 != 6
+///
+/// left:
+///  --> 1:7..1:10
+///   |
+/// 1 | struct Foo;
+///   |        ^^^
+///   |
+///
+/// right:
+///  --> 1:0..1:6
+///   |
+/// 1 | struct Foo;
+///   | ^^^^^^
+///   |
+/// ```
+#[macro_export]
+macro_rules! assert_span_eq {
+    ($left:expr, $right:expr, $code:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !$crate::internal::same_location(left, right) {
+                    ::core::panic!(
+                        "{}",
+                        $crate::internal::assert_span_eq_message(
+                            left,
+                            right,
+                            $code,
+                            None,
+                            &$crate::internal::stderr_options(),
+                        )
+                    );
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $code:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !$crate::internal::same_location(left, right) {
+                    ::core::panic!(
+                        "{}",
+                        $crate::internal::assert_span_eq_message(
+                            left,
+                            right,
+                            $code,
+                            Some(::core::format_args!($($arg)+)),
+                            &$crate::internal::stderr_options(),
+                        )
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Returns `true` if two spans have the same coordinates.
+///
+/// The spans may be of different types, and the identity and hygiene of `proc_macro2::Span`s are
+/// ignored. [`assert_span_eq!`] is the assertion variant, rendering both spans on mismatch.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{same_location, LineColumnSpan};
+///
+/// let input = "struct Foo;";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+/// assert!(same_location(&derive_input.ident.span(), &LineColumnSpan::new(1, 7, 1, 10)));
+/// ```
+pub fn same_location(left: &(impl Span + ?Sized), right: &(impl Span + ?Sized)) -> bool {
+    location_cmp(left, right) == Ordering::Equal
+}
+
+/// Compares two spans by their coordinates: by start position, then by end position.
+///
+/// See [`same_location`] for more information.
+pub fn location_cmp(left: &(impl Span + ?Sized), right: &(impl Span + ?Sized)) -> Ordering {
+    LineColumnSpan::from_span(left).cmp(&LineColumnSpan::from_span(right))
+}
+
+pub fn assert_span_eq_message(
+    left: &(impl Span + ?Sized),
+    right: &(impl Span + ?Sized),
+    code: &(impl SourceCode + ?Sized),
+    args: Option<fmt::Arguments>,
+    options: &DebugSpanOptions,
+) -> String {
+    let title = match args {
+        Some(args) => format!("assertion `left == right` failed: {}", args),
+        None => "assertion `left == right` failed: spans differ".to_string(),
+    };
+    format!(
+        "{}\n  left: {}\n right: {}\n  diff: {}\n\nleft:\n{}\nright:\n{}",
+        title,
+        left.to_range(),
+        right.to_range(),
+        location_diff(left, right),
+        crate::internal::debug_span_with_options(left, code, options),
+        crate::internal::debug_span_with_options(right, code, options),
+    )
+}
+
+fn location_diff(left: &(impl Span + ?Sized), right: &(impl Span + ?Sized)) -> String {
+    let coordinates = [
+        ("start line", left.start_line(), right.start_line()),
+        ("start column", left.start_column(), right.start_column()),
+        ("end line", left.end_line(), right.end_line()),
+        ("end column", left.end_column(), right.end_column()),
+    ];
+    coordinates
+        .iter()
+        .filter(|(_, left, right)| left != right)
+        .map(|(name, left, right)| format!("{} {} != {}", name, left, right))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unindent::Unindent;
+
+    #[test]
+    fn test_same_location() {
+        let input = r###"
+            struct Foo;
+        "###
+        .unindent();
+        let derive_input: syn::DeriveInput = syn::parse_str(&input).unwrap();
+        let ident = derive_input.ident.span();
+        let semi = match derive_input.data {
+            syn::Data::Struct(s) => s.semi_token.unwrap().span,
+            _ => panic!("expected struct"),
+        };
+        assert!(same_location(&ident, &ident));
+        assert!(!same_location(&ident, &semi));
+        assert_span_eq!(ident, ident, &input);
+        assert_eq!(location_cmp(&ident, &semi), Ordering::Less);
+        let owned = LineColumnSpan::from_span(&semi);
+        assert_eq!(location_cmp(&semi, &owned), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_assert_span_eq_message() {
+        let input = r###"
+            struct Foo;
+        "###
+        .unindent();
+        let derive_input: syn::DeriveInput = syn::parse_str(&input).unwrap();
+        let ident = derive_input.ident.span();
+        let semi = match derive_input.data {
+            syn::Data::Struct(s) => s.semi_token.unwrap().span,
+            _ => panic!("expected struct"),
+        };
+        let output = assert_span_eq_message(&ident, &semi, &input, None, &Default::default());
+        insta::assert_snapshot!(output, @r###"
+        assertion `left == right` failed: spans differ
+          left: 1:7..1:10
+         right: 1:10..1:11
+          diff: start column 7 != 10, end column 10 != 11
+
+        left:
+         --> 1:7..1:10
+          |
+        1 | struct Foo;
+          |        ^^^
+          |
+
+        right:
+         --> 1:10..1:11
+          |
+        1 | struct Foo;
+          |           ^
+          |
+        "###);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion `left == right` failed: ident of Foo")]
+    fn test_assert_span_eq_custom_message() {
+        let input = r###"
+            struct Foo;
+        "###
+        .unindent();
+        let derive_input: syn::DeriveInput = syn::parse_str(&input).unwrap();
+        let ident = derive_input.ident.span();
+        let call_site = proc_macro2::Span::call_site();
+        assert_span_eq!(ident, call_site, &input, "ident of {}", "Foo");
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{DebugSpanOptions, LineColumnSpan, SourceCode, Span, SpanExt, TokenStreamExt};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use proc_macro2::TokenStream;
+
+/// Where the span of an output token points to, see [`audit_respanning`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpanOrigin {
+    /// Into the macro input, e.g. with `quote_spanned!` or a token copied from the input.
+    Input,
+    /// Anywhere else, typically `Span::call_site()` or `Span::mixed_site()`.
+    CallSite,
+}
+
+/// A token of the macro output and the origin of its span.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AuditedToken {
+    pub text: String,
+    pub span: LineColumnSpan,
+    pub origin: SpanOrigin,
+}
+
+/// The report of [`audit_respanning`].
+///
+/// It displays as a table of the output tokens, followed by the rendering of the input spans
+/// they carry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RespanAudit {
+    pub tokens: Vec<AuditedToken>,
+    snippet: String,
+}
+
+impl RespanAudit {
+    /// The output tokens whose span points into the input.
+    pub fn input_tokens(&self) -> impl Iterator<Item = &AuditedToken> {
+        self.tokens
+            .iter()
+            .filter(|token| token.origin == SpanOrigin::Input)
+    }
+
+    /// The output tokens whose span doesn't point into the input.
+    pub fn call_site_tokens(&self) -> impl Iterator<Item = &AuditedToken> {
+        self.tokens
+            .iter()
+            .filter(|token| token.origin == SpanOrigin::CallSite)
+    }
+}
+
+/// Report which tokens of a macro output carry spans pointing into the macro input, and which
+/// ones carry call-site spans.
+///
+/// A span points into the input if it is not empty and lies within the first and last tokens of
+/// `input`. `code` is the source of `input`, used to render the input spans of the report.
+/// Snapshotting the report catches span hygiene regressions, e.g. a `quote!` replacing a
+/// `quote_spanned!`.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::audit_respanning;
+/// use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+///
+/// let code = "struct Foo;";
+/// let input = code.parse::<TokenStream>().unwrap();
+/// let name = input.clone().into_iter().nth(1).unwrap();
+/// let output = TokenStream::from_iter([
+///     TokenTree::Ident(Ident::new("impl", Span::call_site())),
+///     name,
+/// ]);
+/// let audit = audit_respanning(&input, &output, code);
+/// assert_eq!(audit.call_site_tokens().count(), 1);
+/// assert_eq!(audit.input_tokens().next().unwrap().text, "Foo");
+/// ```
+pub fn audit_respanning(
+    input: &TokenStream,
+    output: &TokenStream,
+    code: &(impl SourceCode + ?Sized),
+) -> RespanAudit {
+    let mut input_tokens = input.iter_spans().map(|(_, span)| span);
+    let input_span = input_tokens
+        .next()
+        .map(|first| input_tokens.fold(first, |joined, span| joined.join(&span)));
+    let tokens = output
+        .iter_spans()
+        .map(|(text, span)| {
+            let origin = match input_span {
+                Some(input_span) if !span.is_empty() && input_span.contains_span(&span) => {
+                    SpanOrigin::Input
+                }
+                _ => SpanOrigin::CallSite,
+            };
+            AuditedToken { text, span, origin }
+        })
+        .collect::<Vec<_>>();
+    let mut spans = tokens
+        .iter()
+        .filter(|token| token.origin == SpanOrigin::Input)
+        .map(|token| token.span)
+        // the input may contain tokens from other files
+        .filter(|span| crate::error::check_span(span, code).is_ok())
+        .collect::<Vec<_>>();
+    crate::dedup_spans(&mut spans);
+    let options = DebugSpanOptions {
+        deterministic: true,
+        ..Default::default()
+    };
+    let snippet = crate::internal::debug_spans_with_options(&spans, code, &options);
+    RespanAudit { tokens, snippet }
+}
+
+impl fmt::Display for RespanAudit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self
+            .tokens
+            .iter()
+            .map(|token| match token.origin {
+                SpanOrigin::Input => (token.text.as_str(), token.span.to_range(), "input"),
+                SpanOrigin::CallSite => (token.text.as_str(), String::from("-"), "call site"),
+            })
+            .collect::<Vec<_>>();
+        let text_width = rows
+            .iter()
+            .map(|(text, _, _)| text.chars().count())
+            .chain([5])
+            .max()
+            .unwrap_or(0);
+        let span_width = rows
+            .iter()
+            .map(|(_, span, _)| span.len())
+            .chain([4])
+            .max()
+            .unwrap_or(0);
+        writeln!(f, "{:text_width$}  {:span_width$}  origin", "token", "span")?;
+        for (text, span, origin) in &rows {
+            writeln!(f, "{:text_width$}  {:span_width$}  {}", text, span, origin)?;
+        }
+        writeln!(
+            f,
+            "{} of {} tokens point into the input",
+            self.input_tokens().count(),
+            self.tokens.len()
+        )?;
+        if !self.snippet.is_empty() {
+            write!(f, "\n{}", self.snippet)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use proc_macro2::{Ident, Punct, Spacing, TokenTree};
+
+    #[test]
+    fn test_audit_respanning() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        let input = code.parse::<TokenStream>().unwrap();
+        let spanned = |text: &str, index: usize| {
+            let span = input.clone().into_iter().nth(index).unwrap().span();
+            TokenTree::Ident(Ident::new(text, span))
+        };
+        let call_site =
+            |text: &str| TokenTree::Ident(Ident::new(text, proc_macro2::Span::call_site()));
+        let fields = input.clone().into_iter().nth(2).unwrap();
+        let output = TokenStream::from_iter([
+            call_site("impl"),
+            call_site("Trait"),
+            call_site("for"),
+            spanned("Foo", 1),
+            TokenTree::Punct(Punct::new(';', Spacing::Alone)),
+            fields,
+        ]);
+        let audit = audit_respanning(&input, &output, code);
+        insta::assert_snapshot!(audit.to_string(), @r###"
+        token  span        origin
+        impl   -           call site
+        Trait  -           call site
+        for    -           call site
+        Foo    1:7..1:10   input
+        ;      -           call site
+        {      1:11..1:12  input
+        a      2:4..2:5    input
+        :      2:5..2:6    input
+        i32    2:7..2:10   input
+        ,      2:10..2:11  input
+        }      3:0..3:1    input
+        7 of 11 tokens point into the input
+
+         --> 1:7..1:10, 1:11..1:12, 2:4..2:5, 2:5..2:6, 2:7..2:10, 2:10..2:11, 3:0..3:1
+          |
+        1 | struct Foo {
+          |        ^^^
+          |            ^
+        2 |     a: i32,
+          |     ^
+          |      ^
+          |        ^^^
+          |           ^
+        3 | }
+          | ^
+          |
+        "###);
+        assert!(audit_respanning(&TokenStream::new(), &output, code)
+            .input_tokens()
+            .next()
+            .is_none());
+    }
+}
@@ -0,0 +1,118 @@
+//! `cargo debug-span`: run `cargo check` and render the diagnostics originating in macro
+//! expansions, with the chain of macro invocations they come from.
+//!
+//! ```text
+//! cargo debug-span [--all] [<cargo check args>...]
+//! ```
+
+#[path = "../debug-span/rustc_json.rs"]
+mod rustc_json;
+
+use debug_span::DebugSpanOptions;
+use std::process::{Command, ExitCode, Stdio};
+
+const USAGE: &str = "\
+usage: cargo debug-span [--all] [<cargo check args>...]
+
+Run `cargo check` and render the diagnostics originating in macro expansions.
+
+options:
+    --all         render all diagnostics, not only the ones from macro expansions
+    -h, --help    print this help";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).peekable();
+    // cargo passes the subcommand name as the first argument.
+    if args.peek().map(String::as_str) == Some("debug-span") {
+        args.next();
+    }
+    let args = args.collect::<Vec<_>>();
+    match run(&args) {
+        Ok(output) => {
+            print!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Command line arguments.
+#[derive(Debug, Default, PartialEq)]
+struct Args<'a> {
+    help: bool,
+    all: bool,
+    cargo_args: Vec<&'a str>,
+}
+
+fn run(args: &[String]) -> Result<String, String> {
+    let args = parse_args(args);
+    if args.help {
+        return Ok(format!("{}\n", USAGE));
+    }
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut child = Command::new(cargo)
+        .arg("check")
+        .arg("--message-format=json")
+        .args(&args.cargo_args)
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("failed to run cargo check: {}", error))?;
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let output =
+        rustc_json::render_diagnostics(&DebugSpanOptions::default(), &mut stdout, |diagnostic| {
+            args.all || diagnostic.is_from_expansion()
+        })?;
+    child
+        .wait()
+        .map_err(|error| format!("failed to run cargo check: {}", error))?;
+    Ok(output)
+}
+
+fn parse_args(args: &[String]) -> Args<'_> {
+    let mut result = Args::default();
+    for arg in args {
+        match arg.as_str() {
+            "-h" | "--help" => result.help = true,
+            "--all" => result.all = true,
+            _ => result.cargo_args.push(arg),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        let args = ["--all", "-p", "foo"].map(String::from);
+        assert_eq!(
+            parse_args(&args),
+            Args {
+                help: false,
+                all: true,
+                cargo_args: vec!["-p", "foo"],
+            }
+        );
+    }
+
+    #[test]
+    fn test_is_from_expansion() {
+        let span = r#"{"file_name":"src/lib.rs","line_start":1,"line_end":1,"column_start":1,"column_end":2}"#;
+        let plain = format!(r#"{{"message":"m","level":"error","spans":[{}]}}"#, span);
+        let expanded = format!(
+            r#"{{"message":"m","level":"error","spans":[{{"file_name":"src/lib.rs","line_start":1,"line_end":1,"column_start":1,"column_end":2,"expansion":{{"macro_decl_name":"foo!","span":{}}}}}]}}"#,
+            span
+        );
+        assert!(!rustc_json::parse_diagnostic(&plain)
+            .unwrap()
+            .is_from_expansion());
+        assert!(rustc_json::parse_diagnostic(&expanded)
+            .unwrap()
+            .is_from_expansion());
+    }
+}
@@ -0,0 +1,113 @@
+//! The `ast` command, rendering the spans of the syn AST nodes of a file.
+
+use debug_span::internal::debug_spans_with_options;
+use debug_span::{DebugSpanOptions, LineColumnSpan, NamedSource, NodeKind, SpanCollector};
+use syn::visit::Visit;
+
+pub fn run(options: &DebugSpanOptions, kinds: &[&str], args: &[&str]) -> Result<String, String> {
+    let [path] = args else {
+        return Err("expected a file".to_string());
+    };
+    let kinds = kinds
+        .iter()
+        .map(|kind| {
+            NodeKind::from_name(kind).ok_or_else(|| {
+                let names = NodeKind::ALL.map(NodeKind::name);
+                format!(
+                    "unknown kind {}, expected one of {}",
+                    kind,
+                    names.join(", ")
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let code = std::fs::read_to_string(path)
+        .map_err(|error| format!("failed to read {}: {}", path, error))?;
+    render(options, &kinds, &NamedSource::new(*path, code))
+}
+
+/// Render the nodes of every requested kind in one frame per kind, all kinds when `kinds` is
+/// empty.
+fn render(
+    options: &DebugSpanOptions,
+    kinds: &[NodeKind],
+    code: &NamedSource,
+) -> Result<String, String> {
+    let file = syn::parse_file(code.inner()).map_err(|error| {
+        let span = LineColumnSpan::from(error.span());
+        format!(
+            "failed to parse: {}\n{}",
+            error,
+            debug_span::debug_span(span, code)
+        )
+    })?;
+    let mut collector = SpanCollector::new();
+    collector.visit_file(&file);
+    Ok(NodeKind::ALL
+        .into_iter()
+        .filter(|kind| kinds.is_empty() || kinds.contains(kind))
+        .map(|kind| {
+            let spans = collector.spans(kind).collect::<Vec<_>>();
+            format!(
+                "{}:\n{}",
+                kind,
+                debug_spans_with_options(&spans, code, options)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ast() {
+        let code = NamedSource::new(
+            "foo.rs",
+            "#[derive(Debug)]\nstruct Foo {\n    a: i32,\n    b: u8,\n}".to_string(),
+        );
+        let output = render(
+            &DebugSpanOptions::default(),
+            &[NodeKind::Field, NodeKind::Attr],
+            &code,
+        )
+        .unwrap();
+        insta::assert_snapshot!(output, @r###"
+        attr:
+         --> foo.rs:1:0..1:16
+          |
+        1 | #[derive(Debug)]
+          | ^^^^^^^^^^^^^^^^
+          |
+
+        field:
+         --> foo.rs:3:4..3:10, 4:4..4:9
+          |
+        3 |     a: i32,
+          |     ^^^^^^
+        4 |     b: u8,
+          |     ^^^^^
+          |
+        "###);
+        let output = render(&DebugSpanOptions::default(), &[NodeKind::Ident], &code).unwrap();
+        insta::assert_snapshot!(output, @r###"
+        ident:
+         --> foo.rs:1:2..1:8, 2:7..2:10, 3:4..3:5, 3:7..3:10, 4:4..4:5, 4:7..4:9
+          |
+        1 | #[derive(Debug)]
+          |   ^^^^^^
+        2 | struct Foo {
+          |        ^^^
+        3 |     a: i32,
+          |     ^
+          |        ^^^
+        4 |     b: u8,
+          |     ^
+          |        ^^
+          |
+        "###);
+        assert!(run(&DebugSpanOptions::default(), &["foo"], &["foo.rs"]).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+//! The `dump` command, rendering the spans recorded in a `SpanDump` file.
+
+use debug_span::{DebugSpanOptions, Span, SpanDump};
+
+pub fn run(options: &DebugSpanOptions, args: &[&str]) -> Result<String, String> {
+    let [path, names @ ..] = args else {
+        return Err("expected a dump file".to_string());
+    };
+    let records = SpanDump::new(path)
+        .read()
+        .map_err(|error| format!("failed to read {}: {}", path, error))?;
+    Ok(records
+        .iter()
+        .filter(|record| names.is_empty() || names.contains(&record.name.as_str()))
+        .map(|record| {
+            // a span recorded during macro expansion may belong to another file
+            let rendering = record
+                .try_debug_with_options(options)
+                .unwrap_or_else(|error| {
+                    let header = match &record.path {
+                        Some(path) => format!("{}:{}", path, record.span.to_range()),
+                        None => record.span.to_range(),
+                    };
+                    format!(" --> {} ({})\n", header, error)
+                });
+            format!("{}:\n{}", record.name, rendering)
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use debug_span::LineColumnSpan;
+
+    #[test]
+    fn test_dump() {
+        let path =
+            std::env::temp_dir().join(format!("debug-span-cli-dump-{}.jsonl", std::process::id()));
+        let dump = SpanDump::new(&path);
+        let code = "struct Foo {\n    a: i32,\n}";
+        dump.append("ident", LineColumnSpan::new(1, 7, 1, 10), code)
+            .unwrap();
+        dump.append("field", LineColumnSpan::new(2, 4, 2, 10), code)
+            .unwrap();
+        dump.append("stale", LineColumnSpan::new(3, 0, 3, 4), "struct Foo;")
+            .unwrap();
+        let path = path.to_str().unwrap();
+        let all = run(&DebugSpanOptions::default(), &[path]).unwrap();
+        let field = run(&DebugSpanOptions::default(), &[path, "field"]).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        insta::assert_snapshot!(all, @r###"
+        ident:
+         --> 1:7..1:10
+          |
+        1 | struct Foo {
+          |        ^^^
+          |
+
+        field:
+         --> 2:4..2:10
+          |
+        2 |     a: i32,
+          |     ^^^^^^
+          |
+
+        stale:
+         --> 3:0..3:4 (line 3 is out of range, the code has 1 lines)
+        "###);
+        insta::assert_snapshot!(field, @r###"
+        field:
+         --> 2:4..2:10
+          |
+        2 |     a: i32,
+          |     ^^^^^^
+          |
+        "###);
+        assert!(run(&DebugSpanOptions::default(), &[]).is_err());
+    }
+}
@@ -0,0 +1,305 @@
+//! The `explore` command, navigating the span tree of the syn AST of a file in the terminal.
+
+use debug_span::{
+    span_text, DebugSpanOptions, LineColumnSpan, NamedSource, NodeKind, SourceCode, Span,
+    SpanCollector, SpanExt, SpanWidget,
+};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListState, Paragraph};
+use ratatui::Frame;
+use syn::visit::Visit;
+
+pub fn run(options: &DebugSpanOptions, args: &[&str]) -> Result<String, String> {
+    let [path] = args else {
+        return Err("expected a file".to_string());
+    };
+    let code = std::fs::read_to_string(path)
+        .map_err(|error| format!("failed to read {}: {}", path, error))?;
+    let file = syn::parse_file(&code).map_err(|error| {
+        let span = LineColumnSpan::from(error.span());
+        format!(
+            "failed to parse: {}\n{}",
+            error,
+            debug_span::debug_span(span, &code)
+        )
+    })?;
+    let mut collector = SpanCollector::new();
+    collector.visit_file(&file);
+    let mut explorer = Explorer::new(build_tree(&collector));
+    let code = NamedSource::new(*path, code);
+    let mut terminal = ratatui::init();
+    let result = loop {
+        if let Err(error) = terminal.draw(|frame| draw(frame, &explorer, &code, options)) {
+            break Err(error);
+        }
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key.code,
+            Ok(_) => continue,
+            Err(error) => break Err(error),
+        };
+        let key = match key {
+            KeyCode::Up | KeyCode::Char('k') => Key::Up,
+            KeyCode::Down | KeyCode::Char('j') => Key::Down,
+            KeyCode::Left | KeyCode::Char('h') => Key::Left,
+            KeyCode::Right | KeyCode::Char('l') => Key::Right,
+            KeyCode::Enter | KeyCode::Char(' ') => Key::Toggle,
+            KeyCode::PageUp => Key::ScrollUp,
+            KeyCode::PageDown => Key::ScrollDown,
+            KeyCode::Esc | KeyCode::Char('q') => break Ok(()),
+            _ => continue,
+        };
+        explorer.handle(key);
+    };
+    ratatui::restore();
+    result
+        .map(|()| String::new())
+        .map_err(|error| format!("terminal error: {}", error))
+}
+
+/// A node of the span tree.
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    kind: NodeKind,
+    span: LineColumnSpan,
+    depth: usize,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// Nests the spans of the collector, in visit order, under the last span containing them.
+fn build_tree(collector: &SpanCollector) -> Vec<Node> {
+    let mut nodes = Vec::<Node>::new();
+    let mut stack = Vec::<usize>::new();
+    for (kind, span) in collector.iter().filter(|(_, span)| !span.is_empty()) {
+        while let Some(&top) = stack.last() {
+            if nodes[top].span.contains_span(&span) {
+                break;
+            }
+            stack.pop();
+        }
+        let index = nodes.len();
+        let parent = stack.last().copied();
+        if let Some(parent) = parent {
+            nodes[parent].children.push(index);
+        }
+        nodes.push(Node {
+            kind,
+            span,
+            depth: stack.len(),
+            parent,
+            children: Vec::new(),
+        });
+        stack.push(index);
+    }
+    nodes
+}
+
+/// A navigation key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    /// Collapse the selected node, or select its parent.
+    Left,
+    /// Expand the selected node, or select its first child.
+    Right,
+    Toggle,
+    ScrollUp,
+    ScrollDown,
+}
+
+/// The navigation state of the tree.
+struct Explorer {
+    nodes: Vec<Node>,
+    expanded: Vec<bool>,
+    selected: usize,
+    /// Rows of the snippet of the selected node scrolled out.
+    scroll: usize,
+}
+
+impl Explorer {
+    fn new(nodes: Vec<Node>) -> Self {
+        Self {
+            expanded: vec![false; nodes.len()],
+            nodes,
+            selected: 0,
+            scroll: 0,
+        }
+    }
+
+    /// The nodes whose ancestors are all expanded, in tree order.
+    fn visible(&self) -> Vec<usize> {
+        let mut visible = Vec::new();
+        let mut stack = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.parent.is_none())
+            .map(|(index, _)| index)
+            .rev()
+            .collect::<Vec<_>>();
+        while let Some(index) = stack.pop() {
+            visible.push(index);
+            if self.expanded[index] {
+                stack.extend(self.nodes[index].children.iter().rev());
+            }
+        }
+        visible
+    }
+
+    fn handle(&mut self, key: Key) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let visible = self.visible();
+        let position = visible
+            .iter()
+            .position(|&index| index == self.selected)
+            .unwrap_or(0);
+        let node = &self.nodes[self.selected];
+        let selected = match key {
+            Key::Up => visible[position.saturating_sub(1)],
+            Key::Down => visible[(position + 1).min(visible.len() - 1)],
+            Key::Left if self.expanded[self.selected] => {
+                self.expanded[self.selected] = false;
+                self.selected
+            }
+            Key::Left => node.parent.unwrap_or(self.selected),
+            Key::Right if node.children.is_empty() => self.selected,
+            Key::Right if self.expanded[self.selected] => node.children[0],
+            Key::Right => {
+                self.expanded[self.selected] = true;
+                self.selected
+            }
+            Key::Toggle => {
+                self.expanded[self.selected] = !self.expanded[self.selected];
+                self.selected
+            }
+            Key::ScrollUp => {
+                self.scroll = self.scroll.saturating_sub(1);
+                return;
+            }
+            Key::ScrollDown => {
+                self.scroll += 1;
+                return;
+            }
+        };
+        if selected != self.selected {
+            self.selected = selected;
+            self.scroll = 0;
+        }
+    }
+
+    /// The row of a node in the tree pane: its kind, range and the start of its text.
+    fn label(&self, index: usize, code: &str) -> String {
+        let node = &self.nodes[index];
+        let marker = match (node.children.is_empty(), self.expanded[index]) {
+            (true, _) => ' ',
+            (false, true) => '▾',
+            (false, false) => '▸',
+        };
+        let text = span_text(node.span, code);
+        let first_line = text.lines().next().unwrap_or_default();
+        let mut preview = first_line.chars().take(30).collect::<String>();
+        if preview.len() < text.len() {
+            preview.push('…');
+        }
+        format!(
+            "{}{} {} {} {}",
+            "  ".repeat(node.depth),
+            marker,
+            node.kind,
+            node.span.to_range(),
+            preview
+        )
+    }
+}
+
+fn draw(frame: &mut Frame, explorer: &Explorer, code: &NamedSource, options: &DebugSpanOptions) {
+    let [main, help] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+    let [tree, snippet] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(main);
+    let visible = explorer.visible();
+    let items = visible
+        .iter()
+        .map(|&index| Line::from(explorer.label(index, code.inner())))
+        .collect::<Vec<_>>();
+    let mut state = ListState::default()
+        .with_selected(visible.iter().position(|&index| index == explorer.selected));
+    let list = List::new(items)
+        .block(Block::bordered().title(code.path().unwrap_or_default()))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, tree, &mut state);
+    let block = Block::bordered();
+    if let Some(node) = explorer.nodes.get(explorer.selected) {
+        let block = block.title(format!("{} {}", node.kind, node.span.to_range()));
+        let inner = block.inner(snippet);
+        frame.render_widget(block, snippet);
+        let widget = SpanWidget::new(node.span, code, options);
+        let scroll = explorer.scroll.min(widget.row_count().saturating_sub(1));
+        frame.render_widget(widget.scroll(scroll), inner);
+    } else {
+        frame.render_widget(block, snippet);
+    }
+    let keys = "↑↓ move  ← collapse/parent  → expand/child  enter toggle  pgup/pgdn scroll  q quit";
+    frame.render_widget(Paragraph::new(keys), help);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explorer(code: &str) -> Explorer {
+        let file = syn::parse_file(code).unwrap();
+        let mut collector = SpanCollector::new();
+        collector.visit_file(&file);
+        Explorer::new(build_tree(&collector))
+    }
+
+    fn labels(explorer: &Explorer, code: &str) -> String {
+        explorer
+            .visible()
+            .into_iter()
+            .map(|index| {
+                let selected = if index == explorer.selected { ">" } else { " " };
+                format!("{}{}\n", selected, explorer.label(index, code))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_explorer() {
+        let code = "struct Foo {\n    a: i32,\n}\nfn bar() {}";
+        let mut explorer = explorer(code);
+        insta::assert_snapshot!(labels(&explorer, code), @r###"
+        >▸ item 1:0..3:1 struct Foo {…
+         ▸ item 4:0..4:11 fn bar() {}
+        "###);
+        explorer.handle(Key::Right);
+        explorer.handle(Key::Right);
+        explorer.handle(Key::Down);
+        explorer.handle(Key::Right);
+        insta::assert_snapshot!(labels(&explorer, code), @r###"
+         ▾ item 1:0..3:1 struct Foo {…
+             ident 1:7..1:10 Foo
+        >  ▾ field 2:4..2:10 a: i32
+               ident 2:4..2:5 a
+             ▸ type 2:7..2:10 i32
+         ▸ item 4:0..4:11 fn bar() {}
+        "###);
+        explorer.handle(Key::Left);
+        explorer.handle(Key::Left);
+        explorer.handle(Key::Left);
+        explorer.handle(Key::Down);
+        explorer.handle(Key::Down);
+        explorer.handle(Key::Down);
+        insta::assert_snapshot!(labels(&explorer, code), @r###"
+         ▸ item 1:0..3:1 struct Foo {…
+        >▸ item 4:0..4:11 fn bar() {}
+        "###);
+    }
+}
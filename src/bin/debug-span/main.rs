@@ -0,0 +1,352 @@
+//! Render a span of a file from the command line.
+//!
+//! ```text
+//! debug-span [--config <file>] [--ascii] [--context <lines>] [--editor <editor>] <file> <start_line:start_column..end_line:end_column>...
+//! cat foo.rs | debug-span - 3:4..3:7
+//! cargo build --message-format=json | debug-span rustc-json
+//! debug-span dump target/debug-span-dump.jsonl [<name>...]
+//! debug-span ast --kind field,ident,attr src/lib.rs
+//! debug-span explore src/lib.rs
+//! ```
+
+mod ast;
+mod dump;
+mod explore;
+mod rustc_json;
+
+use debug_span::{
+    try_debug_span_with_options, Charset, ColorChoice, DebugSpanOptions, KakouneRenderer,
+    LineColumnSpan, NamedSource, NeovimRenderer, ParseSpanError, Renderer, SourceCode,
+};
+use std::io::Read;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: debug-span [options] <file> <range>...
+       debug-span [options] rustc-json
+       debug-span [options] dump <file> [<name>...]
+       debug-span [options] ast [--kind <kinds>] <file>
+       debug-span [options] explore <file>
+
+Render every span <range> of <file>. <range> is formatted as
+`start_line:start_column..end_line:end_column`, e.g. `3:4..5:1`.
+When <file> is `-`, the source is read from stdin.
+
+commands:
+    rustc-json           re-render the diagnostics of `cargo build --message-format=json`
+                         read from stdin
+    dump                 render the spans recorded in a `SpanDump` file, optionally only
+                         the ones with the given names
+    ast                  parse a Rust file with syn and render the nodes of every kind in
+                         one frame per kind
+    explore              parse a Rust file with syn and navigate the tree of its node spans
+                         with the arrow keys, showing the snippet of the selected node
+
+options:
+    --config <file>      load the options from a TOML file, other options override it
+    --ascii              draw with ASCII characters only
+    --color <when>       color the output: `auto` when it is a terminal and NO_COLOR is not
+                         set, `always` or `never`
+    --prose              describe the spans in plain sentences instead of drawing them
+    --context <lines>    show <lines> lines before and after the span
+    --editor <editor>    print commands highlighting the spans in a running editor instead,
+                         `nvim` for `nvim --remote-send` or `kak` for `kak -p`
+    --kind <kinds>       comma separated node kinds rendered by `ast`, among
+                         item, attr, generics, field, type, expr, lit and ident
+    -h, --help           print this help";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match run(&args, &mut std::io::stdin()) {
+        Ok(output) => {
+            print!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Command line arguments of the default command.
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    options: DebugSpanOptions,
+    editor: Option<Editor>,
+    path: String,
+    spans: Vec<LineColumnSpan>,
+}
+
+/// Options shared by all commands, and the remaining positional arguments.
+#[derive(Debug, Default, PartialEq)]
+struct CommonArgs<'a> {
+    help: bool,
+    options: DebugSpanOptions,
+    editor: Option<Editor>,
+    kinds: Vec<&'a str>,
+    positional: Vec<&'a str>,
+}
+
+fn run(args: &[String], stdin: &mut impl Read) -> Result<String, String> {
+    let common = parse_common_args(args)?;
+    if common.help {
+        return Ok(format!("{}\n", USAGE));
+    }
+    if common.positional.first() == Some(&"rustc-json") {
+        return rustc_json::run(&common.options, stdin);
+    }
+    if common.positional.first() == Some(&"dump") {
+        return dump::run(&common.options, &common.positional[1..]);
+    }
+    if common.positional.first() == Some(&"ast") {
+        return ast::run(&common.options, &common.kinds, &common.positional[1..]);
+    }
+    if common.positional.first() == Some(&"explore") {
+        return explore::run(&common.options, &common.positional[1..]);
+    }
+    let args = parse_args(common)?;
+    if args.path == "-" {
+        let mut code = String::new();
+        stdin
+            .read_to_string(&mut code)
+            .map_err(|error| format!("failed to read stdin: {}", error))?;
+        render_all(&args, &code)
+    } else {
+        let code = std::fs::read_to_string(&args.path)
+            .map_err(|error| format!("failed to read {}: {}", args.path, error))?;
+        render_all(&args, &NamedSource::new(args.path.as_str(), code))
+    }
+}
+
+/// The editor targeted by `--editor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Editor {
+    Neovim,
+    Kakoune,
+}
+
+/// Render every span separately, or highlight them all in the editor. Fails if a span doesn't
+/// point into the code.
+fn render_all(args: &Args, code: &impl SourceCode) -> Result<String, String> {
+    let outputs = args
+        .spans
+        .iter()
+        .map(|span| {
+            try_debug_span_with_options(span, code, &args.options).map_err(|error| {
+                format!(
+                    "invalid range {}:{}..{}:{}: {}",
+                    span.start_line, span.start_column, span.end_line, span.end_column, error
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(match args.editor {
+        Some(Editor::Neovim) => NeovimRenderer.render(&args.spans, code, &args.options),
+        Some(Editor::Kakoune) => {
+            KakouneRenderer::default().render(&args.spans, code, &args.options)
+        }
+        None => outputs.join("\n"),
+    })
+}
+
+fn parse_common_args(args: &[String]) -> Result<CommonArgs<'_>, String> {
+    let mut result = CommonArgs::default();
+    if let Some(index) = args.iter().position(|arg| arg == "--config") {
+        let path = args.get(index + 1).ok_or("missing value for --config")?;
+        result.options = DebugSpanOptions::from_toml(path).map_err(|error| error.to_string())?;
+    }
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => result.help = true,
+            "--config" => {
+                args.next();
+            }
+            "--ascii" => result.options.charset = Charset::Ascii,
+            "--prose" => result.options.prose = true,
+            "--context" => {
+                let value = args.next().ok_or("missing value for --context")?;
+                result.options.context = value
+                    .parse()
+                    .map_err(|_| format!("invalid value for --context: {}", value))?;
+            }
+            "--color" => {
+                let value = args.next().ok_or("missing value for --color")?;
+                let choice = match value.as_str() {
+                    "auto" => ColorChoice::Auto,
+                    "always" => ColorChoice::Always,
+                    "never" => ColorChoice::Never,
+                    _ => return Err(format!("invalid value for --color: {}", value)),
+                };
+                result.options = choice.options(&result.options, &std::io::stdout());
+            }
+            "--editor" => {
+                let value = args.next().ok_or("missing value for --editor")?;
+                result.editor = match value.as_str() {
+                    "nvim" => Some(Editor::Neovim),
+                    "kak" => Some(Editor::Kakoune),
+                    _ => return Err(format!("invalid value for --editor: {}", value)),
+                };
+            }
+            "--kind" => {
+                let value = args.next().ok_or("missing value for --kind")?;
+                result
+                    .kinds
+                    .extend(value.split(',').filter(|kind| !kind.is_empty()));
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("unknown option {}\n\n{}", flag, USAGE))
+            }
+            _ => result.positional.push(arg.as_str()),
+        }
+    }
+    Ok(result)
+}
+
+fn parse_args(common: CommonArgs) -> Result<Args, String> {
+    let [path, ranges @ ..] = &common.positional[..] else {
+        return Err(format!("expected a file and a range\n\n{}", USAGE));
+    };
+    if ranges.is_empty() {
+        return Err(format!("expected a file and a range\n\n{}", USAGE));
+    }
+    let spans = ranges
+        .iter()
+        .map(|range| {
+            let span: LineColumnSpan = range
+                .parse()
+                .map_err(|error: ParseSpanError| error.to_string())?;
+            if (span.start_line, span.start_column) > (span.end_line, span.end_column) {
+                return Err(format!(
+                    "invalid range {}: the start is after the end",
+                    range
+                ));
+            }
+            Ok(span)
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(Args {
+        options: common.options,
+        editor: common.editor,
+        path: path.to_string(),
+        spans,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use debug_span::Theme;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        parse_args(parse_common_args(&self::args(args))?)
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = parse(&["--ascii", "--context", "2", "foo.rs", "3:4..5:1"]).unwrap();
+        assert_eq!(parsed.path, "foo.rs");
+        assert_eq!(parsed.spans, [LineColumnSpan::new(3, 4, 5, 1)]);
+        assert_eq!(parsed.options.charset, Charset::Ascii);
+        assert_eq!(parsed.options.context, 2);
+
+        assert!(parse(&["foo.rs"]).is_err());
+        assert!(parse(&["foo.rs", "3:4"]).is_err());
+        assert!(parse(&["--context", "x", "foo.rs", "3:4..5:1"]).is_err());
+        let error = parse(&["foo.rs", "1:0..1:1", "1:7..1:3"]).unwrap_err();
+        assert_eq!(error, "invalid range 1:7..1:3: the start is after the end");
+        let error = parse(&["-", "2:0..1:3"]).unwrap_err();
+        assert_eq!(error, "invalid range 2:0..1:3: the start is after the end");
+        assert!(parse(&["--color", "foo.rs", "3:4..5:1"]).is_err());
+        let parsed = parse(&["--color", "always", "foo.rs", "3:4..5:1"]).unwrap();
+        assert_eq!(parsed.options.theme, Some(Theme::Default));
+        let parsed = parse(&["--color", "never", "foo.rs", "3:4..5:1"]).unwrap();
+        assert_eq!(parsed.options.theme, None);
+        assert!(parse_common_args(&args(&["--help"])).unwrap().help);
+        let common = args(&["ast", "--kind", "field,ident", "--kind", "attr", "foo.rs"]);
+        let common = parse_common_args(&common).unwrap();
+        assert_eq!(common.kinds, ["field", "ident", "attr"]);
+        assert_eq!(common.positional, ["ast", "foo.rs"]);
+    }
+
+    #[test]
+    fn test_run() {
+        let path = std::env::temp_dir().join(format!("debug-span-cli-{}.rs", std::process::id()));
+        std::fs::write(&path, "struct Foo {\n    a: i32,\n}").unwrap();
+        let path = path.to_str().unwrap();
+        let output = run(
+            &args(&["--context", "1", path, "2:4..2:10"]),
+            &mut std::io::empty(),
+        )
+        .unwrap();
+        let output = output.replace(path, "foo.rs");
+        std::fs::remove_file(path).unwrap();
+        insta::assert_snapshot!(output, @r###"
+         --> foo.rs:2:4..2:10
+          |
+        1 | struct Foo {
+        2 |     a: i32,
+          |     ^^^^^^
+        3 | }
+          |
+        "###);
+        let mut stdin = "struct Foo;".as_bytes();
+        let error = run(&args(&["-", "5:0..5:1"]), &mut stdin).unwrap_err();
+        insta::assert_snapshot!(error, @"invalid range 5:0..5:1: line 5 is out of range, the code has 1 lines");
+        let error = run(&args(&["missing.rs", "1:0..1:1"]), &mut std::io::empty()).unwrap_err();
+        assert!(
+            error.starts_with("failed to read missing.rs: "),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_config() {
+        let path = std::env::temp_dir().join(format!("debug-span-cli-{}.toml", std::process::id()));
+        std::fs::write(&path, "context = 3\ncharset = \"ascii\"\n").unwrap();
+        let path = path.to_str().unwrap();
+        let parsed = parse(&["--context", "1", "--config", path, "foo.rs", "3:4..5:1"]);
+        std::fs::remove_file(path).unwrap();
+        let parsed = parsed.unwrap();
+        assert_eq!(parsed.options.charset, Charset::Ascii);
+        assert_eq!(parsed.options.context, 1);
+        assert_eq!(parsed.path, "foo.rs");
+
+        let error = parse(&["--config", "missing.toml", "foo.rs", "3:4..5:1"]).unwrap_err();
+        assert!(
+            error.starts_with("failed to read missing.toml: "),
+            "{}",
+            error
+        );
+        assert!(parse(&["foo.rs", "3:4..5:1", "--config"]).is_err());
+    }
+
+    #[test]
+    fn test_run_stdin() {
+        let mut stdin = "struct Foo {\n    a: i32,\n}".as_bytes();
+        let output = run(&args(&["-", "1:7..1:10", "2:4..2:5"]), &mut stdin).unwrap();
+        insta::assert_snapshot!(output, @r###"
+         --> 1:7..1:10
+          |
+        1 | struct Foo {
+          |        ^^^
+          |
+
+         --> 2:4..2:5
+          |
+        2 |     a: i32,
+          |     ^
+          |
+        "###);
+        let mut stdin = "struct Foo {\n    a: i32,\n}".as_bytes();
+        let args = args(&["--editor", "kak", "-", "1:7..1:10", "2:4..2:5"]);
+        insta::assert_snapshot!(run(&args, &mut stdin).unwrap(), @"select 1.8,1.10 2.5,2.5");
+        assert!(parse(&["--editor", "emacs", "-", "1:7..1:10"]).is_err());
+    }
+}
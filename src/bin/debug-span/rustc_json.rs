@@ -0,0 +1,220 @@
+//! The `rustc-json` command, re-rendering rustc diagnostics.
+//!
+//! It is shared with `cargo debug-span`.
+
+use debug_span::{
+    try_debug_span_with_options, DebugSpanOptions, LineColumnSpan, NamedSource, Position, Span,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+
+/// A rustc diagnostic, as emitted with `--error-format=json`.
+#[derive(Debug, Deserialize)]
+pub struct Diagnostic {
+    message: String,
+    level: String,
+    spans: Vec<DiagnosticSpan>,
+}
+
+impl Diagnostic {
+    /// Whether one of the spans points into a macro expansion.
+    #[allow(dead_code)] // only used by `cargo debug-span`
+    pub fn is_from_expansion(&self) -> bool {
+        self.spans.iter().any(|span| span.expansion.is_some())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DiagnosticSpan {
+    file_name: String,
+    line_start: usize,
+    line_end: usize,
+    /// 1-indexed, counted in chars.
+    column_start: usize,
+    column_end: usize,
+    label: Option<String>,
+    expansion: Option<Box<Expansion>>,
+}
+
+/// The macro invocation a span was produced by.
+#[derive(Debug, Deserialize)]
+struct Expansion {
+    span: DiagnosticSpan,
+    macro_decl_name: String,
+}
+
+/// Read `cargo build --message-format=json` or `rustc --error-format=json` output and render the
+/// spans of every diagnostic.
+///
+/// Lines that are not diagnostics, like build script or artifact messages, are skipped.
+#[allow(dead_code)] // only used by `debug-span`
+pub fn run(options: &DebugSpanOptions, stdin: &mut impl Read) -> Result<String, String> {
+    render_diagnostics(options, stdin, |_| true)
+}
+
+/// Render the diagnostics read from `input` which match `filter`.
+pub fn render_diagnostics(
+    options: &DebugSpanOptions,
+    input: &mut impl Read,
+    filter: impl Fn(&Diagnostic) -> bool,
+) -> Result<String, String> {
+    let mut files = Files::new();
+    let mut renderings = Vec::new();
+    for line in BufReader::new(input).lines() {
+        let line = line.map_err(|error| format!("failed to read the diagnostics: {}", error))?;
+        match parse_diagnostic(&line) {
+            Some(diagnostic) if filter(&diagnostic) => {
+                renderings.push(render_diagnostic(&diagnostic, options, &mut files));
+            }
+            _ => {}
+        }
+    }
+    Ok(renderings.join("\n"))
+}
+
+/// Sources read so far, or `None` for files that couldn't be read.
+type Files = HashMap<String, Option<NamedSource>>;
+
+pub fn parse_diagnostic(line: &str) -> Option<Diagnostic> {
+    let mut value = serde_json::from_str::<serde_json::Value>(line).ok()?;
+    if let Some(reason) = value.get("reason") {
+        if reason != "compiler-message" {
+            return None;
+        }
+        value = value.get_mut("message")?.take();
+    }
+    serde_json::from_value(value).ok()
+}
+
+fn render_diagnostic(
+    diagnostic: &Diagnostic,
+    options: &DebugSpanOptions,
+    files: &mut Files,
+) -> String {
+    let level = options.messages.level(&diagnostic.level);
+    let mut output = format!("{}: {}\n", level, diagnostic.message);
+    for span in &diagnostic.spans {
+        let options = DebugSpanOptions {
+            label: span.label.clone().or(options.label.clone()),
+            ..options.clone()
+        };
+        output.push_str(&render_span(span, &options, files));
+        let mut expansion = span.expansion.as_deref();
+        while let Some(current) = expansion {
+            let options = DebugSpanOptions {
+                label: None,
+                ..options.clone()
+            };
+            output.push_str(&format!("in expansion of `{}`:\n", current.macro_decl_name));
+            output.push_str(&render_span(&current.span, &options, files));
+            expansion = current.span.expansion.as_deref();
+        }
+    }
+    output
+}
+
+fn render_span(span: &DiagnosticSpan, options: &DebugSpanOptions, files: &mut Files) -> String {
+    let source = files.entry(span.file_name.clone()).or_insert_with(|| {
+        let code = std::fs::read_to_string(&span.file_name).ok()?;
+        Some(NamedSource::new(span.file_name.as_str(), code))
+    });
+    let start = Position::from_one_based(span.line_start, span.column_start);
+    let end = Position::from_one_based(span.line_end, span.column_end);
+    let (Some(start), Some(end)) = (start, end) else {
+        return format!(
+            " --> {}:{}:{} (invalid position)\n",
+            span.file_name, span.line_start, span.column_start
+        );
+    };
+    let line_column_span = LineColumnSpan::from_positions(start, end);
+    let range = line_column_span.to_range();
+    match source {
+        // the file may have been edited since the diagnostic was emitted
+        Some(source) => try_debug_span_with_options(line_column_span, source, options)
+            .unwrap_or_else(|error| format!(" --> {}:{} ({})\n", span.file_name, range, error)),
+        None => format!(" --> {}:{} (source not found)\n", span.file_name, range),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rustc_json() {
+        let path = std::env::temp_dir().join(format!("debug-span-rustc-{}.rs", std::process::id()));
+        std::fs::write(&path, "fn main() {\n    let x = 1;\n}\n").unwrap();
+        let file_name = path.to_str().unwrap();
+        let expansion = serde_json::json!({
+            "macro_decl_name": "foo!",
+            "span": {
+                "file_name": file_name,
+                "line_start": 2, "line_end": 2, "column_start": 13, "column_end": 14,
+            },
+        });
+        let diagnostic = serde_json::json!({
+            "$message_type": "diagnostic",
+            "message": "unused variable: `x`",
+            "level": "warning",
+            "spans": [{
+                "file_name": file_name,
+                "line_start": 2,
+                "line_end": 2,
+                "column_start": 9,
+                "column_end": 10,
+                "is_primary": true,
+                "label": "help: if this is intentional, prefix it with an underscore: `_x`",
+                "expansion": expansion,
+            }],
+        });
+        let missing = serde_json::json!({
+            "message": "unresolved import", "level": "error",
+            "spans": [{
+                "file_name": "missing.rs",
+                "line_start": 1, "line_end": 1, "column_start": 5, "column_end": 8,
+            }],
+        });
+        let stale = serde_json::json!({
+            "message": "stale span", "level": "error",
+            "spans": [{
+                "file_name": file_name,
+                "line_start": 5, "line_end": 5, "column_start": 3, "column_end": 9,
+            }],
+        });
+        let input = [
+            r#"{"reason":"compiler-artifact","package_id":"foo"}"#.to_string(),
+            serde_json::json!({ "reason": "compiler-message", "message": diagnostic }).to_string(),
+            "not json".to_string(),
+            missing.to_string(),
+            stale.to_string(),
+            r#"{"message":"1 warning emitted","level":"warning","spans":[]}"#.to_string(),
+        ]
+        .join("\n");
+        let output = run(&DebugSpanOptions::default(), &mut input.as_bytes()).unwrap();
+        let output = output.replace(file_name, "src/main.rs");
+        std::fs::remove_file(&path).unwrap();
+        insta::assert_snapshot!(output, @r###"
+        warning: unused variable: `x`
+         --> src/main.rs:2:8..2:9
+          |
+        2 |     let x = 1;
+          |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+          |
+        in expansion of `foo!`:
+         --> src/main.rs:2:12..2:13
+          |
+        2 |     let x = 1;
+          |             ^
+          |
+
+        error: unresolved import
+         --> missing.rs:1:4..1:7 (source not found)
+
+        error: stale span
+         --> src/main.rs:5:2..5:8 (line 5 is out of range, the code has 3 lines)
+
+        warning: 1 warning emitted
+        "###);
+    }
+}
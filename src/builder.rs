@@ -0,0 +1,148 @@
+use crate::{Charset, DebugSpanOptions, SourceCode, Span};
+use alloc::string::String;
+
+/// Start building a rendering of `span`.
+///
+/// It is a chainable entry point combining the source code and the [`DebugSpanOptions`].
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug, LineColumnSpan};
+///
+/// let code = "struct Foo;";
+/// let output = debug(LineColumnSpan::new(1, 7, 1, 10))
+///     .code(code)
+///     .label("expected ident")
+///     .context(2)
+///     .ascii()
+///     .render();
+/// assert_eq!(
+///     output,
+///     " --> 1:7..1:10\n  |\n1 | struct Foo;\n  |        ^^^ expected ident\n  |\n"
+/// );
+/// ```
+pub fn debug<S: Span>(span: S) -> DebugSpanBuilder<'static, S> {
+    DebugSpanBuilder {
+        span,
+        code: None,
+        options: DebugSpanOptions::default(),
+    }
+}
+
+/// A builder for rendering a span, created by [`debug`].
+#[derive(Debug)]
+pub struct DebugSpanBuilder<'a, S, C: ?Sized = str> {
+    span: S,
+    code: Option<&'a C>,
+    options: DebugSpanOptions,
+}
+
+impl<S: Clone, C: ?Sized> Clone for DebugSpanBuilder<'_, S, C> {
+    fn clone(&self) -> Self {
+        Self {
+            span: self.span.clone(),
+            code: self.code,
+            options: self.options.clone(),
+        }
+    }
+}
+
+impl<'a, S: Span, C: SourceCode + ?Sized> DebugSpanBuilder<'a, S, C> {
+    /// Set the source code the span points to.
+    pub fn code<'b, D: SourceCode + ?Sized>(self, code: &'b D) -> DebugSpanBuilder<'b, S, D> {
+        DebugSpanBuilder {
+            span: self.span,
+            code: Some(code),
+            options: self.options,
+        }
+    }
+
+    /// Replace all options at once.
+    pub fn options(mut self, options: DebugSpanOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// See [`DebugSpanOptions::label`].
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.options.label = Some(label.into());
+        self
+    }
+
+    /// See [`DebugSpanOptions::context`].
+    pub fn context(mut self, context: usize) -> Self {
+        self.options.context = context;
+        self
+    }
+
+    /// See [`DebugSpanOptions::charset`].
+    pub fn charset(mut self, charset: Charset) -> Self {
+        self.options.charset = charset;
+        self
+    }
+
+    /// Shorthand for `.charset(Charset::Ascii)`.
+    pub fn ascii(self) -> Self {
+        self.charset(Charset::Ascii)
+    }
+
+    /// See [`DebugSpanOptions::deterministic`].
+    pub fn deterministic(mut self) -> Self {
+        self.options.deterministic = true;
+        self
+    }
+
+    /// Render the span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source code is not set with [`code`](Self::code).
+    #[track_caller]
+    pub fn render(&self) -> String {
+        let code = self
+            .code
+            .expect("source code is not set, call `.code(...)` before `.render()`");
+        crate::internal::debug_span_with_options(&self.span, code, &self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+    use unindent::Unindent;
+
+    #[test]
+    fn test_builder() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+                b: i32,
+            }
+        "###
+        .unindent();
+        let output = debug(LineColumnSpan::new(1, 11, 2, 11))
+            .code(&input)
+            .label("fields")
+            .context(1)
+            .ascii()
+            .render();
+        insta::assert_snapshot!(output, @r###"
+         --> 1:11..2:11
+          |
+          |            +----+
+        1 | struct Foo {    |
+        2 |     a: i32,     |
+          |           +-----+ fields
+        3 |     b: i32,
+          |
+        "###);
+    }
+
+    #[test]
+    #[should_panic(expected = "source code is not set")]
+    fn test_builder_without_code() {
+        debug(LineColumnSpan::new(1, 7, 1, 10)).render();
+    }
+}
@@ -0,0 +1,184 @@
+use crate::text::get_source_span_text;
+use crate::{LineColumnSpan, SourceCode, Span};
+use alloc::format;
+use alloc::string::String;
+
+/// A fluent test harness for asserting properties of spans over a piece of source code.
+///
+/// Every failing assertion panics with a message that includes the rendered snippet of the span,
+/// so the location is visible right in the test output.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::SpanChecker;
+///
+/// let input = "struct Foo;";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+///
+/// let checker = SpanChecker::new(input);
+/// checker
+///     .check(derive_input.ident.span())
+///     .covers("Foo")
+///     .starts_at(1, 7)
+///     .ends_at(1, 10)
+///     .renders(" --> 1:7..1:10\n  |\n1 | struct Foo;\n  |        ^^^\n  |\n");
+/// ```
+#[derive(Debug)]
+pub struct SpanChecker<'a, C: ?Sized = str> {
+    code: &'a C,
+}
+
+impl<C: ?Sized> Clone for SpanChecker<'_, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: ?Sized> Copy for SpanChecker<'_, C> {}
+
+impl<'a, C: SourceCode + ?Sized> SpanChecker<'a, C> {
+    pub fn new(code: &'a C) -> Self {
+        Self { code }
+    }
+
+    /// Start a chain of assertions about `span`.
+    pub fn check(&self, span: impl Span) -> SpanCheck<'a, C> {
+        SpanCheck {
+            code: self.code,
+            span: LineColumnSpan::from_span(&span),
+        }
+    }
+}
+
+/// A chain of assertions about a single span, created by [`SpanChecker::check`].
+#[derive(Debug)]
+pub struct SpanCheck<'a, C: ?Sized = str> {
+    code: &'a C,
+    span: LineColumnSpan,
+}
+
+impl<C: ?Sized> Clone for SpanCheck<'_, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: ?Sized> Copy for SpanCheck<'_, C> {}
+
+impl<C: SourceCode + ?Sized> SpanCheck<'_, C> {
+    /// Asserts that the span covers exactly `text`.
+    #[track_caller]
+    pub fn covers(self, text: &str) -> Self {
+        let actual = get_source_span_text(&self.span, self.code);
+        if actual.as_deref() != Some(text) {
+            self.fail(format!(
+                "expected span to cover {:?}, but it covers {:?}",
+                text, actual
+            ));
+        }
+        self
+    }
+
+    /// Asserts that the span starts at `line:column`.
+    #[track_caller]
+    pub fn starts_at(self, line: usize, column: usize) -> Self {
+        if (self.span.start_line, self.span.start_column) != (line, column) {
+            self.fail(format!(
+                "expected span to start at {}:{}, but it starts at {}:{}",
+                line, column, self.span.start_line, self.span.start_column
+            ));
+        }
+        self
+    }
+
+    /// Asserts that the span ends at `line:column`.
+    #[track_caller]
+    pub fn ends_at(self, line: usize, column: usize) -> Self {
+        if (self.span.end_line, self.span.end_column) != (line, column) {
+            self.fail(format!(
+                "expected span to end at {}:{}, but it ends at {}:{}",
+                line, column, self.span.end_line, self.span.end_column
+            ));
+        }
+        self
+    }
+
+    /// Asserts that the span renders exactly as `expected`.
+    ///
+    /// For inline snapshots, use [`render`](Self::render) with `insta::assert_snapshot!` instead.
+    #[track_caller]
+    pub fn renders(self, expected: &str) -> Self {
+        let actual = self.render();
+        if actual != expected {
+            self.fail(format!(
+                "expected span to render as:\n{}\nbut it renders as:",
+                expected
+            ));
+        }
+        self
+    }
+
+    /// Returns the rendered snippet of the span.
+    pub fn render(&self) -> String {
+        crate::debug_span(self.span, self.code)
+    }
+
+    #[track_caller]
+    fn fail(&self, message: String) -> ! {
+        panic!("{}\n{}", message, self.render());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unindent::Unindent;
+
+    #[test]
+    fn test_span_checker() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+            }
+        "###
+        .unindent();
+        let derive_input: syn::DeriveInput = syn::parse_str(&input).unwrap();
+        let checker = SpanChecker::new(&input);
+        checker
+            .check(derive_input.ident.span())
+            .covers("Foo")
+            .starts_at(1, 7)
+            .ends_at(1, 10);
+        let check = checker
+            .check(LineColumnSpan::new(2, 4, 2, 10))
+            .covers("a: i32");
+        insta::assert_snapshot!(check.render(), @r###"
+         --> 2:4..2:10
+          |
+        2 |     a: i32,
+          |     ^^^^^^
+          |
+        "###);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "expected span to cover \"Bar\", but it covers Some(\"Foo\")\n --> 1:7..1:10"
+    )]
+    fn test_span_checker_covers_failure() {
+        let input = "struct Foo;";
+        SpanChecker::new(input)
+            .check(LineColumnSpan::new(1, 7, 1, 10))
+            .covers("Bar");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected span to start at 1:6, but it starts at 1:7")]
+    fn test_span_checker_starts_at_failure() {
+        let input = "struct Foo;";
+        SpanChecker::new(input)
+            .check(LineColumnSpan::new(1, 7, 1, 10))
+            .starts_at(1, 6);
+    }
+}
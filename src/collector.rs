@@ -0,0 +1,252 @@
+use crate::{DebugSpanOptions, LineColumnSpan, SourceCode};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+/// The node types recorded by [`SpanCollector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NodeKind {
+    Item,
+    Attr,
+    Generics,
+    Field,
+    Type,
+    Expr,
+    Lit,
+    Ident,
+}
+
+impl NodeKind {
+    /// Every kind, in report order.
+    pub const ALL: [NodeKind; 8] = [
+        NodeKind::Item,
+        NodeKind::Attr,
+        NodeKind::Generics,
+        NodeKind::Field,
+        NodeKind::Type,
+        NodeKind::Expr,
+        NodeKind::Lit,
+        NodeKind::Ident,
+    ];
+
+    /// The lowercase name of the kind, e.g. `attr`.
+    pub fn name(self) -> &'static str {
+        match self {
+            NodeKind::Item => "item",
+            NodeKind::Attr => "attr",
+            NodeKind::Generics => "generics",
+            NodeKind::Field => "field",
+            NodeKind::Type => "type",
+            NodeKind::Expr => "expr",
+            NodeKind::Lit => "lit",
+            NodeKind::Ident => "ident",
+        }
+    }
+
+    /// Returns the kind named `name`, see [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+impl fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A [`syn::visit::Visit`] implementation recording the span of every visited node, grouped by
+/// [`NodeKind`].
+///
+/// Generics are only recorded when they declare parameters, empty generics have no location.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{LineColumnSpan, NodeKind, SpanCollector};
+/// use syn::visit::Visit;
+///
+/// let code = "struct Foo<T> {\n    a: T,\n}";
+/// let file = syn::parse_file(code).unwrap();
+/// let mut collector = SpanCollector::new();
+/// collector.visit_file(&file);
+/// assert_eq!(
+///     collector.spans(NodeKind::Field).collect::<Vec<_>>(),
+///     [LineColumnSpan::new(2, 4, 2, 8)]
+/// );
+/// let report = collector.render_report(code);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpanCollector {
+    spans: Vec<(NodeKind, LineColumnSpan)>,
+}
+
+impl SpanCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the span of `node` as a `kind`.
+    pub fn push(&mut self, kind: NodeKind, node: &impl Spanned) {
+        self.spans.push((kind, LineColumnSpan::from(node.span())));
+    }
+
+    /// The spans of the nodes of `kind`, in visit order.
+    pub fn spans(&self, kind: NodeKind) -> impl Iterator<Item = LineColumnSpan> + '_ {
+        self.spans
+            .iter()
+            .filter(move |(node_kind, _)| *node_kind == kind)
+            .map(|(_, span)| *span)
+    }
+
+    /// Every recorded span with its kind, in visit order.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeKind, LineColumnSpan)> + '_ {
+        self.spans.iter().copied()
+    }
+
+    /// Render the spans of every kind in one frame per kind, skipping the kinds without spans.
+    ///
+    /// The frames are rendered with [`DebugSpanOptions::deterministic`], so the report can be
+    /// snapshotted.
+    pub fn render_report(&self, code: &(impl SourceCode + ?Sized)) -> String {
+        let options = DebugSpanOptions {
+            deterministic: true,
+            ..Default::default()
+        };
+        NodeKind::ALL
+            .into_iter()
+            .filter_map(|kind| {
+                let spans = self.spans(kind).collect::<Vec<_>>();
+                (!spans.is_empty()).then(|| {
+                    format!(
+                        "{} ({}):\n{}",
+                        kind,
+                        spans.len(),
+                        crate::internal::debug_spans_with_options(&spans, code, &options)
+                    )
+                })
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<'ast> Visit<'ast> for SpanCollector {
+    fn visit_item(&mut self, node: &'ast syn::Item) {
+        self.push(NodeKind::Item, node);
+        visit::visit_item(self, node);
+    }
+
+    fn visit_attribute(&mut self, node: &'ast syn::Attribute) {
+        self.push(NodeKind::Attr, node);
+        visit::visit_attribute(self, node);
+    }
+
+    fn visit_generics(&mut self, node: &'ast syn::Generics) {
+        if !node.params.is_empty() {
+            self.push(NodeKind::Generics, node);
+        }
+        visit::visit_generics(self, node);
+    }
+
+    fn visit_field(&mut self, node: &'ast syn::Field) {
+        self.push(NodeKind::Field, node);
+        visit::visit_field(self, node);
+    }
+
+    fn visit_type(&mut self, node: &'ast syn::Type) {
+        self.push(NodeKind::Type, node);
+        visit::visit_type(self, node);
+    }
+
+    fn visit_expr(&mut self, node: &'ast syn::Expr) {
+        self.push(NodeKind::Expr, node);
+        visit::visit_expr(self, node);
+    }
+
+    fn visit_lit(&mut self, node: &'ast syn::Lit) {
+        self.push(NodeKind::Lit, node);
+        visit::visit_lit(self, node);
+    }
+
+    fn visit_ident(&mut self, node: &'ast syn::Ident) {
+        self.push(NodeKind::Ident, node);
+        visit::visit_ident(self, node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_report() {
+        let code = "#[derive(Debug)]\nstruct Foo<T> {\n    a: T,\n}\nstruct Bar;";
+        let file = syn::parse_file(code).unwrap();
+        let mut collector = SpanCollector::new();
+        collector.visit_file(&file);
+        insta::assert_snapshot!(collector.render_report(code), @r###"
+        item (2):
+         --> 1:0..4:1, 5:0..5:11
+          |
+        1 | #[derive(Debug)]
+          | ^^^^^^^^^^^^^^^^
+        2 | struct Foo<T> {
+        3 |     a: T,
+        4 | }
+          | ^
+        5 | struct Bar;
+          | ^^^^^^^^^^^
+          |
+
+        attr (1):
+         --> 1:0..1:16
+          |
+        1 | #[derive(Debug)]
+          | ^^^^^^^^^^^^^^^^
+          |
+
+        generics (1):
+         --> 2:10..2:13
+          |
+        2 | struct Foo<T> {
+          |           ^^^
+          |
+
+        field (1):
+         --> 3:4..3:8
+          |
+        3 |     a: T,
+          |     ^^^^
+          |
+
+        type (1):
+         --> 3:7..3:8
+          |
+        3 |     a: T,
+          |        ^
+          |
+
+        ident (6):
+         --> 1:2..1:8, 2:7..2:10, 2:11..2:12, 3:4..3:5, 3:7..3:8, 5:7..5:10
+          |
+        1 | #[derive(Debug)]
+          |   ^^^^^^
+        2 | struct Foo<T> {
+          |        ^^^
+          |            ^
+        3 |     a: T,
+          |     ^
+          |        ^
+         ...
+        5 | struct Bar;
+          |        ^^^
+          |
+        "###);
+        assert_eq!(NodeKind::from_name("generics"), Some(NodeKind::Generics));
+        assert_eq!(NodeKind::from_name("foo"), None);
+    }
+}
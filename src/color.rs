@@ -0,0 +1,381 @@
+use alloc::format;
+use alloc::string::String;
+
+/// Named color themes for the frame of a rendering: the gutter, line numbers, markers, boxes and
+/// labels. In the terminal, the gutter is dimmed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Theme {
+    /// Readable on both dark and light backgrounds.
+    #[default]
+    Default,
+    /// Brighter colors for dark backgrounds.
+    Dark,
+    /// Darker colors for light backgrounds.
+    Light,
+    /// The Okabe-Ito palette, distinguishable with the common forms of color blindness.
+    ColorblindSafe,
+    /// User defined colors, e.g. imported with `Theme::from_miette` or `Theme::from_ariadne`.
+    Custom(Palette),
+}
+
+impl Theme {
+    /// Returns the colors of the theme.
+    pub fn palette(self) -> Palette {
+        match self {
+            Theme::Default => Palette {
+                gutter: Color::new((86, 156, 214), 94),
+                line_numbers: Color::new((86, 182, 194), 36),
+                labels: [
+                    Color::new((224, 108, 117), 91),
+                    Color::new((229, 192, 123), 93),
+                    Color::new((152, 195, 121), 92),
+                    Color::new((198, 120, 221), 95),
+                ],
+            },
+            Theme::Dark => Palette {
+                gutter: Color::new((97, 175, 239), 94),
+                line_numbers: Color::new((86, 214, 230), 96),
+                labels: [
+                    Color::new((255, 121, 121), 91),
+                    Color::new((255, 203, 107), 93),
+                    Color::new((195, 232, 141), 92),
+                    Color::new((199, 146, 234), 95),
+                ],
+            },
+            Theme::Light => Palette {
+                gutter: Color::new((0, 92, 197), 34),
+                line_numbers: Color::new((0, 128, 128), 36),
+                labels: [
+                    Color::new((215, 58, 73), 31),
+                    Color::new((176, 136, 0), 33),
+                    Color::new((34, 134, 58), 32),
+                    Color::new((111, 66, 193), 35),
+                ],
+            },
+            Theme::ColorblindSafe => Palette {
+                gutter: Color::new((86, 180, 233), 96),
+                line_numbers: Color::new((86, 180, 233), 96),
+                labels: [
+                    Color::new((213, 94, 0), 31),
+                    Color::new((0, 114, 178), 34),
+                    Color::new((230, 159, 0), 33),
+                    Color::new((0, 158, 115), 36),
+                ],
+            },
+            Theme::Custom(palette) => palette,
+        }
+    }
+}
+
+/// The colors of a [`Theme`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Palette {
+    /// `|` separators and the `-->` arrow, and line numbers outside the terminal output.
+    pub gutter: Color,
+    /// Line numbers of the terminal output.
+    pub line_numbers: Color,
+    /// Markers, boxes and labels. A single span uses the first color, the spans of a
+    /// multi-span rendering cycle through them.
+    pub labels: [Color; 4],
+}
+
+impl Palette {
+    /// Returns the color of the span at `index` in a rendering.
+    pub fn label(&self, index: usize) -> Color {
+        self.labels[index % self.labels.len()]
+    }
+}
+
+/// A color, with its 24-bit value and its 16-color ANSI fallback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub rgb: (u8, u8, u8),
+    /// SGR foreground code, `30..=37` or `90..=97`.
+    pub ansi: u8,
+}
+
+impl Color {
+    pub const fn new(rgb: (u8, u8, u8), ansi: u8) -> Self {
+        Self { rgb, ansi }
+    }
+
+    /// The color `rgb`, with the closest of the 16 ANSI colors as fallback.
+    pub fn from_rgb(rgb: (u8, u8, u8)) -> Self {
+        let distance = |(r, g, b): (u8, u8, u8)| {
+            let delta = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+            delta(r, rgb.0) + delta(g, rgb.1) + delta(b, rgb.2)
+        };
+        let index = (0..16)
+            .min_by_key(|&index| distance(ANSI_RGB[index]))
+            .unwrap_or(0);
+        Self::new(rgb, ansi_code(index as u8))
+    }
+
+    /// The color `index` of the 256-color xterm palette.
+    pub fn from_xterm(index: u8) -> Self {
+        match index {
+            0..=15 => Self::new(ANSI_RGB[usize::from(index)], ansi_code(index)),
+            16..=231 => {
+                let level = |value: u8| match value {
+                    0 => 0,
+                    value => 55 + value * 40,
+                };
+                let index = index - 16;
+                Self::from_rgb((level(index / 36), level(index / 6 % 6), level(index % 6)))
+            }
+            232..=255 => {
+                let gray = 8 + (index - 232) * 10;
+                Self::from_rgb((gray, gray, gray))
+            }
+        }
+    }
+
+    /// Wraps `text` in the ANSI escapes of the color.
+    pub fn paint(self, text: &str, depth: ColorDepth) -> String {
+        let (r, g, b) = self.rgb;
+        match depth {
+            ColorDepth::TrueColor => format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, text),
+            ColorDepth::Ansi16 => format!("\x1b[{}m{}\x1b[0m", self.ansi, text),
+        }
+    }
+
+    /// Wraps `text` in the ANSI escapes of the color, dimmed.
+    pub fn paint_dimmed(self, text: &str, depth: ColorDepth) -> String {
+        format!("\x1b[2m{}", self.paint(text, depth))
+    }
+}
+
+/// The xterm values of the 16 ANSI colors, the normal ones followed by the bright ones.
+const ANSI_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The SGR foreground code of the ANSI color `index`, `0..16`.
+fn ansi_code(index: u8) -> u8 {
+    match index {
+        0..=7 => 30 + index,
+        _ => 90 + index - 8,
+    }
+}
+
+/// Colors supported by the terminal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ColorDepth {
+    /// 24-bit colors.
+    #[default]
+    TrueColor,
+    /// The 16 basic ANSI colors.
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Detects truecolor support from the `COLORTERM` environment variable, falling back to 16
+    /// colors.
+    #[cfg(feature = "std")]
+    pub fn detect() -> Self {
+        match std::env::var("COLORTERM").as_deref() {
+            Ok("truecolor") | Ok("24bit") => ColorDepth::TrueColor,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+}
+
+/// When to color the output, behind the `color` feature.
+///
+/// The printing macros writing to stderr, like [`eprint_span!`](crate::eprint_span!) and
+/// [`dbg_span!`](crate::dbg_span!), and the messages of
+/// [`assert_span_eq!`](crate::assert_span_eq!) use [`ColorChoice::Auto`].
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span_with_options, ColorChoice, DebugSpanOptions, LineColumnSpan};
+///
+/// let options = ColorChoice::Auto.options(&DebugSpanOptions::default(), &std::io::stderr());
+/// let output = debug_span_with_options(LineColumnSpan::new(1, 7, 1, 10), "struct Foo;", &options);
+/// eprint!("{}", output);
+/// ```
+#[cfg(feature = "color")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ColorChoice {
+    /// Colors if the output is a terminal and the `NO_COLOR` environment variable is not set, or
+    /// if `CLICOLOR_FORCE` is set, e.g. in CI logs.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[cfg(feature = "color")]
+impl ColorChoice {
+    /// Tells if the output written to `stream` is colored.
+    pub fn enabled(self, stream: &impl std::io::IsTerminal) -> bool {
+        let is_set = |name| std::env::var_os(name).is_some_and(|value| !value.is_empty());
+        let force = std::env::var_os("CLICOLOR_FORCE").is_some_and(|value| value != "0");
+        self.resolve(is_set("NO_COLOR"), force, || stream.is_terminal())
+    }
+
+    fn resolve(self, no_color: bool, force: bool, is_terminal: impl FnOnce() -> bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => !no_color && (force || is_terminal()),
+        }
+    }
+
+    /// Returns `options` for the output written to `stream`: colored with their
+    /// [theme](crate::DebugSpanOptions::theme), or the default one, and the
+    /// [detected](ColorDepth::detect) color depth if it is colored, without colors otherwise.
+    pub fn options(
+        self,
+        options: &crate::DebugSpanOptions,
+        stream: &impl std::io::IsTerminal,
+    ) -> crate::DebugSpanOptions {
+        match self.enabled(stream) {
+            true => crate::DebugSpanOptions {
+                theme: Some(options.theme.unwrap_or_default()),
+                color_depth: match self {
+                    ColorChoice::Auto => ColorDepth::detect(),
+                    _ => options.color_depth,
+                },
+                ..options.clone()
+            },
+            false => crate::DebugSpanOptions {
+                theme: None,
+                ..options.clone()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{debug_span_with_options, DebugSpanOptions, LineColumnSpan};
+    use unindent::Unindent;
+
+    #[test]
+    fn test_theme() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+            }
+        "###
+        .unindent();
+        let options = DebugSpanOptions {
+            label: Some("expected ident".to_string()),
+            theme: Some(Theme::ColorblindSafe),
+            ..Default::default()
+        };
+        let output = debug_span_with_options(LineColumnSpan::new(1, 7, 1, 10), &input, &options);
+        insta::assert_snapshot!(output.replace('\x1b', "\\e"), @r###"
+         \e[2m\e[38;2;86;180;233m-->\e[0m 1:7..1:10
+         \e[2m\e[38;2;86;180;233m |\e[0m
+        \e[38;2;86;180;233m1\e[0m\e[2m\e[38;2;86;180;233m |\e[0m struct Foo {
+         \e[2m\e[38;2;86;180;233m |\e[0m        \e[38;2;213;94;0m^^^ expected ident\e[0m
+         \e[2m\e[38;2;86;180;233m |\e[0m
+        "###);
+
+        let options = DebugSpanOptions {
+            color_depth: ColorDepth::Ansi16,
+            ..options
+        };
+        let output = debug_span_with_options(LineColumnSpan::new(1, 11, 3, 1), &input, &options);
+        insta::assert_snapshot!(output.replace('\x1b', "\\e"), @r###"
+         \e[2m\e[96m-->\e[0m 1:11..3:1
+         \e[2m\e[96m |\e[0m
+         \e[2m\e[96m |\e[0m            \e[31m┌────╮\e[0m
+        \e[96m1\e[0m\e[2m\e[96m |\e[0m struct Foo {    \e[31m│\e[0m
+        \e[96m2\e[0m\e[2m\e[96m |\e[0m     a: i32,     \e[31m│\e[0m
+        \e[96m3\e[0m\e[2m\e[96m |\e[0m }               \e[31m│\e[0m
+         \e[2m\e[96m |\e[0m \e[31m└───────────────╯ expected ident\e[0m
+         \e[2m\e[96m |\e[0m
+        "###);
+
+        let spans = [
+            LineColumnSpan::new(2, 4, 2, 5),
+            LineColumnSpan::new(2, 7, 2, 10),
+        ];
+        let output = crate::internal::debug_spans_with_options(&spans, &input, &options);
+        insta::assert_snapshot!(output.replace('\x1b', "\\e"), @r###"
+         \e[2m\e[96m-->\e[0m 2:4..2:5, 2:7..2:10
+         \e[2m\e[96m |\e[0m
+        \e[96m2\e[0m\e[2m\e[96m |\e[0m     a: i32,
+         \e[2m\e[96m |\e[0m     \e[31m^\e[0m
+         \e[2m\e[96m |\e[0m        \e[34m^^^\e[0m
+         \e[2m\e[96m |\e[0m
+        "###);
+
+        let options = DebugSpanOptions {
+            theme: Some(Theme::Default),
+            ..options
+        };
+        let output = debug_span_with_options(LineColumnSpan::new(1, 7, 1, 10), &input, &options);
+        insta::assert_snapshot!(output.replace('\x1b', "\\e"), @r###"
+         \e[2m\e[94m-->\e[0m 1:7..1:10
+         \e[2m\e[94m |\e[0m
+        \e[36m1\e[0m\e[2m\e[94m |\e[0m struct Foo {
+         \e[2m\e[94m |\e[0m        \e[91m^^^ expected ident\e[0m
+         \e[2m\e[94m |\e[0m
+        "###);
+
+        let options = DebugSpanOptions {
+            deterministic: true,
+            ..options
+        };
+        let output = debug_span_with_options(LineColumnSpan::new(1, 7, 1, 10), &input, &options);
+        assert!(!output.contains('\x1b'));
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn test_color_choice() {
+        let terminal = || true;
+        let pipe = || false;
+        assert!(ColorChoice::Auto.resolve(false, false, terminal));
+        assert!(!ColorChoice::Auto.resolve(false, false, pipe));
+        assert!(ColorChoice::Auto.resolve(false, true, pipe));
+        assert!(!ColorChoice::Auto.resolve(true, true, terminal));
+        assert!(ColorChoice::Always.resolve(true, false, pipe));
+        assert!(!ColorChoice::Never.resolve(false, true, terminal));
+
+        let options = DebugSpanOptions {
+            theme: Some(Theme::Light),
+            ..Default::default()
+        };
+        let colored = ColorChoice::Always.options(&options, &std::io::stderr());
+        assert_eq!(colored, options);
+        let plain = ColorChoice::Never.options(&options, &std::io::stderr());
+        assert_eq!(plain.theme, None);
+        let colored = ColorChoice::Always.options(&plain, &std::io::stderr());
+        assert_eq!(colored.theme, Some(Theme::Default));
+    }
+
+    #[test]
+    fn test_color_conversions() {
+        assert_eq!(
+            Color::from_rgb((250, 10, 10)),
+            Color::new((250, 10, 10), 91)
+        );
+        assert_eq!(Color::from_rgb((20, 20, 20)), Color::new((20, 20, 20), 30));
+        assert_eq!(Color::from_xterm(3), Color::new((205, 205, 0), 33));
+        assert_eq!(Color::from_xterm(12), Color::new((92, 92, 255), 94));
+        assert_eq!(Color::from_xterm(196), Color::new((255, 0, 0), 91));
+        assert_eq!(Color::from_xterm(246), Color::new((148, 148, 148), 90));
+    }
+}
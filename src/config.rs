@@ -0,0 +1,331 @@
+use crate::{
+    Charset, ColorDepth, DebugSpanOptions, Messages, MultiLineStyle, OutputVersion, Theme,
+};
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+
+/// The settings of a configuration file, all optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Config {
+    context: Option<usize>,
+    max_lines: Option<usize>,
+    max_width: Option<usize>,
+    tab_width: Option<usize>,
+    charset: Option<String>,
+    multi_line_style: Option<String>,
+    output_version: Option<String>,
+    deterministic: Option<bool>,
+    provenance: Option<bool>,
+    prose: Option<bool>,
+    show_empty_spans: Option<bool>,
+    theme: Option<String>,
+    color_depth: Option<String>,
+    highlight: Option<String>,
+    highlighter: Option<String>,
+    #[serde(default)]
+    messages: MessagesConfig,
+}
+
+/// The `[messages]` table, see [`Messages`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct MessagesConfig {
+    error: Option<String>,
+    warning: Option<String>,
+    help: Option<String>,
+    note: Option<String>,
+    lines_omitted: Option<String>,
+    empty_span: Option<String>,
+}
+
+impl MessagesConfig {
+    fn apply(self, messages: &mut Messages) {
+        let fields = [
+            (self.error, &mut messages.error),
+            (self.warning, &mut messages.warning),
+            (self.help, &mut messages.help),
+            (self.note, &mut messages.note),
+            (self.lines_omitted, &mut messages.lines_omitted),
+            (self.empty_span, &mut messages.empty_span),
+        ];
+        for (value, field) in fields {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+    }
+}
+
+impl DebugSpanOptions {
+    /// Load options from a TOML file, behind the `config` feature.
+    ///
+    /// Missing settings keep their default value. The settings are:
+    ///
+    /// ```toml
+    /// context = 2
+    /// max-lines = 10
+    /// max-width = 80
+    /// tab-width = 8
+    /// charset = "ascii"            # or "unicode"
+    /// multi-line-style = "rustc"   # or "box"
+    /// output-version = "v1"        # or "v2"
+    /// deterministic = true
+    /// provenance = true
+    /// prose = true
+    /// show-empty-spans = true
+    /// theme = "dark"               # "default", "dark", "light", "colorblind-safe" or "none"
+    /// color-depth = "ansi16"       # "truecolor", "ansi16" or "detect"
+    /// highlight = "ansi"           # "ansi", "html" or "none", with a highlighting feature
+    /// highlighter = "tree-sitter"  # or "syntect"
+    ///
+    /// [messages]                   # translations of the fixed words, see `Messages`
+    /// error = "erreur"
+    /// warning = "avertissement"
+    /// help = "aide"
+    /// note = "remarque"
+    /// lines-omitted = "[...]"
+    /// empty-span = "(span vide)"
+    /// ```
+    pub fn from_toml(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| ConfigError::Io(path.display().to_string(), error))?;
+        Self::from_toml_str(&text)
+    }
+
+    /// Load options from the text of a TOML file, see [`from_toml`](Self::from_toml).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use debug_span::{Charset, DebugSpanOptions};
+    ///
+    /// let options = DebugSpanOptions::from_toml_str("context = 1\ncharset = \"ascii\"").unwrap();
+    /// assert_eq!(options.context, 1);
+    /// assert_eq!(options.charset, Charset::Ascii);
+    /// ```
+    pub fn from_toml_str(text: &str) -> Result<Self, ConfigError> {
+        let config = toml::from_str::<Config>(text).map_err(ConfigError::Toml)?;
+        let mut options = DebugSpanOptions::default();
+        if let Some(context) = config.context {
+            options.context = context;
+        }
+        if let Some(max_lines) = config.max_lines {
+            options.max_lines = Some(max_lines);
+        }
+        if let Some(max_width) = config.max_width {
+            options.max_width = Some(max_width);
+        }
+        if let Some(tab_width) = config.tab_width {
+            options.tab_width = tab_width;
+        }
+        if let Some(charset) = config.charset {
+            options.charset = match charset.as_str() {
+                "unicode" => Charset::Unicode,
+                "ascii" => Charset::Ascii,
+                _ => return Err(ConfigError::invalid("charset", charset)),
+            };
+        }
+        if let Some(style) = config.multi_line_style {
+            options.multi_line_style = match style.as_str() {
+                "box" => MultiLineStyle::Box,
+                "rustc" => MultiLineStyle::Rustc,
+                _ => return Err(ConfigError::invalid("multi-line-style", style)),
+            };
+        }
+        if let Some(output_version) = config.output_version {
+            options.output_version = match output_version.as_str() {
+                "v1" => OutputVersion::V1,
+                "v2" => OutputVersion::V2,
+                _ => return Err(ConfigError::invalid("output-version", output_version)),
+            };
+        }
+        if let Some(deterministic) = config.deterministic {
+            options.deterministic = deterministic;
+        }
+        if let Some(provenance) = config.provenance {
+            options.provenance = provenance;
+        }
+        if let Some(prose) = config.prose {
+            options.prose = prose;
+        }
+        if let Some(show_empty_spans) = config.show_empty_spans {
+            options.show_empty_spans = show_empty_spans;
+        }
+        if let Some(theme) = config.theme {
+            options.theme = match theme.as_str() {
+                "default" => Some(Theme::Default),
+                "dark" => Some(Theme::Dark),
+                "light" => Some(Theme::Light),
+                "colorblind-safe" => Some(Theme::ColorblindSafe),
+                "none" => None,
+                _ => return Err(ConfigError::invalid("theme", theme)),
+            };
+        }
+        if let Some(color_depth) = config.color_depth {
+            options.color_depth = match color_depth.as_str() {
+                "truecolor" => ColorDepth::TrueColor,
+                "ansi16" => ColorDepth::Ansi16,
+                "detect" => ColorDepth::detect(),
+                _ => return Err(ConfigError::invalid("color-depth", color_depth)),
+            };
+        }
+        if let Some(highlight) = config.highlight {
+            set_highlight(&mut options, highlight)?;
+        }
+        if let Some(highlighter) = config.highlighter {
+            set_highlighter(&mut options, highlighter)?;
+        }
+        config.messages.apply(&mut options.messages);
+        Ok(options)
+    }
+}
+
+#[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+fn set_highlight(options: &mut DebugSpanOptions, highlight: String) -> Result<(), ConfigError> {
+    options.highlight = match highlight.as_str() {
+        "ansi" => Some(crate::Highlight::Ansi),
+        "html" => Some(crate::Highlight::Html),
+        "none" => None,
+        _ => return Err(ConfigError::invalid("highlight", highlight)),
+    };
+    Ok(())
+}
+
+#[cfg(not(any(feature = "syntect", feature = "tree-sitter")))]
+fn set_highlight(_options: &mut DebugSpanOptions, highlight: String) -> Result<(), ConfigError> {
+    match highlight.as_str() {
+        "none" => Ok(()),
+        _ => Err(ConfigError::invalid("highlight", highlight)),
+    }
+}
+
+#[cfg_attr(
+    not(any(feature = "syntect", feature = "tree-sitter")),
+    allow(unused_variables)
+)]
+fn set_highlighter(options: &mut DebugSpanOptions, highlighter: String) -> Result<(), ConfigError> {
+    #[cfg(feature = "syntect")]
+    if highlighter == "syntect" {
+        options.highlighter = crate::Highlighter::Syntect;
+        return Ok(());
+    }
+    #[cfg(feature = "tree-sitter")]
+    if highlighter == "tree-sitter" {
+        options.highlighter = crate::Highlighter::TreeSitter;
+        return Ok(());
+    }
+    Err(ConfigError::invalid("highlighter", highlighter))
+}
+
+/// The error returned when loading a configuration file fails.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file can't be read.
+    Io(String, std::io::Error),
+    /// The file isn't valid TOML, or has unknown settings.
+    Toml(toml::de::Error),
+    /// A setting has an unknown value, or one requiring a disabled feature.
+    Invalid { key: &'static str, value: String },
+}
+
+impl ConfigError {
+    fn invalid(key: &'static str, value: String) -> Self {
+        ConfigError::Invalid { key, value }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, error) => write!(f, "failed to read {}: {}", path, error),
+            ConfigError::Toml(error) => write!(f, "invalid config: {}", error.message()),
+            ConfigError::Invalid { key, value } => {
+                write!(f, "invalid value `{}` for `{}` in config", value, key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(_, error) => Some(error),
+            ConfigError::Toml(error) => Some(error),
+            ConfigError::Invalid { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml_str() {
+        let options = DebugSpanOptions::from_toml_str(
+            r#"
+            context = 2
+            max-lines = 10
+            max-width = 80
+            tab-width = 8
+            charset = "ascii"
+            multi-line-style = "rustc"
+            output-version = "v1"
+            deterministic = true
+            provenance = true
+            prose = true
+            show-empty-spans = true
+            theme = "colorblind-safe"
+            color-depth = "ansi16"
+
+            [messages]
+            error = "erreur"
+            lines-omitted = "[...]"
+            "#,
+        )
+        .unwrap();
+        let expected = DebugSpanOptions {
+            context: 2,
+            max_lines: Some(10),
+            max_width: Some(80),
+            tab_width: 8,
+            charset: Charset::Ascii,
+            multi_line_style: MultiLineStyle::Rustc,
+            output_version: OutputVersion::V1,
+            deterministic: true,
+            provenance: true,
+            prose: true,
+            show_empty_spans: true,
+            theme: Some(Theme::ColorblindSafe),
+            color_depth: ColorDepth::Ansi16,
+            messages: Messages {
+                error: "erreur".to_string(),
+                lines_omitted: "[...]".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(options, expected);
+        assert_eq!(
+            DebugSpanOptions::from_toml_str("").unwrap(),
+            DebugSpanOptions::default()
+        );
+
+        let error = DebugSpanOptions::from_toml_str("theme = \"solarized\"").unwrap_err();
+        insta::assert_snapshot!(error, @"invalid value `solarized` for `theme` in config");
+        let error = DebugSpanOptions::from_toml_str("colour = true").unwrap_err();
+        let message = error.to_string();
+        assert!(
+            message.starts_with("invalid config: unknown field `colour`"),
+            "{}",
+            message
+        );
+        let error = DebugSpanOptions::from_toml("missing.toml").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("failed to read missing.toml: "));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{Charset, DebugSpanOptions};
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// Enables the processing of ANSI escapes by the Windows console, behind the `windows-console`
+/// feature, and tells if escapes and Unicode output are displayed properly.
+///
+/// Legacy consoles, without virtual terminal processing, return `false`. The result is computed
+/// once. Outputs that aren't consoles, e.g. files, and other platforms always return `true`.
+pub fn enable_virtual_terminal() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(enable)
+}
+
+/// Returns `options` adjusted to the console: without a legacy Windows console they are
+/// unchanged, otherwise colors and highlighting are disabled and the box is drawn with ASCII.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{console_options, debug_span_with_options, DebugSpanOptions, LineColumnSpan};
+///
+/// let options = console_options(&DebugSpanOptions::default());
+/// let span = LineColumnSpan::new(1, 7, 1, 10);
+/// let output = debug_span_with_options(span, "struct Foo;", &options);
+/// eprint!("{}", output);
+/// ```
+pub fn console_options(options: &DebugSpanOptions) -> DebugSpanOptions {
+    if enable_virtual_terminal() {
+        return options.clone();
+    }
+    fallback_options(options)
+}
+
+fn fallback_options(options: &DebugSpanOptions) -> DebugSpanOptions {
+    DebugSpanOptions {
+        theme: None,
+        charset: Charset::Ascii,
+        #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+        highlight: None,
+        ..options.clone()
+    }
+}
+
+/// Prepares an already rendered output for stderr: on legacy Windows consoles, escapes are
+/// removed and box drawing characters are replaced by their [ASCII](Charset::Ascii) version.
+pub(crate) fn for_console(output: &str) -> Cow<'_, str> {
+    if enable_virtual_terminal() {
+        return Cow::Borrowed(output);
+    }
+    Cow::Owned(to_ascii(output))
+}
+
+fn to_ascii(output: &str) -> String {
+    let mut ascii = String::with_capacity(output.len());
+    let mut chars = output.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // skip the escape up to its final byte
+            '\x1b' => {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            '┌' | '╮' | '└' | '╯' => ascii.push('+'),
+            '─' => ascii.push('-'),
+            '│' => ascii.push('|'),
+            c => ascii.push(c),
+        }
+    }
+    ascii
+}
+
+#[cfg(windows)]
+fn enable() -> bool {
+    use windows_sys::Win32::System::Console::{
+        GetConsoleMode, GetStdHandle, SetConsoleMode, CONSOLE_MODE,
+        ENABLE_VIRTUAL_TERMINAL_PROCESSING, STD_ERROR_HANDLE, STD_OUTPUT_HANDLE,
+    };
+
+    [STD_OUTPUT_HANDLE, STD_ERROR_HANDLE]
+        .into_iter()
+        .all(|handle| {
+            // SAFETY: the handle comes from `GetStdHandle` and `mode` outlives the calls.
+            unsafe {
+                let handle = GetStdHandle(handle);
+                let mut mode: CONSOLE_MODE = 0;
+                // not a console, e.g. redirected to a file: the output is written as is
+                if GetConsoleMode(handle, &mut mode) == 0 {
+                    return true;
+                }
+                mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+                    || SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+            }
+        })
+}
+
+#[cfg(not(windows))]
+fn enable() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{debug_span_with_options, LineColumnSpan, Theme};
+
+    #[test]
+    fn test_fallback() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        let options = DebugSpanOptions {
+            theme: Some(Theme::Dark),
+            ..Default::default()
+        };
+        let span = LineColumnSpan::new(1, 11, 3, 1);
+        let output = debug_span_with_options(span, code, &options);
+        let fallback = debug_span_with_options(span, code, &fallback_options(&options));
+        assert_eq!(to_ascii(&output), fallback);
+        insta::assert_snapshot!(fallback, @r###"
+         --> 1:11..3:1
+          |
+          |            +----+
+        1 | struct Foo {    |
+        2 |     a: i32,     |
+        3 | }               |
+          | +---------------+
+          |
+        "###);
+        assert_eq!(console_options(&options), options);
+    }
+}
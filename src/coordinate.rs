@@ -0,0 +1,129 @@
+use core::fmt;
+
+/// A 1-based coordinate, used for line numbers.
+///
+/// It is never `0`. rustc diagnostics also use 1-based columns, see
+/// [`ZeroBased::to_one_based`]. There is no `From` conversion between the conventions, so that
+/// converting is always spelled out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OneBased(usize);
+
+/// A 0-based coordinate, used for columns, following [`proc_macro2::LineColumn`].
+///
+/// [`proc_macro2::LineColumn`]: https://docs.rs/proc-macro2/latest/proc_macro2/struct.LineColumn.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ZeroBased(usize);
+
+impl OneBased {
+    /// Returns `None` for `0`, which is not a valid 1-based coordinate.
+    pub const fn new(value: usize) -> Option<Self> {
+        if value == 0 {
+            None
+        } else {
+            Some(Self(value))
+        }
+    }
+
+    pub const fn get(self) -> usize {
+        self.0
+    }
+
+    /// The same coordinate, counted from `0`.
+    pub const fn to_zero_based(self) -> ZeroBased {
+        ZeroBased(self.0 - 1)
+    }
+}
+
+impl ZeroBased {
+    pub const fn new(value: usize) -> Self {
+        Self(value)
+    }
+
+    pub const fn get(self) -> usize {
+        self.0
+    }
+
+    /// The same coordinate, counted from `1`.
+    pub const fn to_one_based(self) -> OneBased {
+        OneBased(self.0 + 1)
+    }
+}
+
+impl fmt::Display for OneBased {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for ZeroBased {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A position in the source code, with a 1-based line and a 0-based column.
+///
+/// Mixing up the conventions, e.g. passing the 1-based column of a rustc diagnostic where a
+/// proc-macro2 column is expected, is a type error.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{LineColumnSpan, OneBased, Position};
+///
+/// // rustc reports `--> src/lib.rs:1:8` for the ident of `struct Foo;`
+/// let line = OneBased::new(1).unwrap();
+/// let column = OneBased::new(8).unwrap();
+/// let start = Position::new(line, column.to_zero_based());
+/// let end = Position::new(line, OneBased::new(11).unwrap().to_zero_based());
+/// let span = LineColumnSpan::from_positions(start, end);
+/// assert_eq!(span, LineColumnSpan::new(1, 7, 1, 10));
+/// assert_eq!(span.start(), Some(start));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    pub line: OneBased,
+    pub column: ZeroBased,
+}
+
+impl Position {
+    pub const fn new(line: OneBased, column: ZeroBased) -> Self {
+        Self { line, column }
+    }
+
+    /// The position of a 1-based line and a 1-based column, as reported by rustc diagnostics
+    /// and `proc_macro::Span`, or `None` if either is `0`.
+    pub const fn from_one_based(line: usize, column: usize) -> Option<Self> {
+        match (OneBased::new(line), OneBased::new(column)) {
+            (Some(line), Some(column)) => Some(Self::new(line, column.to_zero_based())),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_coordinates() {
+        assert_eq!(OneBased::new(0), None);
+        let one = OneBased::new(1).unwrap();
+        assert_eq!(one.to_zero_based(), ZeroBased::new(0));
+        assert_eq!(ZeroBased::new(6).to_one_based().get(), 7);
+        assert_eq!(OneBased::new(7).unwrap().to_zero_based().get(), 6);
+        assert_eq!(Position::new(one, ZeroBased::new(4)).to_string(), "1:4");
+        assert_eq!(
+            Position::from_one_based(1, 5),
+            Some(Position::new(one, ZeroBased::new(4)))
+        );
+        assert_eq!(Position::from_one_based(1, 0), None);
+    }
+}
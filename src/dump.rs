@@ -0,0 +1,236 @@
+use crate::{DebugSpanError, DebugSpanOptions, LineColumnSpan, NamedSource, SourceCode, Span};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// The environment variable naming the dump file used by [`SpanDump::record`].
+const DUMP_VAR: &str = "DEBUG_SPAN_DUMP";
+
+/// A file of recorded spans, for inspecting them after the build finishes.
+///
+/// During macro expansion, the output of the macro is hard to look at. [`SpanDump::record`]
+/// appends the span and its source code as a JSON line, and [`SpanDump::read`] or
+/// `debug-span dump <file>` render them later.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{LineColumnSpan, SpanDump};
+///
+/// let path = std::env::temp_dir().join("debug-span-dump-example.jsonl");
+/// # let _ = std::fs::remove_file(&path);
+/// let dump = SpanDump::new(&path);
+/// dump.append("ident", LineColumnSpan::new(1, 7, 1, 10), "struct Foo;").unwrap();
+///
+/// let records = dump.read().unwrap();
+/// assert_eq!(records[0].name, "ident");
+/// assert_eq!(
+///     records[0].debug(),
+///     " --> 1:7..1:10\n  |\n1 | struct Foo;\n  |        ^^^\n  |\n"
+/// );
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanDump {
+    path: PathBuf,
+}
+
+/// A span recorded in a [`SpanDump`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpanRecord {
+    pub name: String,
+    #[serde(with = "serde_span")]
+    pub span: LineColumnSpan,
+    /// The path of the source, if it has one.
+    pub path: Option<String>,
+    pub code: String,
+}
+
+impl SpanDump {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The dump configured by the environment: the `DEBUG_SPAN_DUMP` file if set, or
+    /// `debug-span-dump.jsonl` in `OUT_DIR`.
+    pub fn from_env() -> Option<Self> {
+        if let Some(path) = std::env::var_os(DUMP_VAR).filter(|path| !path.is_empty()) {
+            return Some(Self::new(path));
+        }
+        let out_dir = std::env::var_os("OUT_DIR")?;
+        Some(Self::new(Path::new(&out_dir).join("debug-span-dump.jsonl")))
+    }
+
+    /// Record a span in the dump configured by the environment.
+    ///
+    /// It does nothing if neither `DEBUG_SPAN_DUMP` nor `OUT_DIR` is set.
+    pub fn record(
+        name: impl Into<String>,
+        span: impl Span,
+        code: &(impl SourceCode + ?Sized),
+    ) -> io::Result<()> {
+        match Self::from_env() {
+            Some(dump) => dump.append(name, span, code),
+            None => Ok(()),
+        }
+    }
+
+    /// The path of the dump file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a span to the dump.
+    pub fn append(
+        &self,
+        name: impl Into<String>,
+        span: impl Span,
+        code: &(impl SourceCode + ?Sized),
+    ) -> io::Result<()> {
+        let code_text = (1..=code.line_count())
+            .filter_map(|line_number| code.line(line_number))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let record = SpanRecord {
+            name: name.into(),
+            span: LineColumnSpan::from_span(&span),
+            path: code.path().map(String::from),
+            code: code_text,
+        };
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())
+    }
+
+    /// Read all records of the dump, in recording order.
+    pub fn read(&self) -> io::Result<Vec<SpanRecord>> {
+        let file = std::fs::File::open(&self.path)?;
+        BufReader::new(file)
+            .lines()
+            .filter(|line| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect()
+    }
+}
+
+impl SpanRecord {
+    /// Render the recorded span against the recorded code.
+    pub fn debug(&self) -> String {
+        self.debug_with_options(&DebugSpanOptions::default())
+    }
+
+    /// Render the recorded span with custom options.
+    pub fn debug_with_options(&self, options: &DebugSpanOptions) -> String {
+        match &self.path {
+            Some(path) => crate::internal::debug_span_with_options(
+                &self.span,
+                &NamedSource::new(path.as_str(), self.code.as_str()),
+                options,
+            ),
+            None => crate::internal::debug_span_with_options(&self.span, &self.code, options),
+        }
+    }
+
+    /// Render the recorded span, or return an error if it doesn't point into the recorded code.
+    pub fn try_debug(&self) -> Result<String, DebugSpanError> {
+        self.try_debug_with_options(&DebugSpanOptions::default())
+    }
+
+    /// Render the recorded span with custom options, or return an error if it doesn't point
+    /// into the recorded code.
+    pub fn try_debug_with_options(
+        &self,
+        options: &DebugSpanOptions,
+    ) -> Result<String, DebugSpanError> {
+        crate::error::check_span(&self.span, self.code.as_str())?;
+        Ok(self.debug_with_options(options))
+    }
+}
+
+/// Serialize spans as `1:7..1:10` ranges, which keeps the dump readable.
+mod serde_span {
+    use crate::{LineColumnSpan, Span};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        span: &LineColumnSpan,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&span.to_range())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<LineColumnSpan, D::Error> {
+        let range = String::deserialize(deserializer)?;
+        range.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_dump() {
+        let path =
+            std::env::temp_dir().join(format!("debug-span-dump-{}.jsonl", std::process::id()));
+        let dump = SpanDump::new(&path);
+        let code = NamedSource::new("src/foo.rs", "struct Foo {\n    a: i32,\n}");
+        dump.append("ident", LineColumnSpan::new(1, 7, 1, 10), &code)
+            .unwrap();
+        dump.append(
+            "field",
+            LineColumnSpan::new(2, 4, 2, 10),
+            "struct Foo {\n    a: i32,\n}",
+        )
+        .unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let records = dump.read().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        insta::assert_snapshot!(content, @r###"
+        {"name":"ident","span":"1:7..1:10","path":"src/foo.rs","code":"struct Foo {\n    a: i32,\n}"}
+        {"name":"field","span":"2:4..2:10","path":null,"code":"struct Foo {\n    a: i32,\n}"}
+        "###);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].path, None);
+        insta::assert_snapshot!(records[0].debug(), @r###"
+         --> src/foo.rs:1:7..1:10
+          |
+        1 | struct Foo {
+          |        ^^^
+          |
+        "###);
+        let mut stale = records[0].clone();
+        stale.span = LineColumnSpan::new(3, 0, 3, 4);
+        assert_eq!(
+            stale.try_debug(),
+            Err(DebugSpanError::ColumnOutOfRange {
+                line: 3,
+                column: 4,
+                line_len: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_span_dump_invalid_record() {
+        let path = std::env::temp_dir().join(format!(
+            "debug-span-dump-invalid-{}.jsonl",
+            std::process::id()
+        ));
+        std::fs::write(&path, r#"{"name":"a","span":"1:7","path":null,"code":""}"#).unwrap();
+        let error = SpanDump::new(&path).read().unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        insta::assert_snapshot!(error, @"invalid range `1:7`, expected `start_line:start_column..end_line:end_column` at line 1 column 24");
+    }
+}
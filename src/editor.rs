@@ -0,0 +1,231 @@
+use crate::{DebugSpanOptions, LineColumnSpan, Renderer, SourceCode, Span};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Keys highlighting the spans in a running Neovim, for `nvim --server <address> --remote-send`.
+///
+/// The keys run a `:lua` command which opens the [path](SourceCode::path) of the code if any,
+/// replaces the previous highlights with extmarks in the `Search` group, shows the
+/// [label](DebugSpanOptions::label) as virtual text and moves the cursor to the first span.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{DebugSpanOptions, LineColumnSpan, NeovimRenderer, Renderer};
+///
+/// let spans = [LineColumnSpan::new(1, 7, 1, 10)];
+/// let keys = NeovimRenderer.render(&spans, &"struct Foo;", &DebugSpanOptions::default());
+/// assert!(keys.starts_with("<C-\\><C-N>:lua "));
+/// // std::process::Command::new("nvim").args(["--server", address, "--remote-send", &keys])
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NeovimRenderer;
+
+/// Commands selecting the spans in a running Kakoune, for `kak -p <session>`.
+///
+/// The commands open the [path](SourceCode::path) of the code if any, select every span and
+/// echo the [label](DebugSpanOptions::label). Commands sent with `kak -p` need a client to run
+/// in, set [`client`](Self::client) to wrap them in `evaluate-commands -client`.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{DebugSpanOptions, KakouneRenderer, LineColumnSpan, Renderer};
+///
+/// let renderer = KakouneRenderer {
+///     client: Some("client0".to_string()),
+/// };
+/// let spans = [LineColumnSpan::new(1, 7, 1, 10)];
+/// let commands = renderer.render(&spans, &"struct Foo;", &DebugSpanOptions::default());
+/// assert_eq!(
+///     commands,
+///     "evaluate-commands -client 'client0' 'select 1.8,1.10'\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct KakouneRenderer {
+    /// The client running the commands.
+    pub client: Option<String>,
+}
+
+impl Renderer for NeovimRenderer {
+    fn render(
+        &self,
+        spans: &[LineColumnSpan],
+        code: &dyn SourceCode,
+        options: &DebugSpanOptions,
+    ) -> String {
+        let spans = editor_spans(spans, options);
+        let mut lua = Vec::new();
+        if let Some(path) = code.path() {
+            lua.push(format!(
+                "vim.cmd(\"edit \" .. vim.fn.fnameescape({}))",
+                lua_string(path)
+            ));
+        }
+        lua.push(String::from(
+            "local ns = vim.api.nvim_create_namespace(\"debug_span\")",
+        ));
+        lua.push(String::from(
+            "vim.api.nvim_buf_clear_namespace(0, ns, 0, -1)",
+        ));
+        for span in &spans {
+            let mut mark = format!(
+                "end_row = {}, end_col = {}, hl_group = \"Search\"",
+                span.end_line - 1,
+                byte_column(code, span.end_line, span.end_column),
+            );
+            if let Some(label) = &options.label {
+                mark.push_str(&format!(
+                    ", virt_text = {{{{{}, \"Comment\"}}}}",
+                    lua_string(label)
+                ));
+            }
+            lua.push(format!(
+                "vim.api.nvim_buf_set_extmark(0, ns, {}, {}, {{{}}})",
+                span.start_line - 1,
+                byte_column(code, span.start_line, span.start_column),
+                mark,
+            ));
+        }
+        if let Some(span) = spans.first() {
+            lua.push(format!(
+                "vim.api.nvim_win_set_cursor(0, {{{}, {}}})",
+                span.start_line,
+                byte_column(code, span.start_line, span.start_column),
+            ));
+        }
+        // `<` starts a key notation in `--remote-send`
+        let lua = lua.join(" ").replace('<', "<lt>");
+        format!("<C-\\><C-N>:lua {}<CR>", lua)
+    }
+}
+
+impl Renderer for KakouneRenderer {
+    fn render(
+        &self,
+        spans: &[LineColumnSpan],
+        code: &dyn SourceCode,
+        options: &DebugSpanOptions,
+    ) -> String {
+        let spans = editor_spans(spans, options);
+        let mut commands = Vec::new();
+        if let Some(path) = code.path() {
+            commands.push(format!("edit {}", kakoune_string(path)));
+        }
+        let selections = spans
+            .iter()
+            .map(|span| {
+                let start = byte_column(code, span.start_line, span.start_column) + 1;
+                let (end_line, end) = kakoune_end(span, code);
+                format!("{}.{},{}.{}", span.start_line, start, end_line, end)
+            })
+            .collect::<Vec<_>>();
+        if !selections.is_empty() {
+            commands.push(format!("select {}", selections.join(" ")));
+        }
+        if let Some(label) = &options.label {
+            commands.push(format!("echo {}", kakoune_string(label)));
+        }
+        let commands = commands.join("; ");
+        match &self.client {
+            Some(client) => format!(
+                "evaluate-commands -client {} {}\n",
+                kakoune_string(client),
+                kakoune_string(&commands)
+            ),
+            None => format!("{}\n", commands),
+        }
+    }
+}
+
+/// The non-empty spans, sorted with the `deterministic` option.
+fn editor_spans(spans: &[LineColumnSpan], options: &DebugSpanOptions) -> Vec<LineColumnSpan> {
+    let mut spans = spans
+        .iter()
+        .filter(|span| !span.is_empty())
+        .copied()
+        .collect::<Vec<_>>();
+    if options.deterministic {
+        spans.sort();
+    }
+    spans
+}
+
+/// The 0-indexed byte column of a char column, editors index lines by bytes.
+fn byte_column(code: &dyn SourceCode, line_number: usize, column: usize) -> usize {
+    let Some(line) = code.line(line_number) else {
+        return column;
+    };
+    match line.char_indices().nth(column) {
+        Some((offset, _)) => offset,
+        None => line.len() + column - line.chars().count(),
+    }
+}
+
+/// The inclusive 1-indexed end of a span: its last char, or the line ending if the span ends at
+/// the start of a line.
+fn kakoune_end(span: &LineColumnSpan, code: &dyn SourceCode) -> (usize, usize) {
+    match span.end_column {
+        0 => {
+            let line = span.end_line - 1;
+            let len = code.line(line).map_or(0, |line| line.len());
+            (line, len + 1)
+        }
+        column => (
+            span.end_line,
+            byte_column(code, span.end_line, column - 1) + 1,
+        ),
+    }
+}
+
+fn lua_string(text: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn kakoune_string(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NamedSource;
+
+    #[test]
+    fn test_editor_renderers() {
+        let code = NamedSource::new("src/it's.rs", "struct Föo {\n    a: i32,\n}");
+        let spans = [
+            LineColumnSpan::new(2, 4, 3, 0),
+            LineColumnSpan::new(1, 7, 1, 10),
+            LineColumnSpan::new(1, 0, 1, 0),
+        ];
+        let options = DebugSpanOptions {
+            label: Some(String::from("<T> \"here\"")),
+            deterministic: true,
+            ..Default::default()
+        };
+        insta::assert_snapshot!(NeovimRenderer.render(&spans, &code, &options), @r#"<C-\><C-N>:lua vim.cmd("edit " .. vim.fn.fnameescape("src/it's.rs")) local ns = vim.api.nvim_create_namespace("debug_span") vim.api.nvim_buf_clear_namespace(0, ns, 0, -1) vim.api.nvim_buf_set_extmark(0, ns, 0, 7, {end_row = 0, end_col = 11, hl_group = "Search", virt_text = {{"<lt>T> \"here\"", "Comment"}}}) vim.api.nvim_buf_set_extmark(0, ns, 1, 4, {end_row = 2, end_col = 0, hl_group = "Search", virt_text = {{"<lt>T> \"here\"", "Comment"}}}) vim.api.nvim_win_set_cursor(0, {1, 7})<CR>"#);
+        let renderer = KakouneRenderer::default();
+        insta::assert_snapshot!(renderer.render(&spans, &code, &options), @r#"edit 'src/it''s.rs'; select 1.8,1.11 2.5,2.12; echo '<T> "here"'"#);
+        let renderer = KakouneRenderer {
+            client: Some(String::from("client0")),
+        };
+        insta::assert_snapshot!(
+            renderer.render(&spans, &"struct Föo {\n    a: i32,\n}", &DebugSpanOptions::default()),
+            @"evaluate-commands -client 'client0' 'select 2.5,2.12 1.8,1.11'"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{SourceCode, Span};
+use core::fmt;
+
+/// The error returned by [`try_debug_span`](crate::try_debug_span) when a span doesn't point
+/// into the code, e.g. because it belongs to another file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DebugSpanError {
+    /// The code has no lines.
+    EmptySource,
+    /// A line of the span is `0` or past the last line of the code.
+    LineOutOfRange {
+        /// The line of the span.
+        line: usize,
+        /// The number of lines of the code.
+        line_count: usize,
+    },
+    /// A column of the span is past the end of its line.
+    ColumnOutOfRange {
+        /// The line of the span.
+        line: usize,
+        /// The column of the span.
+        column: usize,
+        /// The number of chars of the line.
+        line_len: usize,
+    },
+    /// The span starts after its end.
+    StartAfterEnd {
+        /// The `(line, column)` start of the span.
+        start: (usize, usize),
+        /// The `(line, column)` end of the span.
+        end: (usize, usize),
+    },
+}
+
+impl fmt::Display for DebugSpanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DebugSpanError::EmptySource => write!(f, "the span points into empty code"),
+            DebugSpanError::LineOutOfRange { line, line_count } => {
+                write!(
+                    f,
+                    "line {} is out of range, the code has {} lines",
+                    line, line_count
+                )
+            }
+            DebugSpanError::ColumnOutOfRange {
+                line,
+                column,
+                line_len,
+            } => write!(
+                f,
+                "column {} is out of range, line {} has {} chars",
+                column, line, line_len
+            ),
+            DebugSpanError::StartAfterEnd { start, end } => write!(
+                f,
+                "the span starts at {}:{} after its end at {}:{}",
+                start.0, start.1, end.0, end.1
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DebugSpanError {}
+
+/// Checks that the start and the end of `span` are positions of `code`, in order.
+pub fn check_span(
+    span: &(impl Span + ?Sized),
+    code: &(impl SourceCode + ?Sized),
+) -> Result<(), DebugSpanError> {
+    let line_count = code.line_count();
+    if line_count == 0 {
+        return Err(DebugSpanError::EmptySource);
+    }
+    let start = (span.start_line(), span.start_column());
+    let end = (span.end_line(), span.end_column());
+    let positions = [start, end];
+    for (line, column) in positions {
+        let text = (line > 0).then(|| code.line(line)).flatten();
+        let Some(text) = text else {
+            return Err(DebugSpanError::LineOutOfRange { line, line_count });
+        };
+        let line_len = text.trim_end_matches('\r').chars().count();
+        if column > line_len {
+            return Err(DebugSpanError::ColumnOutOfRange {
+                line,
+                column,
+                line_len,
+            });
+        }
+    }
+    if start > end {
+        return Err(DebugSpanError::StartAfterEnd { start, end });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+
+    #[test]
+    fn test_check_span() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        assert_eq!(check_span(&LineColumnSpan::new(1, 7, 3, 1), code), Ok(()));
+        assert_eq!(check_span(&LineColumnSpan::new(2, 11, 2, 11), code), Ok(()));
+        let span = LineColumnSpan::new(1, 7, 1, 10);
+        assert_eq!(
+            crate::try_debug_span(span, code),
+            Ok(crate::debug_span(span, code))
+        );
+        let reversed = crate::try_debug_span(LineColumnSpan::new(1, 7, 1, 3), "struct Foo;");
+        assert!(matches!(
+            reversed,
+            Err(DebugSpanError::StartAfterEnd { .. })
+        ));
+        let errors = [
+            check_span(&LineColumnSpan::new(1, 7, 1, 10), ""),
+            check_span(&LineColumnSpan::new(2, 4, 5, 1), code),
+            check_span(&LineColumnSpan::new(0, 0, 1, 1), code),
+            check_span(&LineColumnSpan::new(1, 7, 3, 2), code),
+            check_span(&LineColumnSpan::new(1, 7, 1, 3), code),
+            check_span(&LineColumnSpan::new(2, 0, 1, 3), code),
+        ];
+        let messages = errors
+            .iter()
+            .map(|error| alloc::format!("{}\n", error.clone().unwrap_err()))
+            .collect::<alloc::string::String>();
+        insta::assert_snapshot!(messages, @r###"
+        the span points into empty code
+        line 5 is out of range, the code has 3 lines
+        line 0 is out of range, the code has 3 lines
+        column 2 is out of range, line 3 has 1 chars
+        the span starts at 1:7 after its end at 1:3
+        the span starts at 2:0 after its end at 1:3
+        "###);
+    }
+}
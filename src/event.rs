@@ -0,0 +1,180 @@
+use crate::{DebugSpanOptions, LineColumnSpan, SourceCode, Span};
+
+/// A step of a rendering, see [`visit_span`].
+///
+/// The events describe the layout of the built-in renderer, whose text output is a visitor of
+/// these events too. Alternative outputs consuming them stay in line with the text rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Event<'a> {
+    /// The beginning of a frame, with the `--> path:ranges` header.
+    BeginSnippet {
+        path: Option<&'a str>,
+        ranges: &'a str,
+        /// Width of the line numbers of the gutter.
+        gutter_width: usize,
+        /// Width of the provenance tags left of the gutter, `0` without tags.
+        tag_width: usize,
+    },
+    /// A line with an empty gutter, at the top and the bottom of the frame.
+    GutterLine,
+    /// A line of code.
+    CodeLine {
+        /// The displayed line number.
+        line_number: usize,
+        code: &'a str,
+        /// The column of the right side of the box of a multi-line span around the line, if any.
+        box_column: Option<usize>,
+        /// The left margin of the [rustc style](crate::MultiLineStyle::Rustc): `|` on the lines
+        /// of the span, `/` starting it, a space on the other lines of the frame. `None` in the
+        /// box style.
+        margin: Option<char>,
+        /// The [provenance tag](crate::SourceCode::line_tag) of the line, with
+        /// [`DebugSpanOptions::provenance`].
+        tag: Option<&'a str>,
+    },
+    /// A marker under a code line, or the top or bottom side of the box of a multi-line span.
+    Marker {
+        kind: MarkerKind,
+        /// The column of the first character of the marker.
+        column: usize,
+        /// The number of `^` of an underline, or of horizontal characters of a box side.
+        width: usize,
+        label: Option<&'a str>,
+        /// The index of the span in a rendering of several spans, `0` otherwise.
+        index: usize,
+    },
+    /// A `...` row between non-adjacent lines.
+    Ellipsis,
+    /// The end of a frame.
+    EndSnippet,
+}
+
+/// The shape of an [`Event::Marker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarkerKind {
+    /// `^^^` under the marked characters.
+    Underline,
+    /// The top side of the box of a multi-line span, above its first line.
+    BoxTop,
+    /// The bottom side of the box of a multi-line span, under its last line.
+    BoxBottom,
+    /// The `____^` joining the margin of the rustc style to the start of a multi-line span, under
+    /// its first line. The `column` is the one of the `^`.
+    MultiLineStart,
+    /// The `|___^` joining the margin of the rustc style to the end of a multi-line span, under
+    /// its last line. The `column` is the one of the `^`.
+    MultiLineEnd,
+}
+
+/// A consumer of rendering [`Event`]s.
+pub trait Visitor {
+    fn visit(&mut self, event: Event<'_>);
+}
+
+/// Emits the rendering events of a span to `visitor`.
+///
+/// An empty span emits no event, unless the
+/// [`show_empty_spans`](crate::DebugSpanOptions::show_empty_spans) option is set.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{visit_span, DebugSpanOptions, Event, LineColumnSpan, Visitor};
+///
+/// struct LineNumbers(Vec<usize>);
+///
+/// impl Visitor for LineNumbers {
+///     fn visit(&mut self, event: Event<'_>) {
+///         if let Event::CodeLine { line_number, .. } = event {
+///             self.0.push(line_number);
+///         }
+///     }
+/// }
+///
+/// let code = "struct Foo {\n    a: i32,\n}";
+/// let mut visitor = LineNumbers(Vec::new());
+/// let span = LineColumnSpan::new(1, 11, 3, 1);
+/// visit_span(span, code, &DebugSpanOptions::default(), &mut visitor);
+/// assert_eq!(visitor.0, [1, 2, 3]);
+/// ```
+pub fn visit_span(
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+    visitor: &mut impl Visitor,
+) {
+    crate::render::visit_span(&span, code, options, visitor)
+}
+
+/// Emits the rendering events of several spans in a single frame to `visitor`.
+///
+/// Every line touched by a span is shown once, followed by an [underline](MarkerKind::Underline)
+/// per span on that line. Empty spans are skipped, and no event is emitted without spans.
+pub fn visit_spans(
+    spans: &[LineColumnSpan],
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+    visitor: &mut impl Visitor,
+) {
+    crate::multi::visit_spans(spans, code, options, visitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+
+    struct Recorder(String);
+
+    impl Visitor for Recorder {
+        fn visit(&mut self, event: Event<'_>) {
+            self.0.push_str(&format!("{:?}\n", event));
+        }
+    }
+
+    #[test]
+    fn test_visit_span() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        let options = DebugSpanOptions {
+            label: Some("expected ident".to_string()),
+            ..Default::default()
+        };
+        let mut recorder = Recorder(String::new());
+        visit_span(
+            LineColumnSpan::new(1, 11, 3, 1),
+            code,
+            &options,
+            &mut recorder,
+        );
+        insta::assert_snapshot!(recorder.0, @r###"
+        BeginSnippet { path: None, ranges: "1:11..3:1", gutter_width: 1, tag_width: 0 }
+        GutterLine
+        Marker { kind: BoxTop, column: 11, width: 4, label: None, index: 0 }
+        CodeLine { line_number: 1, code: "struct Foo {", box_column: Some(16), margin: None, tag: None }
+        CodeLine { line_number: 2, code: "    a: i32,", box_column: Some(16), margin: None, tag: None }
+        CodeLine { line_number: 3, code: "}", box_column: Some(16), margin: None, tag: None }
+        Marker { kind: BoxBottom, column: 0, width: 15, label: Some("expected ident"), index: 0 }
+        GutterLine
+        EndSnippet
+        "###);
+
+        let spans = [
+            LineColumnSpan::new(1, 7, 1, 10),
+            LineColumnSpan::new(3, 0, 3, 1),
+        ];
+        let mut recorder = Recorder(String::new());
+        visit_spans(&spans, code, &DebugSpanOptions::default(), &mut recorder);
+        insta::assert_snapshot!(recorder.0, @r###"
+        BeginSnippet { path: None, ranges: "1:7..1:10, 3:0..3:1", gutter_width: 1, tag_width: 0 }
+        GutterLine
+        CodeLine { line_number: 1, code: "struct Foo {", box_column: None, margin: None, tag: None }
+        Marker { kind: Underline, column: 7, width: 3, label: None, index: 0 }
+        Ellipsis
+        CodeLine { line_number: 3, code: "}", box_column: None, margin: None, tag: None }
+        Marker { kind: Underline, column: 0, width: 1, label: None, index: 1 }
+        GutterLine
+        EndSnippet
+        "###);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{NamedSource, SourceText, Span};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+type FileCache = Mutex<HashMap<PathBuf, Arc<NamedSource<SourceText>>>>;
+
+/// Read the file at `path` and render `span` against it, with the path in the header.
+///
+/// The file content is cached for the lifetime of the process, so repeated calls against the same
+/// fixture only read it once. Invalid UTF-8 is shown with replacement characters, see
+/// [`SourceText::from_bytes_lossy`].
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span_in_file, LineColumnSpan};
+///
+/// let output = debug_span_in_file(LineColumnSpan::new(1, 0, 1, 4), "Cargo.toml").unwrap();
+/// assert_eq!(output, " --> Cargo.toml:1:0..1:4\n  |\n1 | [package]\n  | ^^^^\n  |\n");
+/// ```
+pub fn debug_span_in_file(span: impl Span, path: impl AsRef<Path>) -> io::Result<String> {
+    let source = load_file(path.as_ref())?;
+    Ok(crate::internal::debug_span(&span, &*source))
+}
+
+fn load_file(path: &Path) -> io::Result<Arc<NamedSource<SourceText>>> {
+    static FILES: OnceLock<FileCache> = OnceLock::new();
+    let files = FILES.get_or_init(Default::default);
+    if let Some(source) = files.lock().unwrap().get(path) {
+        return Ok(source.clone());
+    }
+    let code = std::fs::read(path)?;
+    let source = Arc::new(NamedSource::new(
+        path.display().to_string(),
+        SourceText::from_bytes_lossy(&code),
+    ));
+    files
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), source.clone());
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+
+    #[test]
+    fn test_debug_span_in_file() {
+        let path = std::env::temp_dir().join(format!("debug-span-{}.rs", std::process::id()));
+        std::fs::write(&path, "struct Foo;").unwrap();
+        let span = LineColumnSpan::new(1, 7, 1, 10);
+        let output = debug_span_in_file(span, &path).unwrap();
+        assert_eq!(
+            output,
+            format!(
+                " --> {}:1:7..1:10\n  |\n1 | struct Foo;\n  |        ^^^\n  |\n",
+                path.display()
+            )
+        );
+
+        std::fs::write(&path, "struct Bar;").unwrap();
+        assert_eq!(debug_span_in_file(span, &path).unwrap(), output);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_debug_span_in_missing_file() {
+        let error = debug_span_in_file(LineColumnSpan::new(1, 0, 1, 1), "missing.rs").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+}
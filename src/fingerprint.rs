@@ -0,0 +1,172 @@
+use crate::{DebugSpanOptions, LineColumnSpan, SourceCode, Span};
+use alloc::string::String;
+use core::fmt;
+
+/// A hash of a source code, identifying the text a span was created for.
+///
+/// It is computed with FNV-1a over the lines of the source, so it is stable across platforms and
+/// releases, and can be stored in snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fingerprint(pub u64);
+
+impl Fingerprint {
+    /// Compute the fingerprint of `code`.
+    pub fn of(code: &(impl SourceCode + ?Sized)) -> Self {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        let mut hash = OFFSET_BASIS;
+        for line_number in 1..=code.line_count() {
+            let line = code.line(line_number).unwrap_or_default();
+            for byte in line.bytes().chain([b'\n']) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(PRIME);
+            }
+        }
+        Self(hash)
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// An owned span paired with the [`Fingerprint`] of the code it was created for.
+///
+/// Rendering it against another string, e.g. a stale copy of a fixture, returns a
+/// [`FingerprintMismatch`] instead of a misleading snippet.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{FingerprintedSpan, LineColumnSpan};
+///
+/// let code = "struct Foo;";
+/// let span = FingerprintedSpan::new(LineColumnSpan::new(1, 7, 1, 10), code);
+/// assert!(span.try_debug(code).is_ok());
+///
+/// let error = span.try_debug("struct Bar;").unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "span 1:7..1:10 was created for source 4f482d83140eb189, but rendered against source 98c0e7b269deb6a0"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FingerprintedSpan {
+    pub span: LineColumnSpan,
+    pub fingerprint: Fingerprint,
+}
+
+impl FingerprintedSpan {
+    /// Capture `span` together with the fingerprint of `code`.
+    pub fn new(span: impl Span, code: &(impl SourceCode + ?Sized)) -> Self {
+        Self {
+            span: LineColumnSpan::from_span(&span),
+            fingerprint: Fingerprint::of(code),
+        }
+    }
+
+    /// Check that `code` is the source the span was created for.
+    pub fn check(&self, code: &(impl SourceCode + ?Sized)) -> Result<(), FingerprintMismatch> {
+        let actual = Fingerprint::of(code);
+        if actual == self.fingerprint {
+            Ok(())
+        } else {
+            Err(FingerprintMismatch {
+                span: self.span,
+                expected: self.fingerprint,
+                actual,
+            })
+        }
+    }
+
+    /// Render the span after checking the fingerprint of `code`.
+    pub fn try_debug(
+        &self,
+        code: &(impl SourceCode + ?Sized),
+    ) -> Result<String, FingerprintMismatch> {
+        self.try_debug_with_options(code, &DebugSpanOptions::default())
+    }
+
+    /// Render the span with custom options after checking the fingerprint of `code`.
+    pub fn try_debug_with_options(
+        &self,
+        code: &(impl SourceCode + ?Sized),
+        options: &DebugSpanOptions,
+    ) -> Result<String, FingerprintMismatch> {
+        self.check(code)?;
+        Ok(crate::internal::debug_span_with_options(
+            &self.span, code, options,
+        ))
+    }
+}
+
+impl Span for FingerprintedSpan {
+    fn start_line(&self) -> usize {
+        self.span.start_line
+    }
+    fn end_line(&self) -> usize {
+        self.span.end_line
+    }
+    fn start_column(&self) -> usize {
+        self.span.start_column
+    }
+    fn end_column(&self) -> usize {
+        self.span.end_column
+    }
+}
+
+/// The error returned when a [`FingerprintedSpan`] is rendered against the wrong source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FingerprintMismatch {
+    pub span: LineColumnSpan,
+    pub expected: Fingerprint,
+    pub actual: Fingerprint,
+}
+
+impl fmt::Display for FingerprintMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "span {} was created for source {}, but rendered against source {}",
+            self.span.to_range(),
+            self.expected,
+            self.actual
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FingerprintMismatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NamedSource, SourceText};
+
+    #[test]
+    fn test_fingerprint() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        assert_eq!(
+            Fingerprint::of(code),
+            Fingerprint::of(&SourceText::new(code))
+        );
+        assert_eq!(
+            Fingerprint::of(code),
+            Fingerprint::of(&NamedSource::new("foo.rs", code))
+        );
+        assert_ne!(Fingerprint::of(code), Fingerprint::of("struct Foo;"));
+        insta::assert_snapshot!(Fingerprint::of(code), @"a92e4d16f40e6ac9");
+    }
+
+    #[test]
+    fn test_fingerprinted_span() {
+        let code = "struct Foo;";
+        let span = FingerprintedSpan::new(LineColumnSpan::new(1, 7, 1, 10), code);
+        assert_eq!(span.try_debug(code).unwrap(), crate::debug_span(span, code));
+        let error = span.try_debug("struct Bar;").unwrap_err();
+        assert_eq!(error.expected, span.fingerprint);
+        insta::assert_snapshot!(error, @"span 1:7..1:10 was created for source 4f482d83140eb189, but rendered against source 98c0e7b269deb6a0");
+    }
+}
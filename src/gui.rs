@@ -0,0 +1,134 @@
+use crate::{extract_snippet, DebugSpanOptions, SourceCode, Span};
+use alloc::format;
+use alloc::string::ToString;
+use egui::text::{LayoutJob, TextFormat};
+use egui::{Color32, FontId, Label, Response, TextStyle, Ui};
+
+/// Paint the snippet of a span into an egui [`Ui`], behind the `egui` feature.
+///
+/// The code is laid out in the monospace font of the style. The span is highlighted with a
+/// rectangle in the first color of the [theme](DebugSpanOptions::theme), and the
+/// [label](DebugSpanOptions::label) is shown under its last line.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use debug_span::{paint_span, DebugSpanOptions, LineColumnSpan};
+///
+/// fn show(ui: &mut egui::Ui) {
+///     let code = "struct Foo {\n    a: i32,\n}";
+///     let options = DebugSpanOptions::default();
+///     paint_span(ui, LineColumnSpan::new(2, 4, 2, 5), code, &options);
+/// }
+/// ```
+pub fn paint_span(
+    ui: &mut Ui,
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> Response {
+    let font_id = TextStyle::Monospace.resolve(ui.style());
+    let text_color = ui.visuals().text_color();
+    let job = layout_job(span, code, options, font_id, text_color);
+    ui.add(Label::new(job).extend())
+}
+
+fn layout_job(
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+    font_id: FontId,
+    text_color: Color32,
+) -> LayoutJob {
+    let palette = options.theme.unwrap_or_default().palette();
+    let color = |color: crate::Color| Color32::from_rgb(color.rgb.0, color.rgb.1, color.rgb.2);
+    let label_color = color(palette.label(0));
+    let text = TextFormat::simple(font_id.clone(), text_color);
+    let gutter = TextFormat::simple(font_id.clone(), color(palette.gutter));
+    let marked = TextFormat {
+        background: label_color.gamma_multiply(0.35),
+        ..text.clone()
+    };
+    let label = TextFormat::simple(font_id, label_color);
+
+    let snippet = extract_snippet(span, code, options.context);
+    let width = snippet.last_line().to_string().len();
+    let mut job = LayoutJob::default();
+    for (index, (line_number, line)) in snippet.numbered_lines().enumerate() {
+        let gutter_text = format!("{: >width$} | ", line_number, width = width);
+        job.append(&gutter_text, 0.0, gutter.clone());
+        match snippet.marked_columns(index) {
+            Some((start, end)) => {
+                let start = byte_offset(line, start);
+                let end = byte_offset(line, end).max(start);
+                job.append(&line[..start], 0.0, text.clone());
+                job.append(&line[start..end], 0.0, marked.clone());
+                job.append(&line[end..], 0.0, text.clone());
+            }
+            None => job.append(line, 0.0, text.clone()),
+        }
+        job.append("\n", 0.0, text.clone());
+        if let (Some(label_text), true) = (&options.label, index + 1 == snippet.span.end_line) {
+            let (column, label_row) = snippet.label_row(label_text);
+            job.append(
+                &format!("{:width$} | ", "", width = width),
+                0.0,
+                gutter.clone(),
+            );
+            job.append(&" ".repeat(column), 0.0, text.clone());
+            job.append(&label_row, 0.0, label.clone());
+            job.append("\n", 0.0, text.clone());
+        }
+    }
+    job
+}
+
+/// Byte offset of the character at `column`, clamped to the end of the line.
+fn byte_offset(line: &str, column: usize) -> usize {
+    line.char_indices()
+        .nth(column)
+        .map_or(line.len(), |(offset, _)| offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_layout_job() {
+        let code = "struct Foo {\n    a: i32,\n    b: i32,\n}";
+        let options = DebugSpanOptions {
+            label: Some("expected ident".to_string()),
+            ..Default::default()
+        };
+        let job = layout_job(
+            LineColumnSpan::new(1, 11, 2, 5),
+            code,
+            &options,
+            FontId::monospace(12.0),
+            Color32::WHITE,
+        );
+        insta::assert_snapshot!(job.text, @r###"
+        1 | struct Foo {
+        2 |     a: i32,
+          |     ^ expected ident
+        "###);
+        let highlighted = job
+            .sections
+            .iter()
+            .filter(|section| section.format.background != Color32::TRANSPARENT)
+            .map(|section| &job.text[section.byte_range.clone()])
+            .collect::<Vec<_>>();
+        assert_eq!(highlighted, ["{", "    a"]);
+        let labels = job
+            .sections
+            .iter()
+            .filter(|section| section.format.color == Color32::from_rgb(224, 108, 117))
+            .map(|section| String::from(&job.text[section.byte_range.clone()]))
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["^ expected ident"]);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::renderer::escape_html;
+use crate::{Highlight, Highlighter};
+
+/// Marks the start and the end of highlighted HTML in a rendering, see [`finish`].
+const HTML_START: char = '\u{E000}';
+const HTML_END: char = '\u{E001}';
+
+/// Highlight a single line of Rust code.
+///
+/// Lines are highlighted on their own, so constructs spanning several lines, like block
+/// comments, may be highlighted incorrectly.
+pub fn highlight_line(line: &str, highlight: Highlight, highlighter: Highlighter) -> String {
+    match highlighter {
+        #[cfg(feature = "syntect")]
+        Highlighter::Syntect => syntect_backend::highlight_line(line, highlight),
+        #[cfg(feature = "tree-sitter")]
+        Highlighter::TreeSitter => tree_sitter_backend::highlight_line(line, highlight),
+    }
+}
+
+#[cfg(feature = "syntect")]
+mod syntect_backend {
+    use super::{HTML_END, HTML_START};
+    use crate::Highlight;
+    use std::sync::OnceLock;
+    use syntect::easy::HighlightLines;
+    use syntect::highlighting::{Theme, ThemeSet};
+    use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+    use syntect::parsing::SyntaxSet;
+    use syntect::util::as_24_bit_terminal_escaped;
+
+    fn syntax_set() -> &'static SyntaxSet {
+        static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+        SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_nonewlines)
+    }
+
+    fn theme() -> &'static Theme {
+        static THEME: OnceLock<Theme> = OnceLock::new();
+        THEME.get_or_init(|| {
+            let mut themes = ThemeSet::load_defaults().themes;
+            themes.remove("base16-ocean.dark").unwrap()
+        })
+    }
+
+    pub fn highlight_line(line: &str, highlight: Highlight) -> String {
+        let syntax_set = syntax_set();
+        let syntax = syntax_set
+            .find_syntax_by_extension("rs")
+            .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+        let ranges = match HighlightLines::new(syntax, theme()).highlight_line(line, syntax_set) {
+            Ok(ranges) => ranges,
+            Err(_) => return line.to_string(),
+        };
+        match highlight {
+            Highlight::Ansi => format!("{}\x1b[0m", as_24_bit_terminal_escaped(&ranges, false)),
+            Highlight::Html => {
+                match styled_line_to_highlighted_html(&ranges, IncludeBackground::No) {
+                    Ok(html) => format!("{}{}{}", HTML_START, html, HTML_END),
+                    Err(_) => line.to_string(),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tree-sitter")]
+mod tree_sitter_backend {
+    use super::{HTML_END, HTML_START};
+    use crate::renderer::escape_html;
+    use crate::Highlight;
+    use std::fmt::Write;
+    use std::sync::OnceLock;
+    use tree_sitter_highlight::{HighlightConfiguration, HighlightEvent, Highlighter};
+
+    type Rgb = (u8, u8, u8);
+
+    /// Highlight names recognized in the tree-sitter-rust queries, and their color in the
+    /// `base16-ocean.dark` palette. Names are matched by prefix, e.g. `function.macro` is a
+    /// `function`.
+    const NAMES: [(&str, Rgb); 10] = [
+        ("attribute", (0xd0, 0x87, 0x70)),
+        ("comment", (0x65, 0x73, 0x7e)),
+        ("constant", (0xd0, 0x87, 0x70)),
+        ("constructor", (0xeb, 0xcb, 0x8b)),
+        ("escape", (0x96, 0xb5, 0xb4)),
+        ("function", (0x8f, 0xa1, 0xb3)),
+        ("keyword", (0xb4, 0x8e, 0xad)),
+        ("label", (0xd0, 0x87, 0x70)),
+        ("string", (0xa3, 0xbe, 0x8c)),
+        ("type", (0xeb, 0xcb, 0x8b)),
+    ];
+
+    fn configuration() -> &'static HighlightConfiguration {
+        static CONFIGURATION: OnceLock<HighlightConfiguration> = OnceLock::new();
+        CONFIGURATION.get_or_init(|| {
+            let mut configuration = HighlightConfiguration::new(
+                tree_sitter_rust::LANGUAGE.into(),
+                "rust",
+                tree_sitter_rust::HIGHLIGHTS_QUERY,
+                tree_sitter_rust::INJECTIONS_QUERY,
+                "",
+            )
+            .unwrap();
+            configuration.configure(&NAMES.map(|(name, _)| name));
+            configuration
+        })
+    }
+
+    /// Splits a line in segments, with the color of the innermost highlight of each segment.
+    fn segments(line: &str) -> Option<Vec<(Option<Rgb>, &str)>> {
+        let mut highlighter = Highlighter::new();
+        let events = highlighter
+            .highlight(configuration(), line.as_bytes(), None, |_| None)
+            .ok()?;
+        let mut segments = Vec::new();
+        let mut stack = Vec::new();
+        for event in events {
+            match event.ok()? {
+                HighlightEvent::Source { start, end } => {
+                    let color = stack.last().map(|&index: &usize| NAMES[index].1);
+                    segments.push((color, line.get(start..end)?));
+                }
+                HighlightEvent::HighlightStart(highlight) => stack.push(highlight.0),
+                HighlightEvent::HighlightEnd => {
+                    stack.pop();
+                }
+            }
+        }
+        Some(segments)
+    }
+
+    pub fn highlight_line(line: &str, highlight: Highlight) -> String {
+        let Some(segments) = segments(line) else {
+            return line.to_string();
+        };
+        let mut output = String::new();
+        if highlight == Highlight::Html {
+            output.push(HTML_START);
+        }
+        for (color, text) in segments {
+            match (highlight, color) {
+                (Highlight::Ansi, Some((r, g, b))) => {
+                    write!(output, "\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, text).unwrap();
+                }
+                (Highlight::Html, Some((r, g, b))) => {
+                    write!(
+                        output,
+                        "<span style=\"color:#{:02x}{:02x}{:02x};\">{}</span>",
+                        r,
+                        g,
+                        b,
+                        escape_html(text),
+                    )
+                    .unwrap();
+                }
+                (Highlight::Ansi, None) => output.push_str(text),
+                (Highlight::Html, None) => output.push_str(&escape_html(text)),
+            }
+        }
+        if highlight == Highlight::Html {
+            output.push(HTML_END);
+        }
+        output
+    }
+}
+
+/// Finish a highlighted rendering: in HTML, everything but the highlighted code is escaped.
+pub fn finish(output: String, highlight: Highlight) -> String {
+    match highlight {
+        Highlight::Ansi => output,
+        Highlight::Html => {
+            let mut result = String::with_capacity(output.len());
+            for (index, part) in output.split([HTML_START, HTML_END]).enumerate() {
+                if index % 2 == 0 {
+                    result.push_str(&escape_html(part));
+                } else {
+                    result.push_str(part);
+                }
+            }
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{debug_span_with_options, DebugSpanOptions, Highlight, LineColumnSpan};
+
+    #[test]
+    #[cfg(feature = "syntect")]
+    fn test_highlight() {
+        let code = "struct Foo<T> {\n    a: T,\n}";
+        let span = LineColumnSpan::new(1, 7, 2, 8);
+        let options = DebugSpanOptions {
+            highlight: Some(Highlight::Html),
+            label: Some("<generic>".to_string()),
+            ..Default::default()
+        };
+        let output = debug_span_with_options(span, code, &options);
+        insta::assert_snapshot!(output, @r###"
+         --&gt; 1:7..2:8
+          |
+          |        ┌───────────╮
+        1 | <span style="color:#b48ead;">struct </span><span style="color:#c0c5ce;">Foo&lt;T&gt; {</span>    │
+        2 | <span style="color:#c0c5ce;">    a: T,</span>          │
+          |        └───────────╯ &lt;generic&gt;
+          |
+        "###);
+
+        let options = DebugSpanOptions {
+            highlight: Some(Highlight::Ansi),
+            ..Default::default()
+        };
+        let output = debug_span_with_options(LineColumnSpan::new(1, 7, 1, 10), code, &options);
+        insta::assert_snapshot!(output.replace('\x1b', "\\e"), @r###"
+         --> 1:7..1:10
+          |
+        1 | \e[38;2;180;142;173mstruct\e[38;2;192;197;206m \e[38;2;192;197;206mFoo\e[38;2;192;197;206m<\e[38;2;192;197;206mT\e[38;2;192;197;206m>\e[38;2;192;197;206m \e[38;2;192;197;206m{\e[0m
+          |        ^^^
+          |
+        "###);
+
+        let options = DebugSpanOptions {
+            deterministic: true,
+            ..options
+        };
+        let output = debug_span_with_options(LineColumnSpan::new(1, 7, 1, 10), code, &options);
+        assert!(!output.contains('\x1b'));
+    }
+    #[test]
+    #[cfg(feature = "tree-sitter")]
+    fn test_tree_sitter() {
+        let code = "#[derive(Debug)]\nstruct Foo<T> { a: &'static str } // <T>";
+        let options = DebugSpanOptions {
+            highlight: Some(Highlight::Html),
+            highlighter: crate::Highlighter::TreeSitter,
+            ..Default::default()
+        };
+        let output = debug_span_with_options(LineColumnSpan::new(2, 7, 2, 10), code, &options);
+        insta::assert_snapshot!(output, @r###"
+         --&gt; 2:7..2:10
+          |
+        2 | <span style="color:#b48ead;">struct</span> <span style="color:#ebcb8b;">Foo</span>&lt;<span style="color:#ebcb8b;">T</span>&gt; { a: &amp;'<span style="color:#d08770;">static</span> <span style="color:#ebcb8b;">str</span> } <span style="color:#65737e;">// &lt;T&gt;</span>
+          |        ^^^
+          |
+        "###);
+
+        let options = DebugSpanOptions {
+            highlight: Some(Highlight::Ansi),
+            ..options
+        };
+        let output = debug_span_with_options(LineColumnSpan::new(1, 2, 1, 8), code, &options);
+        insta::assert_snapshot!(output.replace('\x1b', "\\e"), @r###"
+         --> 1:2..1:8
+          |
+        1 | \e[38;2;208;135;112m#[derive(\e[0m\e[38;2;235;203;139mDebug\e[0m\e[38;2;208;135;112m)]\e[0m
+          |   ^^^^^^
+          |
+        "###);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::Span;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+/// A `proc_macro2::Span` remembering how it was built.
+///
+/// The hygiene of a `proc_macro2::Span` can't be inspected. Building spans through a `TracedSpan`
+/// records where their location and their hygiene come from, for [`debug_span_hygiene`].
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span_hygiene, TracedSpan};
+///
+/// let input = "struct Foo;";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+/// let ident = TracedSpan::input(derive_input.ident.span());
+/// let span = ident.resolved_at(TracedSpan::mixed_site());
+/// assert_eq!(
+///     debug_span_hygiene(&span),
+///     "built as: input(1:7..1:10).resolved_at(Span::mixed_site())\n\
+///      location: input(1:7..1:10)\n\
+///      hygiene: Span::mixed_site()\n"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct TracedSpan {
+    span: proc_macro2::Span,
+    origin: Origin,
+}
+
+#[derive(Debug, Clone)]
+enum Origin {
+    Input,
+    CallSite,
+    MixedSite,
+    ResolvedAt(Box<TracedSpan>, Box<TracedSpan>),
+    LocatedAt(Box<TracedSpan>, Box<TracedSpan>),
+}
+
+impl TracedSpan {
+    /// A span from the macro input, or any other span built elsewhere.
+    pub fn input(span: proc_macro2::Span) -> Self {
+        Self {
+            span,
+            origin: Origin::Input,
+        }
+    }
+
+    /// See `proc_macro2::Span::call_site`.
+    pub fn call_site() -> Self {
+        Self {
+            span: proc_macro2::Span::call_site(),
+            origin: Origin::CallSite,
+        }
+    }
+
+    /// See `proc_macro2::Span::mixed_site`.
+    pub fn mixed_site() -> Self {
+        Self {
+            span: proc_macro2::Span::mixed_site(),
+            origin: Origin::MixedSite,
+        }
+    }
+
+    /// The location of `self` with the hygiene of `other`, see `proc_macro2::Span::resolved_at`.
+    pub fn resolved_at(&self, other: TracedSpan) -> Self {
+        Self {
+            span: self.span.resolved_at(other.span),
+            origin: Origin::ResolvedAt(Box::new(self.clone()), Box::new(other)),
+        }
+    }
+
+    /// The location of `other` with the hygiene of `self`, see `proc_macro2::Span::located_at`.
+    pub fn located_at(&self, other: TracedSpan) -> Self {
+        Self {
+            span: self.span.located_at(other.span),
+            origin: Origin::LocatedAt(Box::new(self.clone()), Box::new(other)),
+        }
+    }
+
+    /// The built span.
+    pub fn span(&self) -> proc_macro2::Span {
+        self.span
+    }
+
+    /// The span providing the location.
+    fn location(&self) -> &TracedSpan {
+        match &self.origin {
+            Origin::ResolvedAt(span, _) => span.location(),
+            Origin::LocatedAt(_, other) => other.location(),
+            _ => self,
+        }
+    }
+
+    /// The span providing the hygiene.
+    fn hygiene(&self) -> &TracedSpan {
+        match &self.origin {
+            Origin::ResolvedAt(_, other) => other.hygiene(),
+            Origin::LocatedAt(span, _) => span.hygiene(),
+            _ => self,
+        }
+    }
+}
+
+impl fmt::Display for TracedSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.origin {
+            Origin::Input => write!(f, "input({})", self.span.to_range()),
+            Origin::CallSite => write!(f, "Span::call_site()"),
+            Origin::MixedSite => write!(f, "Span::mixed_site()"),
+            Origin::ResolvedAt(span, other) => write!(f, "{}.resolved_at({})", span, other),
+            Origin::LocatedAt(span, other) => write!(f, "{}.located_at({})", span, other),
+        }
+    }
+}
+
+impl Span for TracedSpan {
+    fn start_line(&self) -> usize {
+        self.span.start_line()
+    }
+    fn end_line(&self) -> usize {
+        self.span.end_line()
+    }
+    fn start_column(&self) -> usize {
+        self.span.start_column()
+    }
+    fn end_column(&self) -> usize {
+        self.span.end_column()
+    }
+}
+
+/// Describe how a [`TracedSpan`] was built, and which spans its location and its hygiene come
+/// from.
+///
+/// See [`TracedSpan`] for an example.
+pub fn debug_span_hygiene(span: &TracedSpan) -> String {
+    format!(
+        "built as: {}\nlocation: {}\nhygiene: {}\n",
+        span,
+        span.location(),
+        span.hygiene()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_span_hygiene() {
+        let tokens: proc_macro2::TokenStream = "struct Foo;".parse().unwrap();
+        let spans = tokens
+            .into_iter()
+            .map(|token| TracedSpan::input(token.span()))
+            .collect::<alloc::vec::Vec<_>>();
+        let span = TracedSpan::call_site()
+            .located_at(spans[1].clone())
+            .resolved_at(spans[0].located_at(TracedSpan::mixed_site()));
+        insta::assert_snapshot!(debug_span_hygiene(&span), @r###"
+        built as: Span::call_site().located_at(input(1:7..1:10)).resolved_at(input(1:0..1:6).located_at(Span::mixed_site()))
+        location: input(1:7..1:10)
+        hygiene: input(1:0..1:6)
+        "###);
+        assert_eq!(span.to_range(), "1:7..1:10");
+    }
+}
@@ -37,10 +37,188 @@
 //! "###);
 //! ```
 //!
+//! # Features
+//!
+//! - `std` (default): printing macros like [`print_span!`] and [`dbg_span!`], and
+//!   [`debug_span_in_file`]. Without it, the crate is `no_std` and only requires `alloc`.
+//! - `proc-macro2` (default): implement [`Span`] for `proc_macro2::Span`.
+//! - `color`: `ColorChoice` coloring the output of the stderr macros and of [`assert_span_eq!`]
+//!   when it goes to a terminal, unless `NO_COLOR` is set.
+//! - `unicode-width`: align the markers and the box with the terminal columns of wide chars like
+//!   CJK and emoji, instead of counting one column per char.
+//! - `syn`: extension traits for syn AST nodes and results, and `enclosing_item_span`.
+//! - `macros`: the `debug_span_here!` companion proc-macro, and the `#[debug_spans]` attribute
+//!   tracing proc-macro entry points.
+//! - `config`: `DebugSpanOptions::from_toml` loading shared options from a TOML file.
+//! - `dump`: `SpanDump` recording spans to a file during macro expansion.
+//! - `syntect`: syntax highlighting of the code lines with `DebugSpanOptions::highlight`.
+//! - `tree-sitter`: a lighter highlighting backend based on tree-sitter-rust, see `Highlighter`.
+//! - `egui`: `paint_span` painting a span in egui GUIs.
+//! - `ratatui`: `SpanWidget` rendering a span in ratatui TUIs.
+//! - `wasm`: `render_span` and `render_span_html` exported with wasm-bindgen, for web
+//!   playgrounds.
+//! - `windows-console`: `enable_virtual_terminal` and `console_options` for legacy Windows
+//!   consoles; printed spans fall back to ASCII without colors there.
+//! - `miette` and `ariadne`: `Theme::from_miette` and `Theme::from_ariadne` reusing the colors
+//!   of these diagnostic libraries.
+//! - `cli`: the `debug-span` command line tool rendering a span of a file.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+
+mod adjust;
+mod assert;
+#[cfg(feature = "proc-macro2")]
+mod audit;
+mod builder;
+mod checker;
+#[cfg(feature = "syn")]
+mod collector;
+mod color;
+#[cfg(feature = "config")]
+mod config;
+#[cfg(feature = "windows-console")]
+mod console;
+mod coordinate;
+#[cfg(feature = "dump")]
+mod dump;
+mod editor;
+mod error;
+mod event;
+#[cfg(feature = "std")]
+mod file;
+mod fingerprint;
+#[cfg(feature = "egui")]
+mod gui;
+#[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+mod highlight;
+#[cfg(feature = "proc-macro2")]
+mod hygiene;
+mod line_column;
+mod link;
+#[cfg(feature = "syn")]
+mod literal;
+mod mapper;
+mod markdown;
+mod measure;
+mod messages;
+mod minimap;
+mod multi;
+mod offset;
+mod options;
+mod paginator;
+pub mod prelude;
+mod print;
+mod prose;
+mod reader;
+mod redact;
+mod render;
+mod renderer;
+mod side_by_side;
+#[cfg(feature = "std")]
+mod sink;
+mod snippet;
+mod source;
+mod source_map;
+mod span_ext;
+mod span_set;
+mod stats;
+#[cfg(feature = "syn")]
+mod syn_ext;
+mod template;
+mod text;
+#[cfg(any(feature = "miette", feature = "ariadne"))]
+mod theme_import;
+#[cfg(feature = "proc-macro2")]
+mod token_stream;
+#[cfg(feature = "proc-macro2")]
+mod trace;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "ratatui")]
+mod widget;
+
+pub use adjust::{expand, shrink, to_full_lines, translate, trim_span};
+pub use assert::{location_cmp, same_location};
+#[cfg(feature = "proc-macro2")]
+pub use audit::{audit_respanning, AuditedToken, RespanAudit, SpanOrigin};
+pub use builder::{debug, DebugSpanBuilder};
+pub use checker::{SpanCheck, SpanChecker};
+#[cfg(feature = "syn")]
+pub use collector::{NodeKind, SpanCollector};
+#[cfg(feature = "color")]
+pub use color::ColorChoice;
+pub use color::{Color, ColorDepth, Palette, Theme};
+#[cfg(feature = "config")]
+pub use config::ConfigError;
+#[cfg(feature = "windows-console")]
+pub use console::{console_options, enable_virtual_terminal};
+pub use coordinate::{OneBased, Position, ZeroBased};
+#[cfg(feature = "dump")]
+pub use dump::{SpanDump, SpanRecord};
+pub use editor::{KakouneRenderer, NeovimRenderer};
+pub use error::DebugSpanError;
+pub use event::{visit_span, visit_spans, Event, MarkerKind, Visitor};
+#[cfg(feature = "std")]
+pub use file::debug_span_in_file;
+pub use fingerprint::{Fingerprint, FingerprintMismatch, FingerprintedSpan};
+#[cfg(feature = "egui")]
+pub use gui::paint_span;
+#[cfg(feature = "proc-macro2")]
+pub use hygiene::{debug_span_hygiene, TracedSpan};
+pub use line_column::{dedup_spans, sort_spans, CapturedSpan, LineColumnSpan, ParseSpanError};
+pub use link::SourceLink;
+#[cfg(feature = "syn")]
+pub use literal::{doc_subspan, literal_subspan};
+pub use mapper::SpanMapper;
+pub use markdown::{code_blocks, debug_span_in_markdown, BlockSelector, CodeBlock};
+pub use measure::measure;
+pub use messages::Messages;
+pub use minimap::{minimap, minimap_with_options};
+pub use offset::OffsetSpan;
+pub use options::{Charset, DebugSpanOptions, MultiLineStyle, OutputVersion};
+#[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+pub use options::{Highlight, Highlighter};
+pub use paginator::Paginator;
+pub use print::panic_with_span;
+pub use reader::debug_span_from_lines;
+#[cfg(feature = "std")]
+pub use reader::debug_span_from_reader;
+pub use redact::{redact_line_numbers, relative_line_numbers};
+pub use renderer::{AnsiRenderer, HtmlRenderer, Renderer, SvgRenderer, TextRenderer};
+pub use side_by_side::side_by_side;
+pub use snippet::{extract_snippet, Snippet};
+pub use source::{NamedSource, RemappedSource, SourceCode, SourceText, TaggedSource};
+pub use source_map::{FileId, SourceMap};
+pub use span_ext::{distance, overlap_chars, overlap_lines, SpanExt};
+pub use span_set::SpanSet;
+pub use stats::SpanStats;
+#[cfg(feature = "syn")]
+pub use syn_ext::{
+    debug_all, debug_span_with_structure, enclosing_item_span, ResultExt, SpannedExt,
+};
+pub use template::{Template, TemplateError};
+pub use text::{find_nth_span, find_span, span_text, to_byte_range};
+#[cfg(feature = "proc-macro2")]
+pub use token_stream::{snap_to_tokens, TokenSpans, TokenStreamExt};
+#[cfg(feature = "wasm")]
+pub use wasm::{render_span, render_span_html};
+#[cfg(feature = "ratatui")]
+pub use widget::SpanWidget;
+
+#[cfg(feature = "macros")]
+pub use debug_span_macros::debug_span_here;
+#[cfg(all(feature = "macros", feature = "proc-macro2"))]
+pub use debug_span_macros::debug_spans;
 
 /// A trait for types that represent a span in the source code.
 ///
-/// This trait is implemented for `proc_macro2::Span`
+/// This trait is implemented for `proc_macro2::Span`, [`LineColumnSpan`], [`CapturedSpan`] and
+/// references to any type implementing it.
 pub trait Span {
     fn start_line(&self) -> usize;
     fn end_line(&self) -> usize;
@@ -75,12 +253,28 @@ pub trait Span {
 
     /// Generate a debug representation of the span and the source code it points to.
     ///
-    /// see [`debug_span`] for more information.
+    /// It takes the code as a `&str` so that it is also available on `dyn Span`, use
+    /// [`debug_span`] for other [`SourceCode`].
     fn debug(&self, code: &str) -> String {
         internal::debug_span(self, code)
     }
 }
 
+impl<T: Span + ?Sized> Span for &T {
+    fn start_line(&self) -> usize {
+        (**self).start_line()
+    }
+    fn end_line(&self) -> usize {
+        (**self).end_line()
+    }
+    fn start_column(&self) -> usize {
+        (**self).start_column()
+    }
+    fn end_column(&self) -> usize {
+        (**self).end_column()
+    }
+}
+
 #[cfg(feature = "proc-macro2")]
 mod proc_macro2_span {
     impl crate::Span for proc_macro2::Span {
@@ -97,11 +291,28 @@ mod proc_macro2_span {
             self.end().column
         }
     }
+
+    impl From<proc_macro2::Span> for crate::LineColumnSpan {
+        fn from(span: proc_macro2::Span) -> Self {
+            Self::from_span(&span)
+        }
+    }
+
+    impl From<proc_macro2::Span> for crate::CapturedSpan {
+        fn from(span: proc_macro2::Span) -> Self {
+            Self {
+                span: span.into(),
+                source_text: span.source_text(),
+            }
+        }
+    }
 }
 
 /// Generate a debug representation of a span and the source code it points to.
 ///
 /// It accepts any type that implements the [`Span`] trait. `Span` is implemented for [`proc_macro2::Span`].
+/// The code can be any [`SourceCode`], e.g. `&str` or a [`NamedSource`] to show a path in the
+/// header.
 ///
 /// ## Single line span example
 ///
@@ -124,155 +335,148 @@ mod proc_macro2_span {
 ///   | └───────────────╯
 /// ```
 ///
-pub fn debug_span(span: impl Span, code: &str) -> String {
+pub fn debug_span(span: impl Span, code: &(impl SourceCode + ?Sized)) -> String {
     internal::debug_span(&span, code)
 }
 
-#[doc(hidden)]
-pub mod internal {
-    use crate::Span;
-
-    pub fn debug_span(span: &(impl Span + ?Sized), code: &str) -> String {
-        if span.is_empty() {
-            debug_empty_span(span, code)
-        } else if span.is_single_line() {
-            debug_single_line_span(span, code)
-        } else {
-            debug_multi_line_span(span, code)
-        }
-    }
-
-    pub fn debug_empty_span(_span: &(impl Span + ?Sized), _code: &str) -> String {
-        "".to_string()
-    }
-
-    pub fn debug_single_line_span(span: &(impl Span + ?Sized), code: &str) -> String {
-        let empty_line = empty_line(span);
-        let range_line = range_line(span);
-        let code_line = code_line(span, code);
-        let marker_line = marker_line(span);
-        format!(
-            "{}\n{}\n{}\n{}\n{}\n",
-            range_line, empty_line, code_line, marker_line, empty_line,
-        )
-    }
-
-    pub fn debug_multi_line_span(span: &(impl Span + ?Sized), code: &str) -> String {
-        let empty_line = empty_line(span);
-        let range_line = range_line(span);
-        let start_line = start_line(span, code);
-        let code_lines = code_lines(span, code);
-        let end_line = end_line(span, code);
-        format!(
-            "{}\n{}\n{}\n{}\n{}\n{}\n",
-            range_line, empty_line, start_line, code_lines, end_line, empty_line,
-        )
-    }
-
-    pub fn range_line(span: &(impl Span + ?Sized)) -> String {
-        let line_number_width = span.end_line().to_string().len();
-        let range = span.to_range();
-        format!("{:width$}--> {}", "", range, width = line_number_width,)
-    }
-
-    pub fn empty_line(span: &(impl Span + ?Sized)) -> String {
-        let line_number_width = span.end_line().to_string().len();
-        format!("{:width$} |", "", width = line_number_width)
-    }
-
-    pub fn marker_line(span: &(impl Span + ?Sized)) -> String {
-        let line_number_width = span.end_line().to_string().len();
-        let start_column = span.start_column();
-        let end_column = span.end_column();
+/// Generate a debug representation of a span with custom [`DebugSpanOptions`].
+///
+/// see [`debug_span`] for more information.
+pub fn debug_span_with_options(
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> String {
+    internal::debug_span_with_options(&span, code, options)
+}
 
-        let marker = "^".repeat(end_column - start_column);
-        format!(
-            "{:width$} | {:space$}{}",
-            "",
-            "",
-            marker,
-            space = start_column,
-            width = line_number_width,
-        )
-    }
+/// Generate a debug representation of a span, or an error if the span doesn't point into the
+/// code instead of panicking like [`debug_span`].
+///
+/// ```rust
+/// use debug_span::{try_debug_span, DebugSpanError, LineColumnSpan};
+///
+/// let error = try_debug_span(LineColumnSpan::new(3, 0, 3, 1), "struct Foo;").unwrap_err();
+/// assert_eq!(
+///     error,
+///     DebugSpanError::LineOutOfRange {
+///         line: 3,
+///         line_count: 1
+///     }
+/// );
+/// ```
+pub fn try_debug_span(
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+) -> Result<String, DebugSpanError> {
+    try_debug_span_with_options(span, code, &DebugSpanOptions::default())
+}
 
-    pub fn code_line(span: &(impl Span + ?Sized), code: &str) -> String {
-        let line_number_width = span.end_line().to_string().len();
-        let line = code.lines().nth(span.start_line() - 1).unwrap();
-        format!(
-            "{:width$} | {}",
-            span.start_line(),
-            line,
-            width = line_number_width,
-        )
-    }
+/// Generate a debug representation of a span with custom [`DebugSpanOptions`], or an error if
+/// the span doesn't point into the code.
+///
+/// see [`try_debug_span`] for more information.
+pub fn try_debug_span_with_options(
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> Result<String, DebugSpanError> {
+    error::check_span(&span, code)?;
+    Ok(internal::debug_span_with_options(&span, code, options))
+}
 
-    const PADDING: usize = 3;
+/// Generate a debug representation of a span with a label after its marker.
+///
+/// ```text
+///  --> 1:7..1:10
+///   |
+/// 1 | struct 123;
+///   |        ^^^ expected identifier
+///   |
+/// ```
+///
+/// see [`debug_span`] for more information.
+pub fn debug_span_with_label(
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+    label: &str,
+) -> String {
+    let options = DebugSpanOptions {
+        label: Some(alloc::string::String::from(label)),
+        ..Default::default()
+    };
+    internal::debug_span_with_options(&span, code, &options)
+}
 
-    pub fn start_line(span: &(impl Span + ?Sized), code: &str) -> String {
-        let line_number_width = span.end_line().to_string().len();
-        let start_line = span.start_line();
-        let end_line = span.end_line();
-        let start_column = span.start_column();
+/// Generate a debug representation of several spans in a single frame.
+///
+/// Every line touched by a span is shown once, followed by one marker row per span on that line.
+/// Multi-line spans are marked from their start column to the end of the start line, and from
+/// the start of the end line to their end column. Non-adjacent lines are separated by a `...`
+/// row, and empty spans are skipped.
+///
+/// ```text
+///  --> 2:4..2:5, 2:7..2:10
+///   |
+/// 2 |     a: i32,
+///   |     ^
+///   |        ^^^
+///   |
+/// ```
+pub fn debug_spans(spans: &[impl Span], code: &(impl SourceCode + ?Sized)) -> String {
+    debug_spans_with_options(spans, code, &DebugSpanOptions::default())
+}
 
-        let lines = code
-            .lines()
-            .skip(start_line - 1)
-            .take(end_line - start_line + 1);
-        let max_line_len = lines.map(|line| line.len()).max().unwrap();
-        format!(
-            "{:width$} | {}┌{}╮",
-            "",
-            " ".repeat(start_column),
-            "─".repeat(max_line_len + PADDING - start_column),
-            width = line_number_width,
-        )
-    }
+/// Generate a debug representation of several spans with custom [`DebugSpanOptions`].
+///
+/// see [`debug_spans`](fn@debug_spans) for more information.
+pub fn debug_spans_with_options(
+    spans: &[impl Span],
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> String {
+    let spans = spans
+        .iter()
+        .map(LineColumnSpan::from_span)
+        .collect::<alloc::vec::Vec<_>>();
+    internal::debug_spans_with_options(&spans, code, options)
+}
 
-    pub fn code_lines(span: &(impl Span + ?Sized), code: &str) -> String {
-        let line_number_width = span.end_line().to_string().len();
-        let start_line = span.start_line();
-        let end_line = span.end_line();
-        let lines = code
-            .lines()
-            .skip(start_line - 1)
-            .take(end_line - start_line + 1);
-        let max_line_len = lines.clone().map(|line| line.len()).max().unwrap();
-        lines
-            .into_iter()
-            .enumerate()
-            .map(|(i, line)| {
-                let line_number = start_line + i;
-                format!(
-                    "{: >line_number_width$} | {}{}│",
-                    line_number,
-                    line,
-                    " ".repeat(max_line_len + PADDING + 1 - line.len()),
-                )
-            })
-            .collect::<Vec<_>>()
-            .join("\n")
-    }
+/// Generate a debug representation of several spans in a single frame, with the label of every
+/// span after its last marker.
+///
+/// ```text
+///  --> 2:4..2:5, 2:7..2:10
+///   |
+/// 2 |     a: i32,
+///   |     ^ name
+///   |        ^^^ type
+///   |
+/// ```
+///
+/// see [`debug_spans`](fn@debug_spans) for more information.
+pub fn debug_spans_with_labels(
+    spans: &[(impl Span, &str)],
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> String {
+    let spans = spans
+        .iter()
+        .map(|(span, label)| (LineColumnSpan::from_span(span), Some(*label)))
+        .collect::<alloc::vec::Vec<_>>();
+    multi::debug_labeled_spans_with_options(&spans, code, options)
+}
 
-    pub fn end_line(span: &(impl Span + ?Sized), code: &str) -> String {
-        let line_number_width = span.end_line().to_string().len();
-        let start_line = span.start_line();
-        let end_line = span.end_line();
-        let end_column = span.end_column();
-
-        let lines = code
-            .lines()
-            .skip(start_line - 1)
-            .take(end_line - start_line + 1);
-        let max_line_len = lines.map(|line| line.len()).max().unwrap();
-        format!(
-            "{:width$} | {}└{}╯",
-            "",
-            " ".repeat(end_column - 1),
-            "─".repeat(max_line_len + PADDING - end_column + 1),
-            width = line_number_width,
-        )
-    }
+#[doc(hidden)]
+pub mod internal {
+    pub use crate::assert::{assert_span_eq_message, same_location};
+    pub use crate::multi::debug_spans_with_options;
+    pub use crate::print::{span_message, stderr_options};
+    pub use crate::render::*;
+    #[cfg(feature = "std")]
+    pub use crate::sink::eprint;
+    #[cfg(feature = "proc-macro2")]
+    pub use crate::trace::ProcMacroTrace;
 }
 
 #[cfg(test)]
@@ -293,6 +497,106 @@ mod tests {
         insta::assert_snapshot!(output, @"");
     }
 
+    #[test]
+    fn test_dyn_span_debug() {
+        let span = LineColumnSpan::new(1, 7, 1, 10);
+        let boxed: alloc::boxed::Box<dyn Span> = alloc::boxed::Box::new(span);
+        let span_ref: &dyn Span = &span;
+        assert_eq!(boxed.debug("struct Foo;"), debug_span(span, "struct Foo;"));
+        assert_eq!(span_ref.debug("struct Foo;"), span.debug("struct Foo;"));
+    }
+
+    #[test]
+    fn test_debug_spans() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+            }
+        "###
+        .unindent();
+        let derive_input: syn::DeriveInput = syn::parse_str(&input).unwrap();
+        let Data::Struct(data) = derive_input.data else {
+            panic!("expected struct");
+        };
+        let field = data.fields.iter().next().unwrap();
+        let spans = [field.ident.span(), field.ty.span()];
+        let output = debug_spans(&spans, &input);
+        insta::assert_snapshot!(output, @r###"
+         --> 2:4..2:5, 2:7..2:10
+          |
+        2 |     a: i32,
+          |     ^
+          |        ^^^
+          |
+        "###);
+        let spans = [
+            LineColumnSpan::new(2, 7, 2, 10),
+            LineColumnSpan::new(1, 7, 1, 10),
+        ];
+        let options = DebugSpanOptions {
+            deterministic: true,
+            ..Default::default()
+        };
+        insta::assert_snapshot!(debug_spans_with_options(&spans, &input, &options), @r###"
+         --> 1:7..1:10, 2:7..2:10
+          |
+        1 | struct Foo {
+          |        ^^^
+        2 |     a: i32,
+          |        ^^^
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_labels() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+                b: i32,
+            }
+        "###
+        .unindent();
+        let span = LineColumnSpan::new(2, 4, 2, 5);
+        insta::assert_snapshot!(debug_span_with_label(span, &input, "expected identifier"), @r###"
+         --> 2:4..2:5
+          |
+        2 |     a: i32,
+          |     ^ expected identifier
+          |
+        "###);
+        let spans = [
+            (LineColumnSpan::new(1, 11, 4, 1), "body"),
+            (LineColumnSpan::new(3, 7, 3, 10), "type"),
+            (LineColumnSpan::new(2, 4, 2, 5), "name"),
+            (LineColumnSpan::new(1, 11, 2, 0), "first line"),
+        ];
+        let output = debug_spans_with_labels(&spans, &input, &DebugSpanOptions::default());
+        insta::assert_snapshot!(output, @r###"
+         --> 1:11..4:1, 3:7..3:10, 2:4..2:5, 1:11..2:0
+          |
+        1 | struct Foo {
+          |            ^
+          |            ^ first line
+        2 |     a: i32,
+          |     ^ name
+        3 |     b: i32,
+          |        ^^^ type
+        4 | }
+          | ^ body
+          |
+        "###);
+        let options = DebugSpanOptions {
+            prose: true,
+            ..Default::default()
+        };
+        insta::assert_snapshot!(debug_spans_with_labels(&spans[1..3], &input, &options), @r###"
+        2 spans:
+        type: span 1 starts at line 3 column 7 and ends at line 3 column 10, covering the text `i32` in: b: i32,
+        name: span 2 starts at line 2 column 4 and ends at line 2 column 5, covering the text `a` in: a: i32,
+        "###);
+    }
+
     #[test]
     fn test_single_line() {
         let input = r###"
@@ -443,6 +747,26 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_from_proc_macro2_span() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+            }
+        "###
+        .unindent();
+        let derive_input: syn::DeriveInput = syn::parse_str(&input).unwrap();
+        let span = match derive_input.data {
+            Data::Struct(s) => s.fields.span(),
+            _ => panic!("expected struct"),
+        };
+        let captured = CapturedSpan::from(span);
+        assert_eq!(captured.span, LineColumnSpan::from(span));
+        assert_eq!(captured.span, LineColumnSpan::new(1, 11, 3, 1));
+        assert_eq!(captured.source_text.as_deref(), Some("{\n    a: i32,\n}"));
+        assert_eq!(debug_span(&captured, &input), debug_span(span, &input));
+    }
+
     #[test]
     fn test_debug_method() {
         let input = r###"
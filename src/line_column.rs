@@ -0,0 +1,236 @@
+use crate::{OneBased, Position, Span, ZeroBased};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+/// An owned span made of plain line and column coordinates.
+///
+/// Lines are 1-indexed and columns are 0-indexed, matching [`proc_macro2::LineColumn`]. Unlike
+/// `proc_macro2::Span`, it can be stored, compared and constructed by hand. Spans are ordered by
+/// their start position, then by their end position.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span, LineColumnSpan};
+///
+/// let span = LineColumnSpan::new(1, 7, 1, 10);
+/// let output = debug_span(span, "struct Foo;");
+/// assert_eq!(output, " --> 1:7..1:10\n  |\n1 | struct Foo;\n  |        ^^^\n  |\n");
+/// ```
+///
+/// [`proc_macro2::LineColumn`]: https://docs.rs/proc-macro2/latest/proc_macro2/struct.LineColumn.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct LineColumnSpan {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl LineColumnSpan {
+    pub fn new(start_line: usize, start_column: usize, end_line: usize, end_column: usize) -> Self {
+        Self {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        }
+    }
+
+    /// Creates a span from typed positions, see [`Position`].
+    pub fn from_positions(start: Position, end: Position) -> Self {
+        Self::new(
+            start.line.get(),
+            start.column.get(),
+            end.line.get(),
+            end.column.get(),
+        )
+    }
+
+    /// The start position, or `None` if the start line is `0`, e.g. for a `proc_macro2::Span`
+    /// without location information.
+    pub fn start(&self) -> Option<Position> {
+        let line = OneBased::new(self.start_line)?;
+        Some(Position::new(line, ZeroBased::new(self.start_column)))
+    }
+
+    /// The end position, or `None` if the end line is `0`.
+    pub fn end(&self) -> Option<Position> {
+        let line = OneBased::new(self.end_line)?;
+        Some(Position::new(line, ZeroBased::new(self.end_column)))
+    }
+
+    /// Captures the coordinates of any [`Span`] into an owned `LineColumnSpan`.
+    pub fn from_span(span: &(impl Span + ?Sized)) -> Self {
+        Self::new(
+            span.start_line(),
+            span.start_column(),
+            span.end_line(),
+            span.end_column(),
+        )
+    }
+}
+
+impl Span for LineColumnSpan {
+    fn start_line(&self) -> usize {
+        self.start_line
+    }
+    fn end_line(&self) -> usize {
+        self.end_line
+    }
+    fn start_column(&self) -> usize {
+        self.start_column
+    }
+    fn end_column(&self) -> usize {
+        self.end_column
+    }
+}
+
+/// Parses the `start_line:start_column..end_line:end_column` notation of [`Span::to_range`].
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{LineColumnSpan, Span};
+///
+/// let span: LineColumnSpan = "1:7..1:10".parse().unwrap();
+/// assert_eq!(span, LineColumnSpan::new(1, 7, 1, 10));
+/// assert_eq!(span.to_range().parse(), Ok(span));
+/// ```
+impl FromStr for LineColumnSpan {
+    type Err = ParseSpanError;
+
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        let parse = || {
+            let (start, end) = range.split_once("..")?;
+            let (start_line, start_column) = start.split_once(':')?;
+            let (end_line, end_column) = end.split_once(':')?;
+            Some(Self::new(
+                start_line.parse().ok()?,
+                start_column.parse().ok()?,
+                end_line.parse().ok()?,
+                end_column.parse().ok()?,
+            ))
+        };
+        parse().ok_or_else(|| ParseSpanError {
+            range: range.to_string(),
+        })
+    }
+}
+
+/// The error returned when parsing a malformed range into a [`LineColumnSpan`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseSpanError {
+    range: String,
+}
+
+impl fmt::Display for ParseSpanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid range `{}`, expected `start_line:start_column..end_line:end_column`",
+            self.range
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseSpanError {}
+
+/// Sort spans by their start position, then by their end position.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{sort_spans, LineColumnSpan};
+///
+/// let mut spans = [LineColumnSpan::new(2, 0, 2, 3), LineColumnSpan::new(1, 4, 1, 6)];
+/// sort_spans(&mut spans);
+/// assert_eq!(spans, [LineColumnSpan::new(1, 4, 1, 6), LineColumnSpan::new(2, 0, 2, 3)]);
+/// ```
+pub fn sort_spans<S: Span>(spans: &mut [S]) {
+    spans.sort_by_key(|span| LineColumnSpan::from_span(span));
+}
+
+/// Sort spans with [`sort_spans`] and remove the spans with the same location as the previous
+/// one.
+pub fn dedup_spans<S: Span>(spans: &mut Vec<S>) {
+    sort_spans(spans);
+    spans.dedup_by(|left, right| crate::same_location(left, right));
+}
+
+/// An owned snapshot of a span: its coordinates and, when available, the source text it covers.
+///
+/// `proc_macro2::Span` can't be stored or compared easily. Converting it into a `CapturedSpan`
+/// during macro expansion allows examining the spans after the fact.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{CapturedSpan, LineColumnSpan};
+///
+/// let input = "struct Foo;";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+/// let captured = CapturedSpan::from(derive_input.ident.span());
+/// assert_eq!(captured.span, LineColumnSpan::new(1, 7, 1, 10));
+/// assert_eq!(captured.source_text.as_deref(), Some("Foo"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct CapturedSpan {
+    pub span: LineColumnSpan,
+    /// The source text covered by the span, see `proc_macro2::Span::source_text`.
+    pub source_text: Option<String>,
+}
+
+impl Span for CapturedSpan {
+    fn start_line(&self) -> usize {
+        self.span.start_line
+    }
+    fn end_line(&self) -> usize {
+        self.span.end_line
+    }
+    fn start_column(&self) -> usize {
+        self.span.start_column
+    }
+    fn end_column(&self) -> usize {
+        self.span.end_column
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("3:4..5:1".parse(), Ok(LineColumnSpan::new(3, 4, 5, 1)));
+        let error = "3:4".parse::<LineColumnSpan>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid range `3:4`, expected `start_line:start_column..end_line:end_column`"
+        );
+        assert!("3:4..5".parse::<LineColumnSpan>().is_err());
+        assert!("a:4..5:1".parse::<LineColumnSpan>().is_err());
+    }
+
+    #[test]
+    fn test_dedup_spans() {
+        let mut spans = alloc::vec![
+            LineColumnSpan::new(2, 0, 2, 3),
+            LineColumnSpan::new(1, 4, 2, 0),
+            LineColumnSpan::new(1, 4, 1, 6),
+            LineColumnSpan::new(2, 0, 2, 3),
+        ];
+        dedup_spans(&mut spans);
+        assert_eq!(
+            spans,
+            [
+                LineColumnSpan::new(1, 4, 1, 6),
+                LineColumnSpan::new(1, 4, 2, 0),
+                LineColumnSpan::new(2, 0, 2, 3),
+            ]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use alloc::format;
+use alloc::string::String;
+
+/// Links to the source of a repository host, turning line numbers and headers of HTML renderings
+/// into links, see [`DebugSpanOptions::link`](crate::DebugSpanOptions::link).
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::SourceLink;
+///
+/// let link = SourceLink::new("https://github.com/smmoosavi/debug-span", "main");
+/// assert_eq!(
+///     link.url("src/lib.rs", 3..=3),
+///     "https://github.com/smmoosavi/debug-span/blob/main/src/lib.rs#L3"
+/// );
+/// assert_eq!(
+///     link.url("src/lib.rs", 3..=5),
+///     "https://github.com/smmoosavi/debug-span/blob/main/src/lib.rs#L3-L5"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SourceLink {
+    /// The URL of the repository, e.g. `https://github.com/owner/repo`. GitLab URLs end with
+    /// `/-`, e.g. `https://gitlab.com/owner/repo/-`.
+    pub base_url: String,
+    /// The branch, tag or commit the links point to.
+    pub rev: String,
+}
+
+impl SourceLink {
+    pub fn new(base_url: impl Into<String>, rev: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            rev: rev.into(),
+        }
+    }
+
+    /// The URL of the 1-indexed `lines` of `path`: `{base_url}/blob/{rev}/{path}#L{line}`.
+    ///
+    /// Backslashes of the path are turned into slashes, and a leading `./` is removed.
+    pub fn url(&self, path: &str, lines: core::ops::RangeInclusive<usize>) -> String {
+        let path = path.replace('\\', "/");
+        let path = path.trim_start_matches("./");
+        let anchor = match lines.start() == lines.end() {
+            true => format!("L{}", lines.start()),
+            false => format!("L{}-L{}", lines.start(), lines.end()),
+        };
+        format!(
+            "{}/blob/{}/{}#{}",
+            self.base_url.trim_end_matches('/'),
+            self.rev,
+            path,
+            anchor
+        )
+    }
+}
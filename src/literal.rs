@@ -0,0 +1,278 @@
+use crate::text::get_source_span_text;
+use crate::{LineColumnSpan, SourceCode, Span};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Returns the span of a range of characters of the value of a string literal.
+///
+/// `range` indexes the characters of [`lit.value()`](syn::LitStr::value), the contents after
+/// escape processing. The returned span points at the source characters, after the opening
+/// quote and covering whole escape sequences. Raw strings, e.g. `r#"..."#`, have no escape
+/// sequences and their contents start after the `r`, the hashes and the quote. Returns `None` if
+/// the range is out of bounds of the value.
+///
+/// Crates embedding a DSL in string literals can point errors at the right characters.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{literal_subspan, LineColumnSpan};
+///
+/// let lit: syn::LitStr = syn::parse_str(r#""SELECT\t*""#).unwrap();
+/// assert_eq!(lit.value(), "SELECT\t*");
+/// let span = literal_subspan(&lit, 6..8);
+/// assert_eq!(span, Some(LineColumnSpan::new(1, 7, 1, 10)));
+/// ```
+pub fn literal_subspan(lit: &syn::LitStr, range: Range<usize>) -> Option<LineColumnSpan> {
+    let repr = lit.token().to_string().chars().collect::<Vec<_>>();
+    let (chars, end) = match repr.first() {
+        Some('r') => raw_chars(&repr)?,
+        _ => cooked_chars(&repr)?,
+    };
+    subspan(&lit.span(), &repr, &chars, end, range)
+}
+
+/// Returns the span of a range of characters of the text of a doc attribute.
+///
+/// `attr` is a `#[doc = "..."]` attribute, either written as such or desugared from a `///`,
+/// `//!`, `/** */` or `/*! */` doc comment. `range` indexes the characters of the documentation
+/// string, e.g. `" Foo"` for `/// Foo`. The code is used to tell the written and the desugared
+/// forms apart. Returns `None` if `attr` is not a doc attribute, or the range is out of bounds.
+///
+/// Tools linting doc contents, like intra-doc link checkers, can render precise locations.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{doc_subspan, LineColumnSpan};
+///
+/// let code = "/// See [`Foo`].\nstruct Bar;";
+/// let item: syn::ItemStruct = syn::parse_str(code).unwrap();
+/// let span = doc_subspan(&item.attrs[0], 6..11, code);
+/// assert_eq!(span, Some(LineColumnSpan::new(1, 9, 1, 14)));
+/// ```
+pub fn doc_subspan(
+    attr: &syn::Attribute,
+    range: Range<usize>,
+    code: &(impl SourceCode + ?Sized),
+) -> Option<LineColumnSpan> {
+    let syn::Meta::NameValue(meta) = &attr.meta else {
+        return None;
+    };
+    let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(lit),
+        ..
+    }) = &meta.value
+    else {
+        return None;
+    };
+    if !meta.path.is_ident("doc") {
+        return None;
+    }
+    // a desugared doc comment has the span of the whole comment
+    let source = get_source_span_text(&lit.span(), code)?;
+    let comment_end = match source.get(..3) {
+        Some("///" | "//!") => 0,
+        Some("/**" | "/*!") => 2,
+        _ => return literal_subspan(lit, range),
+    };
+    let repr = source.chars().collect::<Vec<_>>();
+    let end = repr.len().checked_sub(comment_end)?;
+    let chars = (3..end).map(|i| i..i + 1).collect::<Vec<_>>();
+    subspan(&lit.span(), &repr, &chars, end, range)
+}
+
+/// Returns the span of `range` in the value of a literal written as `repr` at `span`.
+///
+/// `chars` are the source characters of each value character, and `end` the end of the value,
+/// as offsets into `repr`.
+fn subspan(
+    span: &impl Span,
+    repr: &[char],
+    chars: &[Range<usize>],
+    end: usize,
+    range: Range<usize>,
+) -> Option<LineColumnSpan> {
+    if range.start > range.end || range.end > chars.len() {
+        return None;
+    }
+
+    let mut positions = Vec::with_capacity(repr.len() + 1);
+    let mut position = (span.start_line(), span.start_column());
+    positions.push(position);
+    for c in repr {
+        position = match c {
+            '\n' => (position.0 + 1, 0),
+            _ => (position.0, position.1 + 1),
+        };
+        positions.push(position);
+    }
+
+    let start = chars.get(range.start).map_or(end, |c| c.start);
+    let end = match range.end {
+        end if end == range.start => start,
+        end => chars[end - 1].end,
+    };
+    let ((start_line, start_column), (end_line, end_column)) = (positions[start], positions[end]);
+    Some(LineColumnSpan::new(
+        start_line,
+        start_column,
+        end_line,
+        end_column,
+    ))
+}
+
+/// Splits the source text of a string literal into the source characters of each value
+/// character, as offsets into `repr`. Also returns the offset of the closing quote.
+fn cooked_chars(repr: &[char]) -> Option<(Vec<Range<usize>>, usize)> {
+    if repr.first() != Some(&'"') {
+        return None;
+    }
+    let mut chars = Vec::new();
+    let mut i = 1;
+    while *repr.get(i)? != '"' {
+        let start = i;
+        if repr[i] != '\\' {
+            i += 1;
+        } else {
+            match repr.get(i + 1)? {
+                'x' => i += 4,
+                'u' => i += repr[i..].iter().position(|c| *c == '}')? + 1,
+                // line continuation: the line break and the leading whitespace are skipped
+                '\n' | '\r' => {
+                    i += 1;
+                    while repr.get(i)?.is_whitespace() {
+                        i += 1;
+                    }
+                    continue;
+                }
+                _ => i += 2,
+            }
+        }
+        chars.push(start..i);
+    }
+    Some((chars, i))
+}
+
+/// Like [`cooked_chars`], for raw strings: every source character is a value character.
+fn raw_chars(repr: &[char]) -> Option<(Vec<Range<usize>>, usize)> {
+    let hashes = repr[1..].iter().take_while(|c| **c == '#').count();
+    let start = 1 + hashes + 1;
+    if repr.get(start - 1) != Some(&'"') {
+        return None;
+    }
+    let end = repr.len().checked_sub(hashes + 1)?;
+    Some(((start..end).map(|i| i..i + 1).collect(), end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_subspan() {
+        let code = "\"SELECT \\\"a\\\",\\u{1F600} \\\n    FROM\\x20t\"";
+        let lit: syn::LitStr = syn::parse_str(code).unwrap();
+        assert_eq!(lit.value(), "SELECT \"a\",\u{1F600} FROM t");
+
+        let subspan = |range: Range<usize>| literal_subspan(&lit, range).unwrap();
+        insta::assert_snapshot!(crate::debug_span(subspan(7..10), code), @r###"
+         --> 1:8..1:13
+          |
+        1 | "SELECT \"a\",\u{1F600} \
+          |         ^^^^^
+          |
+        "###);
+        insta::assert_snapshot!(crate::debug_span(subspan(11..17), code), @r###"
+         --> 1:14..2:8
+          |
+          |               ┌──────────────╮
+        1 | "SELECT \"a\",\u{1F600} \    │
+        2 |     FROM\x20t"               │
+          |        └─────────────────────╯
+          |
+        "###);
+        insta::assert_snapshot!(crate::debug_span(subspan(17..19), code), @r###"
+         --> 2:8..2:13
+          |
+        2 |     FROM\x20t"
+          |         ^^^^^
+          |
+        "###);
+        assert_eq!(subspan(0..0), LineColumnSpan::new(1, 1, 1, 1));
+        assert_eq!(subspan(19..19), LineColumnSpan::new(2, 13, 2, 13));
+        assert_eq!(literal_subspan(&lit, 0..20), None);
+    }
+
+    #[test]
+    fn test_raw_literal_subspan() {
+        let code = "r##\"<a href=\"#\">\\n\n</a>\"##";
+        let lit: syn::LitStr = syn::parse_str(code).unwrap();
+        assert_eq!(lit.value(), "<a href=\"#\">\\n\n</a>");
+
+        let subspan = |range: Range<usize>| literal_subspan(&lit, range).unwrap();
+        insta::assert_snapshot!(crate::debug_span(subspan(3..11), code), @r###"
+         --> 1:7..1:15
+          |
+        1 | r##"<a href="#">\n
+          |        ^^^^^^^^
+          |
+        "###);
+        insta::assert_snapshot!(crate::debug_span(subspan(12..14), code), @r###"
+         --> 1:16..1:18
+          |
+        1 | r##"<a href="#">\n
+          |                 ^^
+          |
+        "###);
+        assert_eq!(subspan(0..0), LineColumnSpan::new(1, 4, 1, 4));
+        assert_eq!(subspan(19..19), LineColumnSpan::new(2, 4, 2, 4));
+        assert_eq!(literal_subspan(&lit, 0..20), None);
+
+        let lit: syn::LitStr = syn::parse_str("r\"a\"").unwrap();
+        assert_eq!(
+            literal_subspan(&lit, 0..1),
+            Some(LineColumnSpan::new(1, 2, 1, 3))
+        );
+    }
+
+    #[test]
+    fn test_doc_subspan() {
+        let code = unindent::unindent(
+            r###"
+            /// Line [`A`].
+            #[doc = "\tB"]
+            /** Block
+                [`C`] */
+            struct Foo;
+            "###,
+        );
+        let item: syn::ItemStruct = syn::parse_str(&code).unwrap();
+        let spans = [
+            doc_subspan(&item.attrs[0], 6..11, code.as_str()).unwrap(),
+            doc_subspan(&item.attrs[1], 0..2, code.as_str()).unwrap(),
+            doc_subspan(&item.attrs[2], 11..16, code.as_str()).unwrap(),
+        ];
+        let options = crate::DebugSpanOptions::default();
+        let output = crate::internal::debug_spans_with_options(&spans, code.as_str(), &options);
+        insta::assert_snapshot!(output, @r###"
+         --> 1:9..1:14, 2:9..2:12, 4:4..4:9
+          |
+        1 | /// Line [`A`].
+          |          ^^^^^
+        2 | #[doc = "\tB"]
+          |          ^^^
+         ...
+        4 |     [`C`] */
+          |     ^^^^^
+          |
+        "###);
+        assert_eq!(doc_subspan(&item.attrs[0], 0..20, code.as_str()), None);
+
+        let item: syn::ItemStruct = syn::parse_str("#[derive(Debug)]\nstruct Foo;").unwrap();
+        assert_eq!(
+            doc_subspan(&item.attrs[0], 0..0, "#[derive(Debug)]\nstruct Foo;"),
+            None
+        );
+    }
+}
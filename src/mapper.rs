@@ -0,0 +1,151 @@
+use crate::{LineColumnSpan, Span};
+use alloc::vec::Vec;
+
+/// Records simple text transformations and remaps spans through them.
+///
+/// Tests often `unindent` a fixture or prepend helper lines before parsing it, which shifts every
+/// span. A `SpanMapper` describes how the original text was transformed, and maps spans from the
+/// original text to the transformed one with [`forward`](Self::forward), or back with
+/// [`backward`](Self::backward).
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span, LineColumnSpan, SpanMapper};
+/// use unindent::Unindent;
+///
+/// let fixture = r###"
+///     struct Foo;
+/// "###;
+/// let input = fixture.unindent();
+/// let derive_input: syn::DeriveInput = syn::parse_str(&input).unwrap();
+///
+/// // `unindent` removed the leading empty line and 4 columns of indentation.
+/// let mapper = SpanMapper::new().offset_lines(-1).dedent(4);
+/// let span = mapper.backward(derive_input.ident.span());
+/// assert_eq!(span, LineColumnSpan::new(2, 11, 2, 14));
+/// let output = debug_span(span, fixture);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpanMapper {
+    transformations: Vec<Transformation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transformation {
+    Lines(isize),
+    Columns(isize),
+    Dedent(usize),
+}
+
+impl SpanMapper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lines were moved by `delta`, e.g. `-1` when the leading line was removed.
+    pub fn offset_lines(mut self, delta: isize) -> Self {
+        self.transformations.push(Transformation::Lines(delta));
+        self
+    }
+
+    /// Columns of every line were moved by `delta`.
+    pub fn offset_columns(mut self, delta: isize) -> Self {
+        self.transformations.push(Transformation::Columns(delta));
+        self
+    }
+
+    /// `count` lines were inserted before the text.
+    pub fn prefix_lines(self, count: usize) -> Self {
+        self.offset_lines(count as isize)
+    }
+
+    /// Up to `columns` leading columns were removed from every line.
+    ///
+    /// Columns inside the removed indentation are mapped forward to column `0`.
+    pub fn dedent(mut self, columns: usize) -> Self {
+        self.transformations.push(Transformation::Dedent(columns));
+        self
+    }
+
+    /// Map a span of the original text to the transformed text.
+    pub fn forward(&self, span: impl Span) -> LineColumnSpan {
+        let [mut start, mut end] = positions(&span);
+        for transformation in &self.transformations {
+            start = transformation.forward(start);
+            end = transformation.forward(end);
+        }
+        from_positions(start, end)
+    }
+
+    /// Map a span of the transformed text back to the original text.
+    pub fn backward(&self, span: impl Span) -> LineColumnSpan {
+        let [mut start, mut end] = positions(&span);
+        for transformation in self.transformations.iter().rev() {
+            start = transformation.backward(start);
+            end = transformation.backward(end);
+        }
+        from_positions(start, end)
+    }
+}
+
+impl Transformation {
+    fn forward(self, (line, column): (usize, usize)) -> (usize, usize) {
+        match self {
+            Self::Lines(delta) => (offset(line, delta).max(1), column),
+            Self::Columns(delta) => (line, offset(column, delta)),
+            Self::Dedent(columns) => (line, column.saturating_sub(columns)),
+        }
+    }
+
+    fn backward(self, (line, column): (usize, usize)) -> (usize, usize) {
+        match self {
+            Self::Lines(delta) => (offset(line, -delta).max(1), column),
+            Self::Columns(delta) => (line, offset(column, -delta)),
+            Self::Dedent(columns) => (line, column + columns),
+        }
+    }
+}
+
+fn offset(value: usize, delta: isize) -> usize {
+    value.saturating_add_signed(delta)
+}
+
+fn positions(span: &impl Span) -> [(usize, usize); 2] {
+    [
+        (span.start_line(), span.start_column()),
+        (span.end_line(), span.end_column()),
+    ]
+}
+
+fn from_positions(start: (usize, usize), end: (usize, usize)) -> LineColumnSpan {
+    LineColumnSpan::new(start.0, start.1, end.0, end.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_mapper() {
+        let mapper = SpanMapper::new()
+            .prefix_lines(2)
+            .offset_columns(4)
+            .offset_lines(-1)
+            .dedent(2);
+        let span = LineColumnSpan::new(1, 7, 2, 10);
+        let mapped = mapper.forward(span);
+        assert_eq!(mapped, LineColumnSpan::new(2, 9, 3, 12));
+        assert_eq!(mapper.backward(mapped), span);
+        assert_eq!(SpanMapper::new().forward(span), span);
+    }
+
+    #[test]
+    fn test_span_mapper_saturates() {
+        let mapper = SpanMapper::new().offset_lines(-3).dedent(8);
+        assert_eq!(
+            mapper.forward(LineColumnSpan::new(2, 4, 5, 10)),
+            LineColumnSpan::new(1, 0, 2, 2)
+        );
+    }
+}
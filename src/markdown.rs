@@ -0,0 +1,199 @@
+use crate::{DebugSpanOptions, RemappedSource, SourceCode, Span};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A fenced code block of a Markdown document, see [`code_blocks`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CodeBlock {
+    /// The info string after the opening fence, e.g. `rust,ignore`.
+    pub info: String,
+    /// The line of the document holding the first line of the contents.
+    pub first_line: usize,
+    /// The contents between the fences, without the indentation of the opening fence.
+    pub code: String,
+}
+
+impl CodeBlock {
+    /// The first word of the info string, e.g. `rust` for `rust,ignore`.
+    pub fn language(&self) -> &str {
+        self.info
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// The contents, displayed with the line numbers of the document.
+    pub fn source(&self) -> RemappedSource<&str> {
+        RemappedSource::new(self.code.as_str()).remap_lines(1, self.first_line)
+    }
+}
+
+/// Which fenced code block of a document to render, see [`debug_span_in_markdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockSelector<'a> {
+    /// The block at this 0-indexed position in the document.
+    Index(usize),
+    /// The first block whose [language](CodeBlock::language) is the given one.
+    Language(&'a str),
+}
+
+/// Returns the fenced code blocks of a Markdown document, in order.
+///
+/// Blocks are opened by at least three backticks or tildes indented by up to three spaces, and
+/// closed by a fence of the same character at least as long, or by the end of the document.
+/// Fences nested in block quotes or list items are not recognized.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::code_blocks;
+///
+/// let markdown = "# Foo\n\n```rust,ignore\nstruct Foo;\n```\n";
+/// let blocks = code_blocks(markdown);
+/// assert_eq!(blocks.len(), 1);
+/// assert_eq!(blocks[0].language(), "rust");
+/// assert_eq!(blocks[0].first_line, 4);
+/// assert_eq!(blocks[0].code, "struct Foo;");
+/// ```
+pub fn code_blocks(markdown: &(impl SourceCode + ?Sized)) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(Fence, CodeBlock, Vec<String>)> = None;
+    for line_number in 1..=markdown.line_count() {
+        let line = markdown.line(line_number).unwrap_or_default();
+        let fence = Fence::parse(&line);
+        match (open.take(), fence) {
+            (None, Some((fence, info))) => {
+                let block = CodeBlock {
+                    info: String::from(info.trim()),
+                    first_line: line_number + 1,
+                    code: String::new(),
+                };
+                open = Some((fence, block, Vec::new()));
+            }
+            (None, None) => {}
+            (Some((fence, mut block, lines)), Some((closing, info)))
+                if closing.closes(&fence) && info.trim().is_empty() =>
+            {
+                block.code = lines.join("\n");
+                blocks.push(block);
+            }
+            (Some((fence, block, mut lines)), _) => {
+                lines.push(String::from(fence.dedent(&line)));
+                open = Some((fence, block, lines));
+            }
+        }
+    }
+    if let Some((_, mut block, lines)) = open {
+        block.code = lines.join("\n");
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// Render a span of a fenced code block of a Markdown document.
+///
+/// The span is relative to the [contents](CodeBlock::code) of the block, the rendering shows
+/// the line numbers and the path of the document. Returns `None` if there is no such block.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span_in_markdown, BlockSelector, LineColumnSpan, NamedSource};
+///
+/// let markdown = NamedSource::new("README.md", "# Foo\n\n```rust\nstruct Foo;\n```\n");
+/// let span = LineColumnSpan::new(1, 7, 1, 10);
+/// let block = BlockSelector::Language("rust");
+/// let output = debug_span_in_markdown(span, &markdown, block, &Default::default()).unwrap();
+/// assert_eq!(output, " --> README.md:4:7..4:10\n  |\n4 | struct Foo;\n  |        ^^^\n  |\n");
+/// ```
+pub fn debug_span_in_markdown(
+    span: impl Span,
+    markdown: &(impl SourceCode + ?Sized),
+    block: BlockSelector<'_>,
+    options: &DebugSpanOptions,
+) -> Option<String> {
+    let blocks = code_blocks(markdown);
+    let block = match block {
+        BlockSelector::Index(index) => blocks.get(index)?,
+        BlockSelector::Language(language) => {
+            blocks.iter().find(|block| block.language() == language)?
+        }
+    };
+    let source = RemappedSource::new(block.code.as_str());
+    let source = match markdown.line_origin(block.first_line) {
+        (Some(path), line) => source.remap_file(1, path, line),
+        (None, line) => source.remap_lines(1, line),
+    };
+    Some(crate::debug_span_with_options(span, &source, options))
+}
+
+/// An opening or closing code fence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Fence {
+    char: char,
+    len: usize,
+    indent: usize,
+}
+
+impl Fence {
+    /// Parses a fence and the info string following it.
+    fn parse(line: &str) -> Option<(Fence, &str)> {
+        let rest = line.trim_start_matches(' ');
+        let indent = line.len() - rest.len();
+        let char = rest.chars().next().filter(|c| *c == '`' || *c == '~')?;
+        let info = rest.trim_start_matches(char);
+        let len = rest.len() - info.len();
+        if indent > 3 || len < 3 || (char == '`' && info.contains('`')) {
+            return None;
+        }
+        Some((Fence { char, len, indent }, info))
+    }
+
+    fn closes(&self, opening: &Fence) -> bool {
+        self.char == opening.char && self.len >= opening.len
+    }
+
+    /// Removes up to the indentation of the fence from a line of the contents.
+    fn dedent<'a>(&self, line: &'a str) -> &'a str {
+        let spaces = line.len() - line.trim_start_matches(' ').len();
+        &line[spaces.min(self.indent)..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+
+    #[test]
+    fn test_code_blocks() {
+        let markdown = "````md\n```rust\n````\n\n  ~~~ rust\n  fn foo() {\n      bar();\n   }\n  ~~~\n\n```\nunclosed";
+        let blocks = code_blocks(markdown);
+        let summary = blocks
+            .iter()
+            .map(|block| alloc::format!("{} {:?} {:?}\n", block.first_line, block.info, block.code))
+            .collect::<String>();
+        insta::assert_snapshot!(summary, @r###"
+        2 "md" "```rust"
+        6 "rust" "fn foo() {\n    bar();\n }"
+        12 "" "unclosed"
+        "###);
+
+        let span = LineColumnSpan::new(1, 3, 3, 2);
+        let options = DebugSpanOptions::default();
+        let output =
+            debug_span_in_markdown(span, markdown, BlockSelector::Language("rust"), &options);
+        insta::assert_snapshot!(output.unwrap(), @r###"
+         --> 6:3..8:2
+          |
+          |    ┌──────────╮
+        6 | fn foo() {    │
+        7 |     bar();    │
+        8 |  }            │
+          |  └────────────╯
+          |
+        "###);
+        let output = debug_span_in_markdown(span, markdown, BlockSelector::Index(3), &options);
+        assert_eq!(output, None);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{
+    render, DebugSpanOptions, Event, MarkerKind, OutputVersion, SourceCode, Span, Visitor,
+};
+
+/// Returns the `(width, height)` of the rendering of a span, in characters and lines, without
+/// rendering it.
+///
+/// The size is the one of [`debug_span_with_options`](crate::debug_span_with_options) without
+/// escapes: colors and highlighting don't change it. An empty span measures `(0, 0)`
+/// unless [`show_empty_spans`](DebugSpanOptions::show_empty_spans) is set.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{measure, DebugSpanOptions, LineColumnSpan};
+///
+/// let code = "struct Foo {\n    a: i32,\n}";
+/// let size = measure(LineColumnSpan::new(2, 4, 2, 5), code, &DebugSpanOptions::default());
+/// assert_eq!(size, (15, 5));
+/// ```
+pub fn measure(
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> (usize, usize) {
+    let mut visitor = MeasureVisitor {
+        lines_omitted: options.messages.lines_omitted.chars().count(),
+        max_width: options.max_width,
+        output_version: options.output_version,
+        ..Default::default()
+    };
+    crate::render::visit_span(&span, code, options, &mut visitor);
+    (visitor.width, visitor.height)
+}
+
+#[derive(Default)]
+struct MeasureVisitor {
+    /// Width of the tag column and the line numbers of the current frame.
+    prefix: usize,
+    /// Width of the text of the [`Event::Ellipsis`] rows.
+    lines_omitted: usize,
+    max_width: Option<usize>,
+    output_version: OutputVersion,
+    width: usize,
+    height: usize,
+}
+
+impl Visitor for MeasureVisitor {
+    fn visit(&mut self, event: Event<'_>) {
+        // the ` |` after the line numbers
+        let gutter = self.prefix + 2;
+        let width = match event {
+            Event::BeginSnippet {
+                path,
+                ranges,
+                gutter_width,
+                tag_width,
+            } => {
+                self.prefix = gutter_width + if tag_width > 0 { tag_width + 1 } else { 0 };
+                let path = path.map_or(0, |path| path.chars().count() + 1);
+                self.prefix + "--> ".len() + path + ranges.chars().count()
+            }
+            Event::GutterLine => gutter,
+            Event::CodeLine {
+                code,
+                box_column,
+                margin,
+                ..
+            } => {
+                let code_width = code.chars().count();
+                let code_len = render::code_len(code, self.output_version);
+                let padding = box_column.map_or(0, |column| column.saturating_sub(code_len) + 1);
+                let margin = margin.map_or(0, |_| 2);
+                gutter + 1 + margin + code_width + padding
+            }
+            Event::Marker {
+                kind,
+                column,
+                width,
+                label,
+                ..
+            } => {
+                let (column, width) = render::clip_marker(kind, column, width, self.max_width);
+                let marker = match kind {
+                    MarkerKind::Underline => width,
+                    MarkerKind::BoxTop | MarkerKind::BoxBottom => width + 2,
+                    // the `_` line starts at the margin
+                    MarkerKind::MultiLineStart | MarkerKind::MultiLineEnd => 3,
+                };
+                let label = label.map_or(0, |label| label.chars().count() + 1);
+                gutter + 1 + column + marker + label
+            }
+            Event::Ellipsis => self.prefix + self.lines_omitted,
+            Event::EndSnippet => return,
+        };
+        self.width = self.width.max(width);
+        self.height += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{debug_span_with_options, LineColumnSpan, NamedSource, TaggedSource};
+
+    fn rendered_size(span: LineColumnSpan, code: &impl SourceCode, options: &DebugSpanOptions) {
+        let output = debug_span_with_options(span, code, options);
+        let width = output.lines().map(|line| line.chars().count()).max();
+        let size = (width.unwrap_or(0), output.lines().count());
+        assert_eq!(measure(span, code, options), size, "{}", output);
+    }
+
+    #[test]
+    fn test_measure() {
+        let code = NamedSource::new("src/lib.rs", "struct Foo {\n    a: i32,\n}\n// ünïcode");
+        let options = DebugSpanOptions {
+            label: Some("expected ident".to_string()),
+            ..Default::default()
+        };
+        rendered_size(LineColumnSpan::new(1, 7, 1, 10), &code, &options);
+        rendered_size(LineColumnSpan::new(1, 11, 3, 1), &code, &options);
+        rendered_size(LineColumnSpan::new(4, 3, 4, 10), &code, &options);
+        let rustc = DebugSpanOptions {
+            multi_line_style: crate::MultiLineStyle::Rustc,
+            context: 1,
+            ..options.clone()
+        };
+        rendered_size(LineColumnSpan::new(1, 11, 3, 1), &code, &rustc);
+        rendered_size(LineColumnSpan::new(2, 4, 3, 1), &code, &rustc);
+        let max_lines = DebugSpanOptions {
+            max_lines: Some(2),
+            ..rustc.clone()
+        };
+        rendered_size(LineColumnSpan::new(1, 11, 4, 3), &code, &max_lines);
+        let max_width = DebugSpanOptions {
+            max_width: Some(6),
+            ..options.clone()
+        };
+        rendered_size(LineColumnSpan::new(1, 7, 1, 10), &code, &max_width);
+        rendered_size(LineColumnSpan::new(1, 11, 4, 3), &code, &max_width);
+        let options = DebugSpanOptions {
+            context: 2,
+            provenance: true,
+            ..Default::default()
+        };
+        let code = TaggedSource::new(code, "user").tag_lines(2..=3, "generated");
+        rendered_size(LineColumnSpan::new(2, 4, 3, 1), &code, &options);
+        rendered_size(LineColumnSpan::new(4, 0, 4, 2), &code, &options);
+        assert_eq!(
+            measure(LineColumnSpan::new(1, 1, 1, 1), &code, &options),
+            (0, 0)
+        );
+        let options = DebugSpanOptions {
+            show_empty_spans: true,
+            ..options
+        };
+        rendered_size(LineColumnSpan::new(1, 1, 1, 1), &code, &options);
+    }
+}
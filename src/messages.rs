@@ -0,0 +1,100 @@
+use alloc::string::String;
+
+/// The fixed words of the renderings, see
+/// [`DebugSpanOptions::messages`](crate::DebugSpanOptions::messages).
+///
+/// Tools built on this crate can translate them to localize their output, the defaults are in
+/// English.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{DebugSpanOptions, Messages};
+///
+/// let options = DebugSpanOptions {
+///     messages: Messages {
+///         error: "erreur".to_string(),
+///         lines_omitted: "[...]".to_string(),
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+/// assert_eq!(options.messages.level("error"), "erreur");
+/// assert_eq!(options.messages.level("failure-note"), "failure-note");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Messages {
+    /// `error`
+    pub error: String,
+    /// `warning`
+    pub warning: String,
+    /// `help`
+    pub help: String,
+    /// `note`
+    pub note: String,
+    /// The row between non-adjacent lines, `...`.
+    pub lines_omitted: String,
+    /// The note after the marker of an empty span, `(empty span)`, see
+    /// [`DebugSpanOptions::show_empty_spans`](crate::DebugSpanOptions::show_empty_spans).
+    pub empty_span: String,
+}
+
+impl Messages {
+    /// Translates a diagnostic level, e.g. the `level` of a rustc JSON diagnostic. Unknown levels
+    /// are returned as is.
+    pub fn level<'a>(&'a self, level: &'a str) -> &'a str {
+        match level {
+            "error" => &self.error,
+            "warning" => &self.warning,
+            "help" => &self.help,
+            "note" => &self.note,
+            _ => level,
+        }
+    }
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self {
+            error: String::from("error"),
+            warning: String::from("warning"),
+            help: String::from("help"),
+            note: String::from("note"),
+            lines_omitted: String::from("..."),
+            empty_span: String::from("(empty span)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DebugSpanOptions, LineColumnSpan};
+
+    #[test]
+    fn test_lines_omitted() {
+        let code = "struct Foo {\n    a: i32,\n    b: i32,\n}";
+        let spans = [
+            LineColumnSpan::new(1, 7, 1, 10),
+            LineColumnSpan::new(3, 4, 3, 5),
+        ];
+        let options = DebugSpanOptions {
+            messages: Messages {
+                lines_omitted: String::from("[lignes omises]"),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let output = crate::internal::debug_spans_with_options(&spans, code, &options);
+        insta::assert_snapshot!(output, @r###"
+         --> 1:7..1:10, 3:4..3:5
+          |
+        1 | struct Foo {
+          |        ^^^
+         [lignes omises]
+        3 |     b: i32,
+          |     ^
+          |
+        "###);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{Charset, DebugSpanOptions, SourceCode, Span};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Maximum number of rows of a [`minimap`].
+const MAX_ROWS: usize = 32;
+
+/// Render a compact overview of where `spans` fall in the whole source code.
+///
+/// Every row stands for the same number of lines, chosen so that the minimap is at most 32 rows
+/// high, and is labeled with its first line number. Rows touched by a span are marked with `█`,
+/// the others with `·`.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{minimap, LineColumnSpan};
+///
+/// let code = "struct Foo;\n".repeat(100);
+/// let spans = [LineColumnSpan::new(10, 0, 10, 6), LineColumnSpan::new(50, 7, 52, 3)];
+/// let output = minimap(&spans, code.as_str());
+/// assert_eq!(output.lines().count(), 25);
+/// assert_eq!(output.lines().nth(2), Some(" 9 | █"));
+/// ```
+pub fn minimap<S: Span>(spans: &[S], code: &(impl SourceCode + ?Sized)) -> String {
+    minimap_with_options(spans, code, &DebugSpanOptions::default())
+}
+
+/// Render a [`minimap`] with custom [`DebugSpanOptions`], only the
+/// [charset](DebugSpanOptions::charset) is used: the [ASCII](Charset::Ascii) one marks the rows
+/// with `#` and `.`.
+pub fn minimap_with_options<S: Span>(
+    spans: &[S],
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> String {
+    let (marked_row, unmarked_row) = match options.charset {
+        Charset::Unicode => ('█', '·'),
+        Charset::Ascii => ('#', '.'),
+    };
+    let line_count = code.line_count();
+    if line_count == 0 {
+        return String::new();
+    }
+    let lines_per_row = line_count.div_ceil(MAX_ROWS);
+    let row_count = line_count.div_ceil(lines_per_row);
+    let mut marked = vec![false; row_count];
+    for span in spans {
+        let first = span.start_line().max(1);
+        let last = span.end_line().min(line_count);
+        if first > last {
+            continue;
+        }
+        for row in marked
+            .iter_mut()
+            .take((last - 1) / lines_per_row + 1)
+            .skip((first - 1) / lines_per_row)
+        {
+            *row = true;
+        }
+    }
+
+    let rows = marked
+        .iter()
+        .enumerate()
+        .map(|(row, &marked)| {
+            let line_number = code.line_origin(row * lines_per_row + 1).1;
+            (line_number, if marked { marked_row } else { unmarked_row })
+        })
+        .collect::<Vec<_>>();
+    let width = rows
+        .iter()
+        .map(|(line_number, _)| line_number.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let mut output = String::new();
+    for (line_number, mark) in rows {
+        output.push_str(&format!(
+            "{: >width$} | {}\n",
+            line_number,
+            mark,
+            width = width
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+
+    #[test]
+    fn test_minimap() {
+        let code = "struct Foo;\n".repeat(10);
+        let spans = [
+            LineColumnSpan::new(2, 0, 2, 6),
+            LineColumnSpan::new(5, 7, 7, 3),
+        ];
+        insta::assert_snapshot!(minimap(&spans, code.as_str()), @r###"
+         1 | ·
+         2 | █
+         3 | ·
+         4 | ·
+         5 | █
+         6 | █
+         7 | █
+         8 | ·
+         9 | ·
+        10 | ·
+        "###);
+
+        let code = "struct Foo;\n".repeat(70);
+        let spans = [LineColumnSpan::new(35, 0, 36, 6)];
+        insta::assert_snapshot!(minimap(&spans, code.as_str()), @r###"
+         1 | ·
+         4 | ·
+         7 | ·
+        10 | ·
+        13 | ·
+        16 | ·
+        19 | ·
+        22 | ·
+        25 | ·
+        28 | ·
+        31 | ·
+        34 | █
+        37 | ·
+        40 | ·
+        43 | ·
+        46 | ·
+        49 | ·
+        52 | ·
+        55 | ·
+        58 | ·
+        61 | ·
+        64 | ·
+        67 | ·
+        70 | ·
+        "###);
+
+        let options = DebugSpanOptions {
+            charset: Charset::Ascii,
+            ..Default::default()
+        };
+        let spans = [LineColumnSpan::new(2, 0, 2, 6)];
+        let output = minimap_with_options(&spans, "struct Foo;\n".repeat(3).as_str(), &options);
+        insta::assert_snapshot!(output, @r###"
+        1 | .
+        2 | #
+        3 | .
+        "###);
+        assert_eq!(minimap(&spans, ""), "");
+    }
+}
@@ -0,0 +1,254 @@
+use crate::render::{
+    display_column, display_line_number_width, display_range, display_width, finish, source_line,
+    tag_width, visit_code_line, TextVisitor,
+};
+use crate::{DebugSpanOptions, Event, LineColumnSpan, MarkerKind, SourceCode, Span, Visitor};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Render several spans in a single frame.
+///
+/// Every line touched by a span is shown once, followed by one marker row per span on that line.
+/// Multi-line spans are marked from their start column to the end of the start line, and from the
+/// start of the end line to their end column. Non-adjacent lines are separated by a `...` row.
+/// Empty spans are skipped. Spans are drawn in the given order, or sorted by position with
+/// [`DebugSpanOptions::deterministic`].
+pub fn debug_spans_with_options(
+    spans: &[LineColumnSpan],
+    source: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> String {
+    if options.prose {
+        return crate::prose::describe(spans, source, options);
+    }
+    let spans = spans.iter().map(|span| (*span, None)).collect::<Vec<_>>();
+    debug_labeled_spans_with_options(&spans, source, options)
+}
+
+/// Render several spans in a single frame, each with an optional label after its last marker.
+pub fn debug_labeled_spans_with_options(
+    spans: &[(LineColumnSpan, Option<&str>)],
+    source: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> String {
+    if options.prose {
+        return crate::prose::describe_labeled(spans, source, options);
+    }
+    let mut visitor = TextVisitor::new(options);
+    visit_labeled_spans(spans, source, options, &mut visitor);
+    finish(visitor.output, options)
+}
+
+/// Emits the events of the rendering of several spans in a single frame.
+pub fn visit_spans(
+    spans: &[LineColumnSpan],
+    source: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+    visitor: &mut impl Visitor,
+) {
+    let spans = spans.iter().map(|span| (*span, None)).collect::<Vec<_>>();
+    visit_labeled_spans(&spans, source, options, visitor)
+}
+
+/// Emits the events of the rendering of several labeled spans in a single frame.
+pub fn visit_labeled_spans(
+    spans: &[(LineColumnSpan, Option<&str>)],
+    source: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+    visitor: &mut impl Visitor,
+) {
+    let mut spans = spans
+        .iter()
+        .filter(|(span, _)| !span.is_empty())
+        .collect::<Vec<_>>();
+    if options.deterministic {
+        spans.sort();
+    }
+    if spans.is_empty() {
+        return;
+    }
+
+    let line_count = source.line_count();
+    let mut line_numbers = spans
+        .iter()
+        .flat_map(|(span, _)| {
+            let first = span.start_line().saturating_sub(options.context).max(1);
+            let last = (span.end_line() + options.context).min(line_count);
+            first..=last
+        })
+        .collect::<Vec<_>>();
+    line_numbers.sort_unstable();
+    line_numbers.dedup();
+
+    let width = display_line_number_width(source, line_numbers.iter().copied());
+    let ranges = spans
+        .iter()
+        .map(|(span, _)| display_range(span, source))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    visitor.visit(Event::BeginSnippet {
+        path: source.line_origin(spans[0].0.start_line).0,
+        ranges: &ranges,
+        gutter_width: width,
+        tag_width: tag_width(source, line_numbers.iter().copied(), options),
+    });
+    // the label of a span follows its last marker
+    let last_marked_lines = spans
+        .iter()
+        .map(|(span, _)| {
+            line_numbers
+                .iter()
+                .copied()
+                .rfind(|&line_number| marked_columns(span, line_number, source, options).is_some())
+        })
+        .collect::<Vec<_>>();
+    visitor.visit(Event::GutterLine);
+    let mut previous = None;
+    for line_number in line_numbers {
+        if previous.is_some_and(|previous| previous + 1 != line_number) {
+            visitor.visit(Event::Ellipsis);
+        }
+        previous = Some(line_number);
+        visit_code_line(line_number, source, options, None, None, visitor);
+        for (index, (span, label)) in spans.iter().enumerate() {
+            if let Some((start, end)) = marked_columns(span, line_number, source, options) {
+                visitor.visit(Event::Marker {
+                    kind: MarkerKind::Underline,
+                    column: start,
+                    width: end - start,
+                    label: label.filter(|_| last_marked_lines[index] == Some(line_number)),
+                    index,
+                });
+            }
+        }
+    }
+    visitor.visit(Event::GutterLine);
+    visitor.visit(Event::EndSnippet);
+}
+
+/// Returns the marked range of displayed columns of `span` on the given line, if any.
+fn marked_columns(
+    span: &LineColumnSpan,
+    line_number: usize,
+    source: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> Option<(usize, usize)> {
+    let column = |column| display_column(source, line_number, column, options);
+    let (start, end) = if span.is_single_line() {
+        if line_number != span.start_line {
+            return None;
+        }
+        (column(span.start_column), column(span.end_column))
+    } else if line_number == span.start_line {
+        let line_len = display_width(&source_line(source, line_number, options));
+        (column(span.start_column), line_len)
+    } else if line_number == span.end_line {
+        (0, column(span.end_column))
+    } else {
+        return None;
+    };
+    (start < end).then_some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unindent::Unindent;
+
+    #[test]
+    fn test_debug_spans_shared_line() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+            }
+        "###
+        .unindent();
+        let spans = [
+            LineColumnSpan::new(2, 4, 2, 5),
+            LineColumnSpan::new(2, 7, 2, 10),
+        ];
+        let output = debug_spans_with_options(&spans, &input, &DebugSpanOptions::default());
+        insta::assert_snapshot!(output, @r###"
+         --> 2:4..2:5, 2:7..2:10
+          |
+        2 |     a: i32,
+          |     ^
+          |        ^^^
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_debug_spans_gap() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+                b: i32,
+                c: i32,
+            }
+        "###
+        .unindent();
+        let spans = [
+            LineColumnSpan::new(2, 4, 2, 10),
+            LineColumnSpan::new(4, 4, 4, 10),
+            LineColumnSpan::new(1, 11, 5, 1),
+        ];
+        let output = debug_spans_with_options(&spans, &input, &DebugSpanOptions::default());
+        insta::assert_snapshot!(output, @r###"
+         --> 2:4..2:10, 4:4..4:10, 1:11..5:1
+          |
+        1 | struct Foo {
+          |            ^
+        2 |     a: i32,
+          |     ^^^^^^
+        3 |     b: i32,
+        4 |     c: i32,
+          |     ^^^^^^
+        5 | }
+          | ^
+          |
+        "###);
+        let output = debug_spans_with_options(&spans[..2], &input, &DebugSpanOptions::default());
+        insta::assert_snapshot!(output, @r###"
+         --> 2:4..2:10, 4:4..4:10
+          |
+        2 |     a: i32,
+          |     ^^^^^^
+         ...
+        4 |     c: i32,
+          |     ^^^^^^
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_debug_spans_deterministic() {
+        let input = "struct Foo {\r\n    a: i32, b: i32,\r\r\n}";
+        let spans = [
+            LineColumnSpan::new(2, 12, 2, 13),
+            LineColumnSpan::new(2, 4, 2, 5),
+        ];
+        let options = DebugSpanOptions {
+            deterministic: true,
+            ..Default::default()
+        };
+        let output = debug_spans_with_options(&spans, input, &options);
+        insta::assert_snapshot!(output, @r###"
+         --> 2:4..2:5, 2:12..2:13
+          |
+        2 |     a: i32, b: i32,
+          |     ^
+          |             ^
+          |
+        "###);
+        assert!(!output.contains('\r'));
+    }
+
+    #[test]
+    fn test_debug_spans_empty() {
+        let spans = [LineColumnSpan::new(1, 0, 1, 0)];
+        let output = debug_spans_with_options(&spans, "struct Foo;", &DebugSpanOptions::default());
+        assert_eq!(output, "");
+    }
+}
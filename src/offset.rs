@@ -0,0 +1,101 @@
+use crate::{LineColumnSpan, SourceCode, Span};
+use core::ops::Range;
+
+/// A span made of byte offsets, for byte based workflows like string slicing or parsers
+/// reporting offsets.
+///
+/// It converts to and from line/column spans through a [`SourceCode`].
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span, LineColumnSpan, OffsetSpan};
+///
+/// let code = "struct Foo {\n    a: i32,\n}";
+/// let offsets = OffsetSpan::new(17, 23);
+/// let span = offsets.to_span(code).unwrap();
+/// assert_eq!(span, LineColumnSpan::new(2, 4, 2, 10));
+/// assert_eq!(OffsetSpan::from_span(span, code), Some(offsets));
+/// assert_eq!(&code[offsets.range()], "a: i32");
+/// let output = debug_span(span, code);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct OffsetSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl OffsetSpan {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Convert a line/column span into byte offsets, or `None` if it points outside of `code`.
+    pub fn from_span(span: impl Span, code: &(impl SourceCode + ?Sized)) -> Option<Self> {
+        let start = code.byte_offset(span.start_line(), span.start_column())?;
+        let end = code.byte_offset(span.end_line(), span.end_column())?;
+        Some(Self { start, end })
+    }
+
+    /// Convert the byte offsets into a line/column span, or `None` if they are out of range or
+    /// not on char boundaries.
+    pub fn to_span(&self, code: &(impl SourceCode + ?Sized)) -> Option<LineColumnSpan> {
+        let (start_line, start_column) = code.line_column(self.start)?;
+        let (end_line, end_column) = code.line_column(self.end)?;
+        Some(LineColumnSpan::new(
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        ))
+    }
+
+    /// Returns the byte range.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    pub fn len(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<Range<usize>> for OffsetSpan {
+    fn from(range: Range<usize>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+impl From<OffsetSpan> for Range<usize> {
+    fn from(span: OffsetSpan) -> Self {
+        span.range()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceText;
+
+    #[test]
+    fn test_offset_span() {
+        let code = "struct Foo {\n    é: i32,\n}";
+        let span = LineColumnSpan::new(2, 4, 3, 1);
+        let offsets = OffsetSpan::from_span(span, code).unwrap();
+        assert_eq!(offsets, OffsetSpan::from(17..27));
+        assert_eq!(offsets.to_span(code), Some(span));
+        assert_eq!(offsets.to_span(&SourceText::new(code)), Some(span));
+        assert_eq!(&code[offsets.range()], "é: i32,\n}");
+
+        assert_eq!(
+            OffsetSpan::from_span(LineColumnSpan::new(4, 0, 4, 1), code),
+            None
+        );
+        assert_eq!(OffsetSpan::new(18, 20).to_span(code), None);
+        assert_eq!(OffsetSpan::new(0, 100).to_span(code), None);
+    }
+}
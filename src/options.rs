@@ -0,0 +1,169 @@
+use crate::{ColorDepth, Messages, SourceLink, Theme};
+use alloc::string::String;
+
+/// Options controlling how a span is rendered.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span_with_options, Charset, DebugSpanOptions, LineColumnSpan};
+///
+/// let options = DebugSpanOptions {
+///     label: Some("expected ident".to_string()),
+///     charset: Charset::Ascii,
+///     ..Default::default()
+/// };
+/// let output = debug_span_with_options(LineColumnSpan::new(1, 7, 1, 10), "struct Foo;", &options);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugSpanOptions {
+    /// A text attached to the marker, e.g. `^^^ expected ident`.
+    pub label: Option<String>,
+    /// Number of lines shown before and after the span, e.g. to see which item it belongs to.
+    ///
+    /// Context lines are numbered like the spanned lines and stop at the start and the end of the
+    /// code. Also set by the `context` key of config files and the `--context` flag of the
+    /// command line.
+    pub context: usize,
+    /// Maximum number of lines shown for a multi-line span, the lines in the middle of taller
+    /// spans are replaced with a [`lines_omitted`](Messages::lines_omitted) row. At least the
+    /// first and the last lines are shown.
+    pub max_lines: Option<usize>,
+    /// Maximum number of chars shown of a code line, longer lines are cut and end with `…`. The
+    /// markers past the cut point at the `…`.
+    pub max_width: Option<usize>,
+    /// Tabs are expanded up to the next multiple of this number of columns, `4` by default like
+    /// rustc. `0` keeps the tabs, counted as a single column like in [`OutputVersion::V1`].
+    pub tab_width: usize,
+    /// Characters used to draw the multi-line box.
+    pub charset: Charset,
+    /// How a multi-line span is drawn in renderings of a single span.
+    pub multi_line_style: MultiLineStyle,
+    /// Version of the text format, pin it to keep existing snapshots while upgrading the crate.
+    pub output_version: OutputVersion,
+    /// Guarantee reproducible output across platforms, intended for snapshot tests.
+    ///
+    /// Multiple spans are rendered sorted by position instead of in the given order, and stray
+    /// `\r` line endings are removed from the displayed code even by [`OutputVersion::V1`]. The
+    /// output never contains file paths that were not explicitly provided, nor color or
+    /// hyperlink escapes.
+    pub deterministic: bool,
+    /// Show the [provenance tag](crate::SourceCode::line_tag) of every code line in a column left
+    /// of the line numbers.
+    pub provenance: bool,
+    /// Describe the spans in plain sentences instead of drawing them, e.g. for screen readers:
+    /// ``span starts at line 1 column 7 and ends at line 1 column 10, covering the text `Foo` in:
+    /// struct Foo;``. The other drawing options are ignored.
+    pub prose: bool,
+    /// Render an empty span, e.g. the span of a missing token, as a `^` at its position followed
+    /// by the [`empty_span`](Messages::empty_span) note. Renderings of a single span are empty
+    /// otherwise.
+    pub show_empty_spans: bool,
+    /// Colors the gutter, markers, boxes and labels with ANSI escapes. Ignored by
+    /// [`deterministic`](Self::deterministic) renderings and HTML highlighting.
+    pub theme: Option<Theme>,
+    /// Colors used by the [`theme`](Self::theme), see [`ColorDepth::detect`].
+    pub color_depth: ColorDepth,
+    /// Turns the line numbers and the header of [HTML](crate::HtmlRenderer) renderings into
+    /// links to the source. Sources without a [path](crate::SourceCode::path) are not linked.
+    pub link: Option<SourceLink>,
+    /// The fixed words of the renderings, to localize them.
+    pub messages: Messages,
+    /// Syntax highlighting of the code lines, the gutter, markers and box characters are not
+    /// styled. Ignored by [`deterministic`](Self::deterministic) renderings.
+    #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+    pub highlight: Option<Highlight>,
+    /// Backend used by the [`highlight`](Self::highlight) option.
+    #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+    pub highlighter: Highlighter,
+}
+
+impl Default for DebugSpanOptions {
+    fn default() -> Self {
+        Self {
+            label: None,
+            context: 0,
+            max_lines: None,
+            max_width: None,
+            tab_width: 4,
+            charset: Charset::default(),
+            multi_line_style: MultiLineStyle::default(),
+            output_version: OutputVersion::default(),
+            deterministic: false,
+            provenance: false,
+            prose: false,
+            show_empty_spans: false,
+            theme: None,
+            color_depth: ColorDepth::default(),
+            link: None,
+            messages: Messages::default(),
+            #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+            highlight: None,
+            #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+            highlighter: Highlighter::default(),
+        }
+    }
+}
+
+/// Output format of the syntax highlighting, behind the `syntect` and `tree-sitter` features.
+#[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Highlight {
+    /// ANSI truecolor escapes for terminals.
+    Ansi,
+    /// HTML, with `<span style="...">` elements around the highlighted code. The rest of the
+    /// rendering is HTML-escaped, the output is meant to be placed in a `<pre>` element.
+    Html,
+}
+
+/// Syntax highlighting backend, defaults to syntect when both backends are enabled.
+#[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Highlighter {
+    /// syntect with the `base16-ocean.dark` theme, behind the `syntect` feature.
+    #[cfg(feature = "syntect")]
+    #[default]
+    Syntect,
+    /// tree-sitter-rust with a `base16-ocean.dark` palette, behind the `tree-sitter` feature.
+    #[cfg(feature = "tree-sitter")]
+    #[cfg_attr(not(feature = "syntect"), default)]
+    TreeSitter,
+}
+
+/// Characters used to draw the rendering.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Charset {
+    /// Box drawing characters: `┌ ─ ╮ │ └ ╯`.
+    #[default]
+    Unicode,
+    /// Plain ASCII characters: `+ - |`.
+    Ascii,
+}
+
+/// How a multi-line span is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MultiLineStyle {
+    /// A box around the spanned code, closed on the right of the lines.
+    #[default]
+    Box,
+    /// Like rustc diagnostics: a `|` margin left of the spanned lines, joined to the start and
+    /// the end of the span by `_` lines. The margin starts with `/` on the first line when the
+    /// span starts at the indentation of the line.
+    Rustc,
+}
+
+/// Version of the text format.
+///
+/// New versions change the output, e.g. to make it friendlier to inline snapshots, the old
+/// versions keep rendering byte for byte as they did, so snapshots can be migrated one at a time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OutputVersion {
+    /// The format of the 0.2 releases.
+    V1,
+    /// Empty code lines end with the gutter, without the trailing space that editors strip from
+    /// inline snapshots, the box of multi-line spans is aligned by chars instead of bytes and
+    /// tabs are expanded, see [`tab_width`](DebugSpanOptions::tab_width). Stray `\r` line endings
+    /// are always removed from the displayed code.
+    #[default]
+    V2,
+}
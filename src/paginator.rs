@@ -0,0 +1,184 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Splits long renderings in pages of a fixed number of lines.
+///
+/// A frame cut by a page break gets its `-->` header and the gutter line under it repeated at
+/// the top of the next page, so every page tells which source it shows. Blank lines separating
+/// the frames of a [report](crate::SourceMap::debug_report) are dropped at the edges of a page.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{LineColumnSpan, Paginator, SourceMap};
+///
+/// let mut source_map = SourceMap::new();
+/// let file = source_map.add("lib.rs", "struct A;\nstruct B;\nstruct C;");
+/// let output = source_map.debug_report([
+///     (file, LineColumnSpan::new(1, 7, 1, 8)),
+///     (file, LineColumnSpan::new(3, 7, 3, 8)),
+/// ]);
+/// let pages = Paginator::new(5).paginate(&output);
+/// assert_eq!(pages.len(), 2);
+/// assert!(pages[1].starts_with(" --> lib.rs:1:7..1:8, 3:7..3:8\n  |\n"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Paginator {
+    page_height: usize,
+}
+
+/// Number of lines repeated at the top of a page: the header and the gutter line under it.
+const HEADER_LINES: usize = 2;
+
+impl Paginator {
+    /// Create a paginator with pages of `page_height` lines.
+    ///
+    /// Pages hold at least the repeated header and one more line, so heights under 3 are raised
+    /// to 3.
+    pub fn new(page_height: usize) -> Self {
+        Self {
+            page_height: page_height.max(HEADER_LINES + 1),
+        }
+    }
+
+    /// Returns the number of lines of a page.
+    pub fn page_height(&self) -> usize {
+        self.page_height
+    }
+
+    /// Split `rendering` in pages, every line of a page ends with a newline.
+    pub fn paginate(&self, rendering: &str) -> Vec<String> {
+        let lines = rendering.lines().collect::<Vec<_>>();
+        let mut pages = Vec::new();
+        let mut page = Vec::new();
+        let mut header = None;
+        for (index, line) in lines.iter().enumerate() {
+            if is_header(line) {
+                header = Some(index);
+            } else if line.is_empty() {
+                header = None;
+            }
+            if page.len() == self.page_height {
+                pages.push(join(&page));
+                page.clear();
+                if let Some(header) = header {
+                    page.extend(&lines[header..index.min(header + HEADER_LINES)]);
+                }
+            }
+            if page.is_empty() && line.is_empty() {
+                continue;
+            }
+            page.push(*line);
+        }
+        if !page.is_empty() {
+            pages.push(join(&page));
+        }
+        pages
+    }
+
+    /// Write the pages of `rendering` to numbered files `page-1.txt`, `page-2.txt`, ... in
+    /// `dir`, and return their paths.
+    ///
+    /// The numbers are padded with zeros to the width of the last one, so the files sort in
+    /// page order. `dir` is created if needed.
+    #[cfg(feature = "std")]
+    pub fn write_pages(
+        &self,
+        rendering: &str,
+        dir: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<Vec<std::path::PathBuf>> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let pages = self.paginate(rendering);
+        let width = pages.len().to_string().len();
+        let mut paths = Vec::with_capacity(pages.len());
+        for (index, page) in pages.iter().enumerate() {
+            let path = dir.join(format!("page-{:0width$}.txt", index + 1, width = width));
+            std::fs::write(&path, page)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+/// Tells if `line` is a `-->` header line, possibly colored.
+fn is_header(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("-->") || (line.starts_with('\x1b') && line.contains("m-->"))
+}
+
+/// Joins the lines of a page, without the blank lines at its end.
+fn join(mut lines: &[&str]) -> String {
+    while let [rest @ .., ""] = lines {
+        lines = rest;
+    }
+    let mut page = String::new();
+    for line in lines {
+        page.push_str(line);
+        page.push('\n');
+    }
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LineColumnSpan, SourceMap};
+
+    #[test]
+    fn test_paginate() {
+        let mut source_map = SourceMap::new();
+        let a = source_map.add("a.rs", "struct A {\n    b: B,\n    c: C,\n}");
+        let b = source_map.add("b.rs", "struct B;");
+        let output = source_map.debug_report([
+            (a, LineColumnSpan::new(1, 7, 1, 8)),
+            (a, LineColumnSpan::new(2, 7, 2, 8)),
+            (a, LineColumnSpan::new(3, 7, 3, 8)),
+            (b, LineColumnSpan::new(1, 7, 1, 8)),
+        ]);
+        let pages = Paginator::new(6).paginate(&output);
+        insta::assert_snapshot!(pages.join("---\n"), @r###"
+         --> a.rs:1:7..1:8, 2:7..2:8, 3:7..3:8
+          |
+        1 | struct A {
+          |        ^
+        2 |     b: B,
+          |        ^
+        ---
+         --> a.rs:1:7..1:8, 2:7..2:8, 3:7..3:8
+          |
+        3 |     c: C,
+          |        ^
+          |
+        ---
+         --> b.rs:1:7..1:8
+          |
+        1 | struct B;
+          |        ^
+          |
+        "###);
+
+        assert_eq!(Paginator::new(0).page_height(), 3);
+        assert_eq!(Paginator::new(100).paginate(&output), [output]);
+        assert!(Paginator::new(4).paginate("").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_write_pages() {
+        let dir = std::env::temp_dir().join(format!("debug-span-pages-{}", std::process::id()));
+        let rendering = (1..=30)
+            .map(|line| format!("{}\n", line))
+            .collect::<String>();
+        let paths = Paginator::new(3).write_pages(&rendering, &dir).unwrap();
+        let names = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        let last = std::fs::read_to_string(&paths[9]).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(names.first().unwrap(), "page-01.txt");
+        assert_eq!(names.last().unwrap(), "page-10.txt");
+        assert_eq!(last, "28\n29\n30\n");
+    }
+}
@@ -0,0 +1,28 @@
+//! Commonly used items, for a single glob import in test files.
+//!
+//! ```rust
+//! use debug_span::prelude::*;
+//!
+//! let input = "struct Foo;";
+//! let span = LineColumnSpan::new(1, 7, 1, 10);
+//! assert_span_eq!(span, span, input);
+//! let output = debug(span).code(input).label("ident").render();
+//! assert_eq!(try_debug_span(span, input), Ok(debug_span(span, input)));
+//! assert_eq!(output, debug_span_with_label(span, input, "ident"));
+//! ```
+
+pub use crate::{
+    assert_span_eq, debug, debug_span, debug_span_with_label, debug_span_with_options, debug_spans,
+    debug_spans_with_labels, find_nth_span, find_span, panic_with_span, span_text, to_byte_range,
+    try_debug_span, Charset, DebugSpanError, DebugSpanOptions, LineColumnSpan, NamedSource,
+    SourceCode, SourceText, Span, SpanChecker, SpanExt,
+};
+
+#[cfg(feature = "macros")]
+pub use crate::debug_span_here;
+#[cfg(feature = "proc-macro2")]
+pub use crate::TokenStreamExt;
+#[cfg(feature = "std")]
+pub use crate::{dbg_span, debug_span_in_file, eprint_span, print_span};
+#[cfg(feature = "syn")]
+pub use crate::{debug_all, ResultExt, SpannedExt};
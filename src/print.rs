@@ -0,0 +1,266 @@
+use crate::{DebugSpanOptions, SourceCode, Span};
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+/// Render a span against the code and print it to stdout, optionally preceded by a message.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::print_span;
+///
+/// let input = "struct Foo;";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+/// let span = derive_input.ident.span();
+///
+/// print_span!(span, input);
+/// print_span!(span, input, "ident of {}", "Foo");
+/// ```
+///
+/// Prints:
+///
+/// ```text
+///  --> 1:7..1:10
+///   |
+/// 1 | struct Foo;
+///   |        ^^^
+///   |
+/// ident of Foo
+///  --> 1:7..1:10
+///   |
+/// 1 | struct Foo;
+///   |        ^^^
+///   |
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! print_span {
+    ($span:expr, $code:expr $(,)?) => {
+        ::std::print!(
+            "{}",
+            $crate::internal::span_message(&$span, $code, None, &::core::default::Default::default())
+        )
+    };
+    ($span:expr, $code:expr, $($arg:tt)+) => {
+        ::std::print!(
+            "{}",
+            $crate::internal::span_message(
+                &$span,
+                $code,
+                Some(::std::format_args!($($arg)+)),
+                &::core::default::Default::default(),
+            )
+        )
+    };
+}
+
+/// Render a span against the code and print it to stderr, optionally preceded by a message.
+///
+/// When the `DEBUG_SPAN_OUT` environment variable is set, the output is appended to the file it
+/// names instead, preceded by an invocation counter and a timestamp. cargo doesn't always show
+/// the stderr of proc-macros, the file keeps the output of every expansion. It also applies to
+/// [`dbg_span!`](crate::dbg_span!) and `debug_span_here!`.
+///
+/// See [`print_span!`] for more information.
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! eprint_span {
+    ($span:expr, $code:expr $(,)?) => {
+        $crate::internal::eprint(&$crate::internal::span_message(
+            &$span,
+            $code,
+            None,
+            &$crate::internal::stderr_options(),
+        ))
+    };
+    ($span:expr, $code:expr, $($arg:tt)+) => {
+        $crate::internal::eprint(&$crate::internal::span_message(
+            &$span,
+            $code,
+            Some(::std::format_args!($($arg)+)),
+            &$crate::internal::stderr_options(),
+        ))
+    };
+}
+
+/// Print the call site, the span expression and the rendered snippet to stderr, and return the
+/// span.
+///
+/// The output can be redirected to a file with `DEBUG_SPAN_OUT`, see [`eprint_span!`].
+///
+/// Like [`dbg!`], it can be dropped into the middle of an expression without restructuring the
+/// code.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::dbg_span;
+///
+/// let input = "struct Foo;";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+/// let span = dbg_span!(derive_input.ident.span(), input);
+/// # let _ = span;
+/// ```
+///
+/// Prints:
+///
+/// ```text
+/// [src/main.rs:6:12] derive_input.ident.span()
+///  --> 1:7..1:10
+///   |
+/// 1 | struct Foo;
+///   |        ^^^
+///   |
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! dbg_span {
+    ($span:expr, $code:expr $(,)?) => {
+        match $span {
+            span => {
+                $crate::internal::eprint(&::std::format!(
+                    "[{}:{}:{}] {}\n{}",
+                    ::std::file!(),
+                    ::std::line!(),
+                    ::std::column!(),
+                    ::std::stringify!($span),
+                    $crate::internal::debug_span_with_options(
+                        &span,
+                        $code,
+                        &$crate::internal::stderr_options(),
+                    ),
+                ));
+                span
+            }
+        }
+    };
+}
+
+/// Panic with a message followed by the rendered snippet of the span.
+///
+/// Useful inside proc-macros during development: the compiler surfaces the panic message, and a
+/// visual location is easier to follow than a bare line and column.
+///
+/// # Example
+///
+/// ```rust,should_panic
+/// use debug_span::panic_with_span;
+///
+/// let input = "struct Foo;";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+/// panic_with_span(derive_input.ident.span(), input, "unexpected ident");
+/// ```
+#[track_caller]
+pub fn panic_with_span(
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+    msg: impl fmt::Display,
+) -> ! {
+    panic!(
+        "{}",
+        span_message(
+            &span,
+            code,
+            Some(format_args!("{}", msg)),
+            &DebugSpanOptions::default()
+        )
+    )
+}
+
+pub fn span_message(
+    span: &(impl Span + ?Sized),
+    code: &(impl SourceCode + ?Sized),
+    args: Option<fmt::Arguments>,
+    options: &DebugSpanOptions,
+) -> String {
+    let snippet = crate::internal::debug_span_with_options(span, code, options);
+    match args {
+        Some(args) => format!("{}\n{}", args, snippet),
+        None => snippet,
+    }
+}
+
+/// The options of the renderings written to stderr, colored with the `color` feature unless
+/// `DEBUG_SPAN_OUT` redirects them to a file, see `ColorChoice::Auto`.
+pub fn stderr_options() -> DebugSpanOptions {
+    #[cfg(feature = "color")]
+    return colored_stderr_options(crate::ColorChoice::Auto);
+    #[cfg(not(feature = "color"))]
+    DebugSpanOptions::default()
+}
+
+#[cfg(feature = "color")]
+fn colored_stderr_options(choice: crate::ColorChoice) -> DebugSpanOptions {
+    let options = DebugSpanOptions::default();
+    if crate::sink::is_redirected() {
+        return options;
+    }
+    choice.options(&options, &std::io::stderr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+
+    #[test]
+    fn test_span_message() {
+        let input = "struct Foo;";
+        let span = LineColumnSpan::new(1, 7, 1, 10);
+        let args = Some(format_args!("ident of {}", "Foo"));
+        let output = span_message(&span, input, args, &DebugSpanOptions::default());
+        insta::assert_snapshot!(output, @r###"
+        ident of Foo
+         --> 1:7..1:10
+          |
+        1 | struct Foo;
+          |        ^^^
+          |
+        "###);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "unexpected ident\n --> 1:7..1:10\n  |\n1 | struct Foo;\n  |        ^^^\n"
+    )]
+    fn test_panic_with_span() {
+        let input = "struct Foo;";
+        let span = LineColumnSpan::new(1, 7, 1, 10);
+        panic_with_span(span, input, "unexpected ident");
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn test_stderr_options_redirected() {
+        let path =
+            std::env::temp_dir().join(format!("debug-span-out-color-{}.txt", std::process::id()));
+        std::env::set_var("DEBUG_SPAN_OUT", &path);
+        let span = LineColumnSpan::new(1, 7, 1, 10);
+        let options = colored_stderr_options(crate::ColorChoice::Always);
+        crate::sink::eprint(&span_message(&span, "struct Foo;", None, &options));
+        std::env::remove_var("DEBUG_SPAN_OUT");
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(content.contains("1 | struct Foo;"), "{}", content);
+        assert!(!content.contains('\x1b'), "{:?}", content);
+    }
+
+    #[test]
+    fn test_print_macros() {
+        let input = "struct Foo;";
+        let span = LineColumnSpan::new(1, 7, 1, 10);
+        print_span!(span, input);
+        print_span!(span, input, "ident of {}", "Foo");
+        eprint_span!(span, input);
+        eprint_span!(span, input, "ident of {}", "Foo");
+    }
+
+    #[test]
+    fn test_dbg_span() {
+        let input = "struct Foo;";
+        let span = LineColumnSpan::new(1, 7, 1, 10);
+        assert_eq!(dbg_span!(span, input), span);
+        assert_eq!(dbg_span!(&span, input), &span);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{DebugSpanOptions, LineColumnSpan, SourceCode, Span};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Describes the spans in plain sentences, one line per span, see
+/// [`DebugSpanOptions::prose`].
+pub fn describe(
+    spans: &[LineColumnSpan],
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> String {
+    let spans = spans
+        .iter()
+        .map(|span| (*span, options.label.as_deref()))
+        .collect::<Vec<_>>();
+    describe_labeled(&spans, code, options)
+}
+
+/// Describes spans with their own labels, the label of the options is not used.
+pub fn describe_labeled(
+    spans: &[(LineColumnSpan, Option<&str>)],
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> String {
+    let mut spans = spans
+        .iter()
+        .filter(|(span, _)| !span.is_empty())
+        .collect::<Vec<_>>();
+    if options.deterministic {
+        spans.sort();
+    }
+    let mut output = String::new();
+    if spans.len() > 1 {
+        output.push_str(&format!("{} spans:\n", spans.len()));
+    }
+    for (index, (span, label)) in spans.iter().enumerate() {
+        if let Some(label) = label {
+            output.push_str(&format!("{}: ", label));
+        }
+        output.push_str("span");
+        if spans.len() > 1 {
+            output.push_str(&format!(" {}", index + 1));
+        }
+        let (path, start_line) = code.line_origin(span.start_line);
+        if let Some(path) = path {
+            output.push_str(&format!(" in {}", path));
+        }
+        let end_line = code.line_origin(span.end_line).1;
+        output.push_str(&format!(
+            " starts at line {} column {} and ends at line {} column {}, {}\n",
+            start_line,
+            span.start_column,
+            end_line,
+            span.end_column,
+            covering(span, code),
+        ));
+    }
+    output
+}
+
+/// The rest of the sentence describing the text of the span.
+fn covering(span: &LineColumnSpan, code: &(impl SourceCode + ?Sized)) -> String {
+    let Some(text) = crate::text::get_source_span_text(span, code) else {
+        return String::from("outside of the source code");
+    };
+    if span.is_single_line() {
+        let line = code.line(span.start_line).unwrap_or_default();
+        return format!("covering the text `{}` in: {}", text, line.trim());
+    }
+    let lines = text.lines().map(str::trim).collect::<Vec<_>>();
+    format!(
+        "covering {} lines starting with `{}` and ending with `{}`",
+        lines.len(),
+        lines.first().copied().unwrap_or_default(),
+        lines.last().copied().unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NamedSource;
+
+    #[test]
+    fn test_describe() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        let options = DebugSpanOptions {
+            prose: true,
+            ..Default::default()
+        };
+        let output =
+            crate::debug_span_with_options(LineColumnSpan::new(1, 7, 1, 10), code, &options);
+        insta::assert_snapshot!(output, @"span starts at line 1 column 7 and ends at line 1 column 10, covering the text `Foo` in: struct Foo {");
+        let spans = [
+            LineColumnSpan::new(1, 11, 3, 1),
+            LineColumnSpan::new(2, 4, 2, 5),
+            LineColumnSpan::new(5, 0, 5, 1),
+        ];
+        let code = NamedSource::new("foo.rs", code);
+        let options = DebugSpanOptions {
+            label: Some(String::from("field")),
+            prose: true,
+            ..Default::default()
+        };
+        let output = crate::internal::debug_spans_with_options(&spans, &code, &options);
+        insta::assert_snapshot!(output, @r###"
+        3 spans:
+        field: span 1 in foo.rs starts at line 1 column 11 and ends at line 3 column 1, covering 3 lines starting with `{` and ending with `}`
+        field: span 2 in foo.rs starts at line 2 column 4 and ends at line 2 column 5, covering the text `a` in: a: i32,
+        field: span 3 in foo.rs starts at line 5 column 0 and ends at line 5 column 1, outside of the source code
+        "###);
+    }
+}
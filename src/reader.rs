@@ -0,0 +1,185 @@
+use crate::{DebugSpanOptions, SourceCode, Span};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Render a span of the code made of `lines`, only keeping the lines of the span and its
+/// context.
+///
+/// The iterator is consumed up to the last line of the rendering, so the code never has to be
+/// held in memory as a whole. Lines are given without their line endings.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span_from_lines, DebugSpanOptions, LineColumnSpan};
+///
+/// let lines = (1..).map(|line| format!("let a{} = {};", line, line));
+/// let span = LineColumnSpan::new(1000, 4, 1000, 9);
+/// let output = debug_span_from_lines(span, lines, &DebugSpanOptions::default());
+/// assert_eq!(
+///     output,
+///     "    --> 1000:4..1000:9\n     |\n1000 | let a1000 = 1000;\n     |     ^^^^^\n     |\n"
+/// );
+/// ```
+pub fn debug_span_from_lines<L: AsRef<str>>(
+    span: impl Span,
+    lines: impl IntoIterator<Item = L>,
+    options: &DebugSpanOptions,
+) -> String {
+    let first_line = first_line(&span, options);
+    let lines = lines
+        .into_iter()
+        .take(span.end_line() + options.context)
+        .skip(first_line - 1)
+        .map(|line| String::from(line.as_ref()))
+        .collect();
+    let window = LineWindow { first_line, lines };
+    crate::debug_span_with_options(span, &window, options)
+}
+
+/// Render a span of the code read from `reader`, reading it line by line until the last line of
+/// the rendering, see [`debug_span_from_lines`].
+///
+/// The lines before the span are read but not kept, so enormous generated files can be rendered
+/// without being loaded. Invalid UTF-8 is shown with replacement characters.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span_from_reader, DebugSpanOptions, LineColumnSpan};
+///
+/// let reader = std::io::Cursor::new("struct Foo;\r\nstruct Bar;\r\n");
+/// let span = LineColumnSpan::new(2, 7, 2, 10);
+/// let output = debug_span_from_reader(span, reader, &DebugSpanOptions::default()).unwrap();
+/// assert_eq!(output, " --> 2:7..2:10\n  |\n2 | struct Bar;\n  |        ^^^\n  |\n");
+/// ```
+#[cfg(feature = "std")]
+pub fn debug_span_from_reader(
+    span: impl Span,
+    mut reader: impl std::io::BufRead,
+    options: &DebugSpanOptions,
+) -> std::io::Result<String> {
+    let first_line = first_line(&span, options);
+    let last_line = span.end_line() + options.context;
+    let mut lines = Vec::new();
+    let mut buffer = Vec::new();
+    for line_number in 1..=last_line {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        if line_number < first_line {
+            continue;
+        }
+        let line = buffer.strip_suffix(b"\n").unwrap_or(&buffer);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        lines.push(String::from_utf8_lossy(line).into_owned());
+    }
+    let window = LineWindow { first_line, lines };
+    Ok(crate::debug_span_with_options(span, &window, options))
+}
+
+/// The first line of the rendering of `span`, context included.
+fn first_line(span: &impl Span, options: &DebugSpanOptions) -> usize {
+    span.start_line().saturating_sub(options.context).max(1)
+}
+
+/// Consecutive lines of a larger code, starting at `first_line`.
+///
+/// The lines before the window are unknown, offsets are counted from the start of the window.
+struct LineWindow {
+    first_line: usize,
+    lines: Vec<String>,
+}
+
+impl LineWindow {
+    fn get(&self, line_number: usize) -> Option<&str> {
+        let index = line_number.checked_sub(self.first_line)?;
+        self.lines.get(index).map(String::as_str)
+    }
+}
+
+impl SourceCode for LineWindow {
+    fn line_count(&self) -> usize {
+        self.first_line - 1 + self.lines.len()
+    }
+
+    fn line(&self, line_number: usize) -> Option<Cow<'_, str>> {
+        self.get(line_number).map(Cow::Borrowed)
+    }
+
+    fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        let text = self.get(line)?;
+        let offset = match text.char_indices().nth(column) {
+            Some((offset, _)) => offset,
+            None if column == text.chars().count() => text.len(),
+            None => return None,
+        };
+        let before = self.lines[..line - self.first_line]
+            .iter()
+            .map(|line| line.len() + 1)
+            .sum::<usize>();
+        Some(before + offset)
+    }
+
+    fn line_column(&self, offset: usize) -> Option<(usize, usize)> {
+        let mut start = 0;
+        for (index, line) in self.lines.iter().enumerate() {
+            if offset <= start + line.len() {
+                let text = line.get(..offset - start)?;
+                return Some((self.first_line + index, text.chars().count()));
+            }
+            start += line.len() + 1;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+
+    #[test]
+    fn test_line_window() {
+        let code = "fn foo() {\n    bar();\n}\n\nfn bar() {}\n";
+        let span = LineColumnSpan::new(1, 9, 3, 1);
+        let options = DebugSpanOptions {
+            context: 1,
+            label: Some(String::from("body")),
+            ..Default::default()
+        };
+        let expected = crate::debug_span_with_options(span, code, &options);
+        assert_eq!(
+            debug_span_from_lines(span, code.lines(), &options),
+            expected
+        );
+        let reader = std::io::Cursor::new(code);
+        let output = debug_span_from_reader(span, reader, &options).unwrap();
+        assert_eq!(output, expected);
+
+        let span = LineColumnSpan::new(5, 3, 5, 6);
+        let expected = crate::debug_span_with_options(span, code, &options);
+        insta::assert_snapshot!(debug_span_from_lines(span, code.lines(), &options), @r###"
+         --> 5:3..5:6
+          |
+        4 |
+        5 | fn bar() {}
+          |    ^^^ body
+          |
+        "###);
+        assert_eq!(
+            debug_span_from_lines(span, code.lines(), &options),
+            expected
+        );
+
+        let window = LineWindow {
+            first_line: 4,
+            lines: alloc::vec![String::new(), String::from("fn bar() {}")],
+        };
+        assert_eq!(window.line(3), None);
+        assert_eq!(window.byte_offset(5, 3), Some(4));
+        assert_eq!(window.line_column(4), Some((5, 3)));
+    }
+}
@@ -0,0 +1,249 @@
+use crate::LineColumnSpan;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Replace the absolute line numbers of a rendering with `[LINE]` placeholders.
+///
+/// Snapshots of the redacted output survive fixture files being reorganized, while still
+/// validating the shape of the output. Column numbers are kept.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span, redact_line_numbers, LineColumnSpan};
+///
+/// let code = "\n".repeat(120) + "struct Foo;";
+/// let output = debug_span(LineColumnSpan::new(121, 7, 121, 10), &code);
+/// insta::assert_snapshot!(redact_line_numbers(&output), @r###"
+///       --> [LINE]:7..[LINE]:10
+///        |
+/// [LINE] | struct Foo;
+///        |        ^^^
+///        |
+/// "###);
+/// ```
+pub fn redact_line_numbers(output: &str) -> String {
+    map_line_numbers(output, |_, _| "[LINE]".to_string())
+}
+
+/// Renumber the lines of a rendering so that the first line of each span is line `1`.
+///
+/// Like [`redact_line_numbers`], it makes snapshots independent of where the code is located
+/// in the fixture, but keeps the line numbers meaningful relative to each other.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span, relative_line_numbers, LineColumnSpan};
+///
+/// let code = "\n".repeat(120) + "struct Foo {\n    a: i32,\n}";
+/// let output = debug_span(LineColumnSpan::new(122, 4, 122, 10), &code);
+/// insta::assert_snapshot!(relative_line_numbers(&output), @r###"
+///  --> 1:4..1:10
+///   |
+/// 1 |     a: i32,
+///   |     ^^^^^^
+///   |
+/// "###);
+/// ```
+pub fn relative_line_numbers(output: &str) -> String {
+    map_line_numbers(output, |line, first_line| {
+        (line + 1).saturating_sub(first_line).to_string()
+    })
+}
+
+/// Rewrite the line numbers of every rendering found in `output`.
+///
+/// A rendering starts with a `--> ranges` or `--> path:ranges` header, and continues with gutter
+/// lines which have the same indentation as the header arrow. `map` receives the line number and
+/// the first line number of the header.
+fn map_line_numbers(output: &str, map: impl Fn(usize, usize) -> String) -> String {
+    let mut result = Vec::new();
+    let mut lines = output.split('\n').peekable();
+    while let Some(line) = lines.next() {
+        let Some((width, path, ranges)) = parse_header(line) else {
+            result.push(line.to_string());
+            continue;
+        };
+        let first_line = ranges
+            .iter()
+            .map(|range| range.start_line)
+            .min()
+            .unwrap_or_default();
+
+        let mut block = Vec::new();
+        while let Some(line) = lines.peek() {
+            match parse_gutter(line, width) {
+                Some((number, rest)) => {
+                    let number = number.map(|number| map(number, first_line));
+                    block.push((number, rest));
+                    lines.next();
+                }
+                None => break,
+            }
+        }
+
+        let new_ranges = ranges
+            .iter()
+            .map(|range| {
+                format!(
+                    "{}:{}..{}:{}",
+                    map(range.start_line, first_line),
+                    range.start_column,
+                    map(range.end_line, first_line),
+                    range.end_column
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let new_width = block
+            .iter()
+            .filter_map(|(number, _)| number.as_ref().map(|number| number.len()))
+            .max()
+            .unwrap_or(width);
+        let path = path.map(|path| format!("{}:", path)).unwrap_or_default();
+        result.push(format!(
+            "{:width$}--> {}{}",
+            "",
+            path,
+            new_ranges,
+            width = new_width
+        ));
+        for (number, rest) in block {
+            match (number, rest) {
+                (_, None) => result.push(format!("{:width$}...", "", width = new_width)),
+                (number, Some(rest)) => result.push(format!(
+                    "{:>width$} |{}",
+                    number.unwrap_or_default(),
+                    rest,
+                    width = new_width
+                )),
+            }
+        }
+    }
+    result.join("\n")
+}
+
+/// Parse a `--> path:1:7..1:10, 2:4..2:5` header into the arrow indentation, the optional path
+/// and the ranges.
+#[allow(clippy::type_complexity)]
+fn parse_header(line: &str) -> Option<(usize, Option<&str>, Vec<LineColumnSpan>)> {
+    let width = line.find("--> ")?;
+    if !line[..width].chars().all(|c| c == ' ') {
+        return None;
+    }
+    let (path, ranges) = split_path(&line[width + 4..])?;
+    let ranges = ranges
+        .split(", ")
+        .map(|range| range.parse().ok())
+        .collect::<Option<Vec<_>>>()?;
+    Some((width, path, ranges))
+}
+
+/// Split `path:1:7..1:10` into the path and the ranges. The path itself may contain `:`.
+fn split_path(header: &str) -> Option<(Option<&str>, &str)> {
+    let (start, _) = header.split_once("..")?;
+    let mut parts = start.rsplitn(3, ':');
+    parts.next()?;
+    parts.next()?;
+    match parts.next() {
+        Some(path) => Some((Some(path), &header[path.len() + 1..])),
+        None => Some((None, header)),
+    }
+}
+
+/// Parse a gutter line into its line number (if any) and the text after the `|`.
+///
+/// A `...` gap line is returned as `(None, None)`.
+#[allow(clippy::type_complexity)]
+fn parse_gutter(line: &str, width: usize) -> Option<(Option<usize>, Option<&str>)> {
+    if line.get(width..) == Some("...") && line[..width].trim().is_empty() {
+        return Some((None, None));
+    }
+    let gutter = line.get(..width)?;
+    let rest = line.get(width..)?.strip_prefix(" |")?;
+    let gutter = gutter.trim();
+    if gutter.is_empty() {
+        Some((None, Some(rest)))
+    } else {
+        Some((Some(gutter.parse().ok()?), Some(rest)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{debug_span, LineColumnSpan, NamedSource};
+
+    #[test]
+    fn test_redact_line_numbers_multi_line() {
+        let code = "\n".repeat(8) + "struct Foo {\n    a: i32,\n}";
+        let output = debug_span(LineColumnSpan::new(9, 11, 11, 1), &code);
+        insta::assert_snapshot!(redact_line_numbers(&output), @r###"
+              --> [LINE]:11..[LINE]:1
+               |
+               |            ┌────╮
+        [LINE] | struct Foo {    │
+        [LINE] |     a: i32,     │
+        [LINE] | }               │
+               | └───────────────╯
+               |
+        "###);
+        insta::assert_snapshot!(relative_line_numbers(&output), @r###"
+         --> 1:11..3:1
+          |
+          |            ┌────╮
+        1 | struct Foo {    │
+        2 |     a: i32,     │
+        3 | }               │
+          | └───────────────╯
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_redact_line_numbers_with_path() {
+        let code = NamedSource::new("src/lib.rs", "\n".repeat(9) + "struct Foo;");
+        let output = debug_span(LineColumnSpan::new(10, 7, 10, 10), &code);
+        insta::assert_snapshot!(redact_line_numbers(&output), @r###"
+              --> src/lib.rs:[LINE]:7..[LINE]:10
+               |
+        [LINE] | struct Foo;
+               |        ^^^
+               |
+        "###);
+        insta::assert_snapshot!(relative_line_numbers(&output), @r###"
+         --> src/lib.rs:1:7..1:10
+          |
+        1 | struct Foo;
+          |        ^^^
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_redact_keeps_surrounding_text() {
+        let code = "\n".repeat(9) + "struct Foo;";
+        let output = format!(
+            "left:\n{}\nright:\n{}",
+            debug_span(LineColumnSpan::new(10, 7, 10, 10), &code),
+            debug_span(LineColumnSpan::new(10, 0, 10, 6), &code),
+        );
+        insta::assert_snapshot!(relative_line_numbers(&output), @r###"
+        left:
+         --> 1:7..1:10
+          |
+        1 | struct Foo;
+          |        ^^^
+          |
+
+        right:
+         --> 1:0..1:6
+          |
+        1 | struct Foo;
+          | ^^^^^^
+          |
+        "###);
+    }
+}
@@ -0,0 +1,1116 @@
+use crate::{
+    Charset, Color, ColorDepth, DebugSpanOptions, Event, LineColumnSpan, MarkerKind,
+    MultiLineStyle, OutputVersion, Palette, SourceCode, Span, Visitor,
+};
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+pub fn debug_span(span: &(impl Span + ?Sized), source: &(impl SourceCode + ?Sized)) -> String {
+    debug_span_with_options(span, source, &DebugSpanOptions::default())
+}
+
+pub fn debug_span_with_options(
+    span: &(impl Span + ?Sized),
+    source: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> String {
+    if options.prose {
+        return crate::prose::describe(&[LineColumnSpan::from_span(span)], source, options);
+    }
+    let mut visitor = TextVisitor::new(options);
+    visit_span(span, source, options, &mut visitor);
+    finish(visitor.output, options)
+}
+
+/// Emits the events of the rendering of a single span.
+pub fn visit_span(
+    span: &(impl Span + ?Sized),
+    source: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+    visitor: &mut impl Visitor,
+) {
+    let empty = span.is_empty();
+    if empty && !options.show_empty_spans {
+        return;
+    }
+    let width = line_number_width(span, source, options);
+    let ranges = display_range(span, source);
+    let last = context_after_range(span, source, options)
+        .last()
+        .unwrap_or(span.end_line());
+    visitor.visit(Event::BeginSnippet {
+        path: source.line_origin(span.start_line()).0,
+        ranges: &ranges,
+        gutter_width: width,
+        tag_width: tag_width(
+            source,
+            context_before_range(span, options).start..=last,
+            options,
+        ),
+    });
+    visitor.visit(Event::GutterLine);
+    let rustc = !span.is_single_line() && options.multi_line_style == MultiLineStyle::Rustc;
+    let margin = Some(' ').filter(|_| rustc);
+    let start_column = display_column(source, span.start_line(), span.start_column(), options);
+    let end_column = display_column(source, span.end_line(), span.end_column(), options);
+    for line_number in context_before_range(span, options) {
+        visit_code_line(line_number, source, options, None, margin, visitor);
+    }
+    if span.is_single_line() {
+        visit_code_line(span.start_line(), source, options, None, None, visitor);
+        let note = empty.then(|| match &options.label {
+            Some(label) => format!("{} {}", label, options.messages.empty_span),
+            None => options.messages.empty_span.clone(),
+        });
+        visitor.visit(Event::Marker {
+            kind: MarkerKind::Underline,
+            column: start_column,
+            width: if empty { 1 } else { end_column - start_column },
+            label: note.as_deref().or(options.label.as_deref()),
+            index: 0,
+        });
+    } else if rustc {
+        let first_line = source_line(source, span.start_line(), options);
+        let indented = first_line
+            .chars()
+            .take(start_column)
+            .all(char::is_whitespace);
+        let first_margin = if indented { '/' } else { ' ' };
+        visit_code_line(
+            span.start_line(),
+            source,
+            options,
+            None,
+            Some(first_margin),
+            visitor,
+        );
+        if !indented {
+            visitor.visit(Event::Marker {
+                kind: MarkerKind::MultiLineStart,
+                column: start_column,
+                width: 1,
+                label: None,
+                index: 0,
+            });
+        }
+        for line_number in shown_lines(span, options).into_iter().skip(1) {
+            match line_number {
+                Some(line_number) => {
+                    visit_code_line(line_number, source, options, None, Some('|'), visitor)
+                }
+                None => visitor.visit(Event::Ellipsis),
+            }
+        }
+        visitor.visit(Event::Marker {
+            kind: MarkerKind::MultiLineEnd,
+            column: end_column.max(1) - 1,
+            width: 1,
+            label: options.label.as_deref(),
+            index: 0,
+        });
+    } else {
+        let max_line_len = max_line_len(span, source, options);
+        // the box sides past the cut of the `max_width` option point at the `…`
+        let max_width = options
+            .max_width
+            .map_or(usize::MAX, |max_width| max_width.max(1));
+        let start_column = start_column.min(max_width - 1);
+        visitor.visit(Event::Marker {
+            kind: MarkerKind::BoxTop,
+            column: start_column,
+            width: max_line_len + PADDING - start_column,
+            label: None,
+            index: 0,
+        });
+        for line_number in shown_lines(span, options) {
+            let box_column = Some(max_line_len + PADDING + 1);
+            match line_number {
+                Some(line_number) => {
+                    visit_code_line(line_number, source, options, box_column, None, visitor)
+                }
+                None => visitor.visit(Event::Ellipsis),
+            }
+        }
+        let end_column = end_column.max(1).min(max_width);
+        visitor.visit(Event::Marker {
+            kind: MarkerKind::BoxBottom,
+            column: end_column - 1,
+            width: max_line_len + PADDING - end_column + 1,
+            label: options.label.as_deref(),
+            index: 0,
+        });
+    }
+    for line_number in context_after_range(span, source, options) {
+        visit_code_line(line_number, source, options, None, margin, visitor);
+    }
+    visitor.visit(Event::GutterLine);
+    visitor.visit(Event::EndSnippet);
+}
+
+/// Emits the [`Event::CodeLine`] of a 1-indexed line.
+pub fn visit_code_line(
+    line_number: usize,
+    source: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+    box_column: Option<usize>,
+    margin: Option<char>,
+    visitor: &mut impl Visitor,
+) {
+    let line = source_line(source, line_number, options);
+    visitor.visit(Event::CodeLine {
+        line_number: source.line_origin(line_number).1,
+        code: &line,
+        box_column,
+        margin,
+        tag: source.line_tag(line_number).filter(|_| options.provenance),
+    });
+}
+
+/// Width of the provenance tag column for the given lines, `0` if there is no tag to show.
+pub fn tag_width(
+    code: &(impl SourceCode + ?Sized),
+    line_numbers: impl IntoIterator<Item = usize>,
+    options: &DebugSpanOptions,
+) -> usize {
+    if !options.provenance {
+        return 0;
+    }
+    line_numbers
+        .into_iter()
+        .filter_map(|line_number| code.line_tag(line_number))
+        .map(|tag| tag.chars().count())
+        .max()
+        .unwrap_or(0)
+}
+
+/// The visitor producing the text rendering.
+pub struct TextVisitor<'a> {
+    options: &'a DebugSpanOptions,
+    width: usize,
+    tag_width: usize,
+    pub output: String,
+}
+
+impl<'a> TextVisitor<'a> {
+    pub fn new(options: &'a DebugSpanOptions) -> Self {
+        Self {
+            options,
+            width: 1,
+            tag_width: 0,
+            output: String::new(),
+        }
+    }
+
+    fn push_line(&mut self, tag: Option<&str>, line: &str) {
+        if self.tag_width > 0 {
+            let tag = format!("{:width$} ", tag.unwrap_or(""), width = self.tag_width);
+            self.output.push_str(&paint_gutter(&tag, self.options));
+        }
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+}
+
+impl Visitor for TextVisitor<'_> {
+    fn visit(&mut self, event: Event<'_>) {
+        let options = self.options;
+        let width = self.width;
+        let line = match event {
+            Event::BeginSnippet {
+                path,
+                ranges,
+                gutter_width,
+                tag_width,
+            } => {
+                self.width = gutter_width;
+                self.tag_width = tag_width;
+                header_line(ranges, path, options, gutter_width)
+            }
+            Event::GutterLine => gutter("", options, width),
+            Event::CodeLine {
+                line_number,
+                code,
+                box_column,
+                margin,
+                tag,
+            } => {
+                let mut line = gutter(line_number, options, width);
+                let blank = code.is_empty()
+                    && box_column.is_none()
+                    && options.output_version != OutputVersion::V1;
+                if let Some(margin) = margin.filter(|margin| !blank || *margin != ' ') {
+                    line.push(' ');
+                    line.push_str(&paint(
+                        &margin.to_string(),
+                        |palette| palette.label(0),
+                        options,
+                    ));
+                }
+                if !blank {
+                    line.push(' ');
+                    line.push_str(&display_code(code, options));
+                }
+                if let Some(box_column) = box_column {
+                    let vertical = box_chars(options.charset).vertical.to_string();
+                    let code_len = code_len(code, options.output_version);
+                    line.push_str(&" ".repeat(box_column.saturating_sub(code_len)));
+                    line.push_str(&paint(&vertical, |palette| palette.label(0), options));
+                }
+                self.push_line(tag, &line);
+                return;
+            }
+            Event::Marker {
+                kind,
+                column,
+                width: marker_width,
+                label,
+                index,
+            } => {
+                let (column, marker_width) =
+                    clip_marker(kind, column, marker_width, options.max_width);
+                let chars = box_chars(options.charset);
+                let horizontal = chars.horizontal.to_string().repeat(marker_width);
+                let (indent, marker) = match kind {
+                    MarkerKind::Underline => (column, "^".repeat(marker_width)),
+                    MarkerKind::BoxTop => (
+                        column,
+                        format!("{}{}{}", chars.top_left, horizontal, chars.top_right),
+                    ),
+                    MarkerKind::BoxBottom => (
+                        column,
+                        format!("{}{}{}", chars.bottom_left, horizontal, chars.bottom_right),
+                    ),
+                    // the `_` line starts in the margin, right of the gutter
+                    MarkerKind::MultiLineStart => (0, format!(" {}^", "_".repeat(column + 1))),
+                    MarkerKind::MultiLineEnd => (0, format!("|{}^", "_".repeat(column + 1))),
+                };
+                let marker = match label {
+                    Some(label) => format!("{} {}", marker, label),
+                    None => marker,
+                };
+                format!(
+                    "{} {}{}",
+                    gutter("", options, width),
+                    " ".repeat(indent),
+                    paint(&marker, |palette| palette.label(index), options),
+                )
+            }
+            Event::Ellipsis => {
+                let dots = paint_gutter(&options.messages.lines_omitted, options);
+                format!("{:width$}{}", "", dots, width = width)
+            }
+            Event::EndSnippet => return,
+        };
+        self.push_line(None, &line);
+    }
+}
+
+/// Applies the post-processing required by the `highlight` option.
+#[cfg_attr(
+    not(any(feature = "syntect", feature = "tree-sitter")),
+    allow(unused_variables)
+)]
+pub fn finish(output: String, options: &DebugSpanOptions) -> String {
+    #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+    if let (Some(highlight), false) = (options.highlight, options.deterministic) {
+        return crate::highlight::finish(output, highlight);
+    }
+    output
+}
+
+/// Returns the code of a line as it is displayed, highlighted if requested.
+#[cfg_attr(
+    not(any(feature = "syntect", feature = "tree-sitter")),
+    allow(unused_variables)
+)]
+fn display_code<'a>(line: &'a str, options: &DebugSpanOptions) -> Cow<'a, str> {
+    #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+    if let (Some(highlight), false) = (options.highlight, options.deterministic) {
+        return Cow::Owned(crate::highlight::highlight_line(
+            line,
+            highlight,
+            options.highlighter,
+        ));
+    }
+    Cow::Borrowed(line)
+}
+
+/// Returns the palette of the `theme` option and its color depth, if the rendering is colored.
+pub fn palette(options: &DebugSpanOptions) -> Option<(Palette, ColorDepth)> {
+    if options.deterministic {
+        return None;
+    }
+    #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+    if options.highlight == Some(crate::Highlight::Html) {
+        return None;
+    }
+    options
+        .theme
+        .map(|theme| (theme.palette(), options.color_depth))
+}
+
+/// Colors `text` with a color of the palette of the `theme` option, if the rendering is colored.
+pub fn paint(
+    text: &str,
+    color: impl FnOnce(&Palette) -> Color,
+    options: &DebugSpanOptions,
+) -> String {
+    match palette(options) {
+        Some((palette, depth)) => color(&palette).paint(text, depth),
+        None => text.to_string(),
+    }
+}
+
+/// Colors `text` with the dimmed gutter color, if the rendering is colored.
+fn paint_gutter(text: &str, options: &DebugSpanOptions) -> String {
+    match palette(options) {
+        Some((palette, depth)) => palette.gutter.paint_dimmed(text, depth),
+        None => text.to_string(),
+    }
+}
+
+/// The `line |` gutter.
+pub fn gutter(line_number: impl Display, options: &DebugSpanOptions, width: usize) -> String {
+    let line_number = line_number.to_string();
+    let padding = width.saturating_sub(line_number.len());
+    let line_number = match line_number.is_empty() {
+        true => line_number,
+        false => paint(&line_number, |palette| palette.line_numbers, options),
+    };
+    format!(
+        "{:padding$}{}{}",
+        "",
+        line_number,
+        paint_gutter(" |", options),
+        padding = padding
+    )
+}
+
+/// Width of the line number gutter, based on the displayed line numbers.
+pub fn line_number_width(
+    span: &(impl Span + ?Sized),
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> usize {
+    let first_line = context_before_range(span, options).start;
+    let last_line = context_after_range(span, code, options)
+        .last()
+        .unwrap_or(span.end_line());
+    display_line_number_width(code, first_line..=last_line)
+}
+
+/// Width of the line number gutter for the given lines.
+pub fn display_line_number_width(
+    code: &(impl SourceCode + ?Sized),
+    line_numbers: impl IntoIterator<Item = usize>,
+) -> usize {
+    line_numbers
+        .into_iter()
+        .map(|line_number| code.line_origin(line_number).1.to_string().len())
+        .max()
+        .unwrap_or(1)
+}
+
+/// The `start_line:start_column..end_line:end_column` range, with the displayed line numbers.
+pub fn display_range(span: &(impl Span + ?Sized), code: &(impl SourceCode + ?Sized)) -> String {
+    format!(
+        "{}:{}..{}:{}",
+        code.line_origin(span.start_line()).1,
+        span.start_column(),
+        code.line_origin(span.end_line()).1,
+        span.end_column(),
+    )
+}
+
+/// The `--> path:ranges` header line.
+fn header_line(
+    ranges: &str,
+    path: Option<&str>,
+    options: &DebugSpanOptions,
+    width: usize,
+) -> String {
+    let arrow = paint_gutter("-->", options);
+    match path {
+        Some(path) => format!("{:width$}{} {}:{}", "", arrow, path, ranges, width = width),
+        None => format!("{:width$}{} {}", "", arrow, ranges, width = width),
+    }
+}
+
+/// Returns the text of a 1-indexed line as it is displayed.
+pub fn source_line<'a>(
+    code: &'a (impl SourceCode + ?Sized),
+    line_number: usize,
+    options: &DebugSpanOptions,
+) -> Cow<'a, str> {
+    let line = raw_line(code, line_number, options);
+    let tab_width = tab_width(options);
+    let line = match line {
+        line if tab_width == 0 || !line.contains('\t') => line,
+        line => {
+            let mut expanded = String::new();
+            let mut column = 0;
+            for c in line.chars() {
+                match c {
+                    '\t' => {
+                        let spaces = tab_width - column % tab_width;
+                        expanded.push_str(&" ".repeat(spaces));
+                        column += spaces;
+                    }
+                    c => {
+                        expanded.push(c);
+                        column += char_width(c);
+                    }
+                }
+            }
+            Cow::Owned(expanded)
+        }
+    };
+    match options.max_width {
+        Some(max_width) if display_width(&line) > max_width => {
+            let mut truncated = String::new();
+            let mut width = 0;
+            for c in line.chars() {
+                width += char_width(c);
+                if width >= max_width.max(1) {
+                    break;
+                }
+                truncated.push(c);
+            }
+            truncated.push('…');
+            Cow::Owned(truncated)
+        }
+        _ => line,
+    }
+}
+
+/// Returns the text of a 1-indexed line without a stray `\r` line ending, e.g. left by splitting
+/// CRLF code on `\n`. [`OutputVersion::V1`] only removes it in `deterministic` renderings.
+fn raw_line<'a>(
+    code: &'a (impl SourceCode + ?Sized),
+    line_number: usize,
+    options: &DebugSpanOptions,
+) -> Cow<'a, str> {
+    let line = code.line(line_number).unwrap();
+    match line {
+        line if options.output_version == OutputVersion::V1 && !options.deterministic => line,
+        Cow::Borrowed(line) => Cow::Borrowed(line.trim_end_matches('\r')),
+        Cow::Owned(line) => Cow::Owned(line.trim_end_matches('\r').to_string()),
+    }
+}
+
+/// The `tab_width` option, `0` when tabs are kept as they are.
+fn tab_width(options: &DebugSpanOptions) -> usize {
+    match options.output_version {
+        OutputVersion::V1 => 0,
+        _ => options.tab_width,
+    }
+}
+
+/// The column of the displayed line at the given char column of a 1-indexed line, after the
+/// expansion of the tabs and the wide chars preceding it, see [`display_width`].
+pub fn display_column(
+    code: &(impl SourceCode + ?Sized),
+    line_number: usize,
+    column: usize,
+    options: &DebugSpanOptions,
+) -> usize {
+    if options.output_version == OutputVersion::V1 {
+        return column;
+    }
+    let tab_width = options.tab_width;
+    let line = raw_line(code, line_number, options);
+    let mut display_column = 0;
+    let mut chars = 0;
+    for c in line.chars().take(column) {
+        display_column = match c {
+            '\t' if tab_width > 0 => (display_column / tab_width + 1) * tab_width,
+            c => display_column + char_width(c),
+        };
+        chars += 1;
+    }
+    // columns past the end of the line
+    display_column + column - chars
+}
+
+/// Length of a displayed line used to align the box, in columns since [`OutputVersion::V2`].
+pub fn code_len(code: &str, output_version: OutputVersion) -> usize {
+    match output_version {
+        OutputVersion::V1 => code.len(),
+        _ => display_width(code),
+    }
+}
+
+/// Number of terminal columns of a text. Wide chars like CJK and emoji take two columns with
+/// the `unicode-width` feature, every char takes a single column without it.
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Number of terminal columns of a char, given by `unicode-width` behind the feature of the same
+/// name. Chars without a width, like control chars, take a single column.
+#[cfg(feature = "unicode-width")]
+fn char_width(c: char) -> usize {
+    unicode_width::UnicodeWidthChar::width(c).unwrap_or(1)
+}
+
+/// Number of terminal columns of a char, a single one without the `unicode-width` feature.
+#[cfg(not(feature = "unicode-width"))]
+fn char_width(_: char) -> usize {
+    1
+}
+
+/// The column and the width of a marker under lines cut by the `max_width` option, the parts
+/// past the cut point at the `…`.
+pub fn clip_marker(
+    kind: MarkerKind,
+    column: usize,
+    width: usize,
+    max_width: Option<usize>,
+) -> (usize, usize) {
+    let Some(max_width) = max_width.map(|max_width| max_width.max(1)) else {
+        return (column, width);
+    };
+    let clipped = column.min(max_width - 1);
+    match kind {
+        MarkerKind::Underline => {
+            let end = (column + width).min(max_width);
+            (clipped, end.saturating_sub(clipped).max(1))
+        }
+        // the box fits the cut lines, its right side stays in place
+        MarkerKind::BoxTop | MarkerKind::BoxBottom => (clipped, width + column - clipped),
+        MarkerKind::MultiLineStart | MarkerKind::MultiLineEnd => (clipped, width),
+    }
+}
+
+fn context_before_range(
+    span: &(impl Span + ?Sized),
+    options: &DebugSpanOptions,
+) -> core::ops::Range<usize> {
+    let start_line = span.start_line();
+    start_line.saturating_sub(options.context).max(1)..start_line
+}
+
+fn context_after_range(
+    span: &(impl Span + ?Sized),
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> core::ops::RangeInclusive<usize> {
+    let end_line = span.end_line();
+    let last_line = code.line_count().min(end_line + options.context);
+    end_line + 1..=last_line
+}
+
+const PADDING: usize = 3;
+
+/// Box drawing characters used by the multi-line renderer.
+struct BoxChars {
+    top_left: char,
+    top_right: char,
+    bottom_left: char,
+    bottom_right: char,
+    horizontal: char,
+    vertical: char,
+}
+
+fn box_chars(charset: Charset) -> BoxChars {
+    match charset {
+        Charset::Unicode => BoxChars {
+            top_left: '┌',
+            top_right: '╮',
+            bottom_left: '└',
+            bottom_right: '╯',
+            horizontal: '─',
+            vertical: '│',
+        },
+        Charset::Ascii => BoxChars {
+            top_left: '+',
+            top_right: '+',
+            bottom_left: '+',
+            bottom_right: '+',
+            horizontal: '-',
+            vertical: '|',
+        },
+    }
+}
+
+/// The spanned lines that are shown, with `None` in place of the lines elided by the
+/// `max_lines` option.
+fn shown_lines(span: &(impl Span + ?Sized), options: &DebugSpanOptions) -> Vec<Option<usize>> {
+    let (start_line, end_line) = (span.start_line(), span.end_line());
+    // the first and the last lines are always shown
+    let max_lines = options
+        .max_lines
+        .map_or(usize::MAX, |max_lines| max_lines.max(2));
+    if end_line - start_line < max_lines {
+        return (start_line..=end_line).map(Some).collect();
+    }
+    let head = max_lines.div_ceil(2);
+    let tail = max_lines - head;
+    let mut lines = (start_line..start_line + head)
+        .map(Some)
+        .collect::<Vec<_>>();
+    lines.push(None);
+    lines.extend((end_line + 1 - tail..=end_line).map(Some));
+    lines
+}
+
+fn max_line_len(
+    span: &(impl Span + ?Sized),
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> usize {
+    shown_lines(span, options)
+        .into_iter()
+        .flatten()
+        .map(|line_number| {
+            code_len(
+                &source_line(code, line_number, options),
+                options.output_version,
+            )
+        })
+        .max()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+    use unindent::Unindent;
+
+    #[test]
+    fn test_label() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+            }
+        "###
+        .unindent();
+        let options = DebugSpanOptions {
+            label: Some("expected ident".to_string()),
+            ..Default::default()
+        };
+        let output = debug_span_with_options(&LineColumnSpan::new(1, 7, 1, 10), &input, &options);
+        insta::assert_snapshot!(output, @r###"
+         --> 1:7..1:10
+          |
+        1 | struct Foo {
+          |        ^^^ expected ident
+          |
+        "###);
+        let output = debug_span_with_options(&LineColumnSpan::new(1, 11, 3, 1), &input, &options);
+        insta::assert_snapshot!(output, @r###"
+         --> 1:11..3:1
+          |
+          |            ┌────╮
+        1 | struct Foo {    │
+        2 |     a: i32,     │
+        3 | }               │
+          | └───────────────╯ expected ident
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_context() {
+        let input = r###"
+            #[derive(Debug)]
+            struct Foo {
+                a: i32,
+                b: i32,
+            }
+        "###
+        .unindent();
+        let options = DebugSpanOptions {
+            context: 1,
+            ..Default::default()
+        };
+        let output = debug_span_with_options(&LineColumnSpan::new(3, 4, 3, 5), &input, &options);
+        insta::assert_snapshot!(output, @r###"
+         --> 3:4..3:5
+          |
+        2 | struct Foo {
+        3 |     a: i32,
+          |     ^
+        4 |     b: i32,
+          |
+        "###);
+        let options = DebugSpanOptions {
+            context: 2,
+            ..Default::default()
+        };
+        let output = debug_span_with_options(&LineColumnSpan::new(1, 0, 1, 16), &input, &options);
+        insta::assert_snapshot!(output, @r###"
+         --> 1:0..1:16
+          |
+        1 | #[derive(Debug)]
+          | ^^^^^^^^^^^^^^^^
+        2 | struct Foo {
+        3 |     a: i32,
+          |
+        "###);
+        let output = debug_span_with_options(&LineColumnSpan::new(3, 4, 4, 10), &input, &options);
+        insta::assert_snapshot!(output, @r###"
+         --> 3:4..4:10
+          |
+        1 | #[derive(Debug)]
+        2 | struct Foo {
+          |     ┌──────────╮
+        3 |     a: i32,    │
+        4 |     b: i32,    │
+          |          └─────╯
+        5 | }
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_context_line_number_width() {
+        let input = "struct Foo;\n".repeat(10);
+        let options = DebugSpanOptions {
+            context: 1,
+            ..Default::default()
+        };
+        let output = debug_span_with_options(&LineColumnSpan::new(9, 7, 9, 10), &input, &options);
+        insta::assert_snapshot!(output, @r###"
+          --> 9:7..9:10
+           |
+         8 | struct Foo;
+         9 | struct Foo;
+           |        ^^^
+        10 | struct Foo;
+           |
+        "###);
+    }
+
+    #[test]
+    fn test_ascii_charset() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+            }
+        "###
+        .unindent();
+        let options = DebugSpanOptions {
+            charset: Charset::Ascii,
+            ..Default::default()
+        };
+        let output = debug_span_with_options(&LineColumnSpan::new(1, 11, 3, 1), &input, &options);
+        insta::assert_snapshot!(output, @r###"
+         --> 1:11..3:1
+          |
+          |            +----+
+        1 | struct Foo {    |
+        2 |     a: i32,     |
+        3 | }               |
+          | +---------------+
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_output_version() {
+        let input = "struct Foo;
+
+struct Bar;
+";
+        let span = LineColumnSpan::new(3, 7, 3, 10);
+        let options = DebugSpanOptions {
+            context: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            debug_span_with_options(&span, input, &options),
+            " --> 3:7..3:10\n  |\n2 |\n3 | struct Bar;\n  |        ^^^\n  |\n"
+        );
+        let options = DebugSpanOptions {
+            output_version: OutputVersion::V1,
+            ..options
+        };
+        assert_eq!(
+            debug_span_with_options(&span, input, &options),
+            " --> 3:7..3:10\n  |\n2 | \n3 | struct Bar;\n  |        ^^^\n  |\n"
+        );
+    }
+
+    #[test]
+    fn test_max_lines() {
+        let input = (1..=12)
+            .map(|line| format!("    a{} = {};\n", line, line))
+            .collect::<String>();
+        let span = LineColumnSpan::new(1, 4, 12, 11);
+        let options = DebugSpanOptions {
+            max_lines: Some(4),
+            ..Default::default()
+        };
+        insta::assert_snapshot!(debug_span_with_options(&span, input.as_str(), &options), @r###"
+          --> 1:4..12:11
+           |
+           |     ┌────────────╮
+         1 |     a1 = 1;      │
+         2 |     a2 = 2;      │
+          ...
+        11 |     a11 = 11;    │
+        12 |     a12 = 12;    │
+           |           └──────╯
+           |
+        "###);
+        let options = DebugSpanOptions {
+            max_lines: Some(3),
+            multi_line_style: MultiLineStyle::Rustc,
+            ..Default::default()
+        };
+        insta::assert_snapshot!(debug_span_with_options(&span, input.as_str(), &options), @r###"
+          --> 1:4..12:11
+           |
+         1 | /     a1 = 1;
+         2 | |     a2 = 2;
+          ...
+        12 | |     a12 = 12;
+           | |___________^
+           |
+        "###);
+        let options = DebugSpanOptions {
+            max_lines: Some(12),
+            ..Default::default()
+        };
+        assert_eq!(
+            debug_span_with_options(&span, input.as_str(), &options),
+            debug_span(&span, input.as_str())
+        );
+    }
+
+    #[test]
+    fn test_max_width() {
+        let input =
+            "fn foo() { let télé = vec![1, 2, 3, 4, 5, 6]; }\nfn bar() {\n    loooooooong();\n}";
+        let options = DebugSpanOptions {
+            max_width: Some(20),
+            label: Some("here".to_string()),
+            ..Default::default()
+        };
+        let span = LineColumnSpan::new(1, 15, 1, 35);
+        insta::assert_snapshot!(debug_span_with_options(&span, input, &options), @r###"
+         --> 1:15..1:35
+          |
+        1 | fn foo() { let télé…
+          |                ^^^^^ here
+          |
+        "###);
+        let span = LineColumnSpan::new(1, 30, 1, 33);
+        insta::assert_snapshot!(debug_span_with_options(&span, input, &options), @r###"
+         --> 1:30..1:33
+          |
+        1 | fn foo() { let télé…
+          |                    ^ here
+          |
+        "###);
+        let span = LineColumnSpan::new(1, 9, 3, 11);
+        insta::assert_snapshot!(debug_span_with_options(&span, input, &options), @r###"
+         --> 1:9..3:11
+          |
+          |          ┌──────────────╮
+        1 | fn foo() { let télé…    │
+        2 | fn bar() {              │
+        3 |     loooooooong();      │
+          |           └─────────────╯ here
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_tab_width() {
+        let input = "fn foo() {\n\tlet a =\t1;\n\tbar(a);\n}";
+        let span = LineColumnSpan::new(2, 9, 2, 10);
+        insta::assert_snapshot!(debug_span(&span, input), @r###"
+         --> 2:9..2:10
+          |
+        2 |     let a = 1;
+          |             ^
+          |
+        "###);
+        let options = DebugSpanOptions {
+            tab_width: 8,
+            ..Default::default()
+        };
+        let span = LineColumnSpan::new(2, 1, 3, 7);
+        insta::assert_snapshot!(debug_span_with_options(&span, input, &options), @r###"
+         --> 2:1..3:7
+          |
+          |         ┌─────────────╮
+        2 |         let a = 1;    │
+        3 |         bar(a);       │
+          |              └────────╯
+          |
+        "###);
+        let spans = [
+            LineColumnSpan::new(2, 5, 2, 6),
+            LineColumnSpan::new(3, 1, 3, 4),
+        ];
+        insta::assert_snapshot!(crate::debug_spans(&spans, input), @r###"
+         --> 2:5..2:6, 3:1..3:4
+          |
+        2 |     let a = 1;
+          |         ^
+        3 |     bar(a);
+          |     ^^^
+          |
+        "###);
+        let options = DebugSpanOptions {
+            output_version: OutputVersion::V1,
+            ..Default::default()
+        };
+        let span = LineColumnSpan::new(2, 9, 2, 10);
+        assert_eq!(
+            debug_span_with_options(&span, input, &options),
+            " --> 2:9..2:10\n  |\n2 | \tlet a =\t1;\n  |          ^\n  |\n"
+        );
+    }
+
+    #[test]
+    fn test_show_empty_spans() {
+        let input = "struct Foo {\n    a: i32\n}";
+        let span = LineColumnSpan::new(2, 10, 2, 10);
+        assert_eq!(debug_span(&span, input), "");
+        let options = DebugSpanOptions {
+            show_empty_spans: true,
+            ..Default::default()
+        };
+        insta::assert_snapshot!(debug_span_with_options(&span, input, &options), @r###"
+         --> 2:10..2:10
+          |
+        2 |     a: i32
+          |           ^ (empty span)
+          |
+        "###);
+        let options = DebugSpanOptions {
+            label: Some("expected `,`".to_string()),
+            ..options
+        };
+        let span = LineColumnSpan::new(1, 7, 1, 7);
+        insta::assert_snapshot!(debug_span_with_options(&span, input, &options), @r###"
+         --> 1:7..1:7
+          |
+        1 | struct Foo {
+          |        ^ expected `,` (empty span)
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_crlf() {
+        let input = "struct Foo {\r\n    a: i32,\r\n}\r\n";
+        let span = LineColumnSpan::new(1, 11, 3, 1);
+        let lf = debug_span(&span, input.replace("\r\n", "\n").as_str());
+        assert_eq!(debug_span(&span, input), lf);
+        let split = crate::debug_span_from_lines(span, input.split('\n'), &Default::default());
+        assert_eq!(split, lf);
+        insta::assert_snapshot!(split, @r###"
+         --> 1:11..3:1
+          |
+          |            ┌────╮
+        1 | struct Foo {    │
+        2 |     a: i32,     │
+        3 | }               │
+          | └───────────────╯
+          |
+        "###);
+        let options = DebugSpanOptions {
+            output_version: OutputVersion::V1,
+            ..Default::default()
+        };
+        let span = LineColumnSpan::new(3, 0, 3, 1);
+        let split = crate::debug_span_from_lines(span, input.split('\n'), &options);
+        assert_eq!(split, " --> 3:0..3:1\n  |\n3 | }\r\n  | ^\n  |\n");
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-width")]
+    fn test_unicode_width() {
+        let input = "let 名前 = \"🦀\"; foo();\nbar();";
+        let span = LineColumnSpan::new(1, 12, 1, 17);
+        insta::assert_snapshot!(debug_span(&span, input), @r###"
+         --> 1:12..1:17
+          |
+        1 | let 名前 = "🦀"; foo();
+          |                ^^^^^
+          |
+        "###);
+        let span = LineColumnSpan::new(1, 4, 2, 3);
+        insta::assert_snapshot!(debug_span(&span, input), @r###"
+         --> 1:4..2:3
+          |
+          |     ┌──────────────────────╮
+        1 | let 名前 = "🦀"; foo();    │
+        2 | bar();                     │
+          |   └────────────────────────╯
+          |
+        "###);
+        let options = DebugSpanOptions {
+            max_width: Some(8),
+            ..Default::default()
+        };
+        let span = LineColumnSpan::new(1, 4, 1, 6);
+        insta::assert_snapshot!(debug_span_with_options(&span, input, &options), @r###"
+         --> 1:4..1:6
+          |
+        1 | let 名…
+          |     ^^^^
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_rustc_style() {
+        let input = "struct Foo {
+    a: i32,
+
+}
+fn foo() {
+    bar();
+}";
+        let options = DebugSpanOptions {
+            label: Some("expected ident".to_string()),
+            multi_line_style: MultiLineStyle::Rustc,
+            context: 1,
+            ..Default::default()
+        };
+        let span = LineColumnSpan::new(1, 11, 4, 1);
+        insta::assert_snapshot!(debug_span_with_options(&span, input, &options), @r###"
+         --> 1:11..4:1
+          |
+        1 |   struct Foo {
+          |  ____________^
+        2 | |     a: i32,
+        3 | |
+        4 | | }
+          | |_^ expected ident
+        5 |   fn foo() {
+          |
+        "###);
+        let span = LineColumnSpan::new(5, 0, 7, 1);
+        insta::assert_snapshot!(debug_span_with_options(&span, input, &options), @r###"
+         --> 5:0..7:1
+          |
+        4 |   }
+        5 | / fn foo() {
+        6 | |     bar();
+        7 | | }
+          | |_^ expected ident
+          |
+        "###);
+        let span = LineColumnSpan::new(6, 4, 6, 7);
+        insta::assert_snapshot!(debug_span_with_options(&span, input, &options), @r###"
+         --> 6:4..6:7
+          |
+        5 | fn foo() {
+        6 |     bar();
+          |     ^^^ expected ident
+        7 | }
+          |
+        "###);
+    }
+}
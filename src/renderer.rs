@@ -0,0 +1,396 @@
+use crate::{Color, DebugSpanOptions, LineColumnSpan, Palette, SourceCode, SourceLink};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// An output format for renderings.
+///
+/// The built-in [`TextRenderer`], [`AnsiRenderer`], [`HtmlRenderer`] and [`SvgRenderer`] share
+/// the layout of [`debug_span_with_options`](crate::debug_span_with_options) and apply the
+/// [`DebugSpanOptions`] the same way, [`NeovimRenderer`](crate::NeovimRenderer) and
+/// [`KakouneRenderer`](crate::KakouneRenderer) highlight the spans in an editor instead. Other
+/// crates can implement it to add their own formats.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{DebugSpanOptions, HtmlRenderer, LineColumnSpan, Renderer};
+///
+/// let spans = [LineColumnSpan::new(1, 7, 1, 10)];
+/// let output = HtmlRenderer.render(&spans, &"struct Foo;", &DebugSpanOptions::default());
+/// assert!(output.starts_with("<pre class=\"debug-span\">"));
+/// ```
+pub trait Renderer {
+    /// Render `spans` of `code` in a single frame.
+    fn render(
+        &self,
+        spans: &[LineColumnSpan],
+        code: &dyn SourceCode,
+        options: &DebugSpanOptions,
+    ) -> String;
+}
+
+/// Plain text, colors and highlighting are ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TextRenderer;
+
+/// Text with ANSI escapes, colored with the [theme](DebugSpanOptions::theme) or the default one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AnsiRenderer;
+
+/// An HTML `<pre class="debug-span">` element, colored with the
+/// [theme](DebugSpanOptions::theme) if any, and linked to the source with the
+/// [link](DebugSpanOptions::link) option.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct HtmlRenderer;
+
+/// An SVG image with a line of monospace text per line of the rendering, colored with the
+/// [theme](DebugSpanOptions::theme) if any.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SvgRenderer;
+
+impl Renderer for TextRenderer {
+    fn render(
+        &self,
+        spans: &[LineColumnSpan],
+        code: &dyn SourceCode,
+        options: &DebugSpanOptions,
+    ) -> String {
+        let options = DebugSpanOptions {
+            theme: None,
+            #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+            highlight: None,
+            ..options.clone()
+        };
+        render_text(spans, code, &options)
+    }
+}
+
+impl Renderer for AnsiRenderer {
+    fn render(
+        &self,
+        spans: &[LineColumnSpan],
+        code: &dyn SourceCode,
+        options: &DebugSpanOptions,
+    ) -> String {
+        let options = DebugSpanOptions {
+            theme: Some(options.theme.unwrap_or_default()),
+            #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+            highlight: options.highlight.map(|_| crate::Highlight::Ansi),
+            ..options.clone()
+        };
+        render_text(spans, code, &options)
+    }
+}
+
+impl Renderer for HtmlRenderer {
+    fn render(
+        &self,
+        spans: &[LineColumnSpan],
+        code: &dyn SourceCode,
+        options: &DebugSpanOptions,
+    ) -> String {
+        let text_options = DebugSpanOptions {
+            theme: None,
+            #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+            highlight: options.highlight.map(|_| crate::Highlight::Html),
+            ..options.clone()
+        };
+        let output = render_text(spans, code, &text_options);
+        // highlighted renderings are already escaped
+        let output = if is_highlighted(&text_options) {
+            output
+        } else {
+            escape_html(&output)
+        };
+        let link = options.link.as_ref().and_then(|link| {
+            let path = code.line_origin(spans.first()?.start_line).0?;
+            Some((link, path))
+        });
+        let mut html = String::from("<pre class=\"debug-span\">");
+        for (gutter, rest, is_marker) in styled_lines(&output) {
+            let gutter = match link {
+                Some((link, path)) => linked_gutter(gutter, link, path, spans, code),
+                None => String::from(gutter),
+            };
+            html.push_str(&colored_html(&gutter, |palette| palette.gutter, options));
+            match is_marker {
+                true => html.push_str(&colored_html(rest, |palette| palette.label(0), options)),
+                false => html.push_str(rest),
+            }
+            html.push('\n');
+        }
+        html.push_str("</pre>");
+        html
+    }
+}
+
+impl Renderer for SvgRenderer {
+    fn render(
+        &self,
+        spans: &[LineColumnSpan],
+        code: &dyn SourceCode,
+        options: &DebugSpanOptions,
+    ) -> String {
+        let output = TextRenderer.render(spans, code, options);
+        let lines = styled_lines(&output);
+        let columns = output
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             font-family=\"monospace\" font-size=\"{}\">\n",
+            columns * SVG_CHAR_WIDTH,
+            lines.len() * SVG_LINE_HEIGHT + SVG_LINE_HEIGHT / 4,
+            SVG_FONT_SIZE,
+        );
+        for (index, (gutter, rest, is_marker)) in lines.into_iter().enumerate() {
+            let gutter = colored_svg(gutter, |palette| palette.gutter, options);
+            let rest = match is_marker {
+                true => colored_svg(rest, |palette| palette.label(0), options),
+                false => escape_html(rest),
+            };
+            svg.push_str(&format!(
+                "<text x=\"0\" y=\"{}\" xml:space=\"preserve\">{}{}</text>\n",
+                (index + 1) * SVG_LINE_HEIGHT,
+                gutter,
+                rest,
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+const SVG_FONT_SIZE: usize = 14;
+const SVG_CHAR_WIDTH: usize = 9;
+const SVG_LINE_HEIGHT: usize = 18;
+
+fn render_text(
+    spans: &[LineColumnSpan],
+    code: &dyn SourceCode,
+    options: &DebugSpanOptions,
+) -> String {
+    match spans {
+        [span] => crate::debug_span_with_options(span, code, options),
+        spans => crate::internal::debug_spans_with_options(spans, code, options),
+    }
+}
+
+#[cfg_attr(
+    not(any(feature = "syntect", feature = "tree-sitter")),
+    allow(unused_variables)
+)]
+fn is_highlighted(options: &DebugSpanOptions) -> bool {
+    #[cfg(any(feature = "syntect", feature = "tree-sitter"))]
+    if options.highlight.is_some() && !options.deterministic {
+        return true;
+    }
+    false
+}
+
+/// Splits the lines of a text rendering in their gutter and the rest, and tells if the line is a
+/// marker line: a line with markers or a box and no line number.
+fn styled_lines(output: &str) -> Vec<(&str, &str, bool)> {
+    output
+        .lines()
+        .map(|line| match line.find(" |") {
+            Some(index) => {
+                let (gutter, rest) = line.split_at(index + 2);
+                let is_marker = gutter.trim() == "|" && !rest.trim().is_empty();
+                (gutter, rest, is_marker)
+            }
+            None => (line, "", false),
+        })
+        .collect()
+}
+
+/// Links the line number of an HTML-escaped gutter, or the ranges of the header to the lines of
+/// all spans.
+fn linked_gutter(
+    gutter: &str,
+    link: &SourceLink,
+    path: &str,
+    spans: &[LineColumnSpan],
+    code: &dyn SourceCode,
+) -> String {
+    let anchor = |text: &str, lines| {
+        let url = escape_html(&link.url(path, lines));
+        format!("<a href=\"{}\">{}</a>", url, text)
+    };
+    if let Some(header) = gutter.trim_start().strip_prefix("--&gt; ") {
+        let start = spans.iter().map(|span| span.start_line).min().unwrap_or(1);
+        let end = spans
+            .iter()
+            .map(|span| span.end_line)
+            .max()
+            .unwrap_or(start);
+        let lines = code.line_origin(start).1..=code.line_origin(end).1;
+        let prefix = &gutter[..gutter.len() - header.len()];
+        return format!("{}{}", prefix, anchor(header, lines));
+    }
+    let number = gutter.trim_end_matches(" |").trim_start();
+    match number.parse::<usize>() {
+        Ok(line) => {
+            let start = gutter.len() - " |".len() - number.len();
+            let (indent, rest) = gutter.split_at(start);
+            format!(
+                "{}{}{}",
+                indent,
+                anchor(number, line..=line),
+                &rest[number.len()..]
+            )
+        }
+        Err(_) => String::from(gutter),
+    }
+}
+
+fn colored_html(
+    text: &str,
+    color: impl FnOnce(&Palette) -> Color,
+    options: &DebugSpanOptions,
+) -> String {
+    match (options.theme, options.deterministic, text.is_empty()) {
+        (Some(theme), false, false) => {
+            let (r, g, b) = color(&theme.palette()).rgb;
+            format!(
+                "<span style=\"color:#{:02x}{:02x}{:02x};\">{}</span>",
+                r, g, b, text
+            )
+        }
+        _ => String::from(text),
+    }
+}
+
+fn colored_svg(
+    text: &str,
+    color: impl FnOnce(&Palette) -> Color,
+    options: &DebugSpanOptions,
+) -> String {
+    match (options.theme, options.deterministic, text.is_empty()) {
+        (Some(theme), false, false) => {
+            let (r, g, b) = color(&theme.palette()).rgb;
+            format!(
+                "<tspan fill=\"#{:02x}{:02x}{:02x}\">{}</tspan>",
+                r,
+                g,
+                b,
+                escape_html(text)
+            )
+        }
+        _ => escape_html(text),
+    }
+}
+
+/// Escapes the characters of `text` that are special in HTML and XML.
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Theme;
+    use unindent::Unindent;
+
+    #[test]
+    fn test_renderers() {
+        let input = r###"
+            fn f<T>() {
+                a: i32,
+            }
+        "###
+        .unindent();
+        let spans = [LineColumnSpan::new(1, 4, 1, 7)];
+        let options = DebugSpanOptions {
+            label: Some("<T>".to_string()),
+            theme: Some(Theme::Light),
+            ..Default::default()
+        };
+        insta::assert_snapshot!(TextRenderer.render(&spans, &input, &options), @r###"
+         --> 1:4..1:7
+          |
+        1 | fn f<T>() {
+          |     ^^^ <T>
+          |
+        "###);
+        let output = AnsiRenderer.render(&spans, &input, &options);
+        insta::assert_snapshot!(output.replace('\x1b', "\\e"), @r###"
+         \e[2m\e[38;2;0;92;197m-->\e[0m 1:4..1:7
+         \e[2m\e[38;2;0;92;197m |\e[0m
+        \e[38;2;0;128;128m1\e[0m\e[2m\e[38;2;0;92;197m |\e[0m fn f<T>() {
+         \e[2m\e[38;2;0;92;197m |\e[0m     \e[38;2;215;58;73m^^^ <T>\e[0m
+         \e[2m\e[38;2;0;92;197m |\e[0m
+        "###);
+        insta::assert_snapshot!(HtmlRenderer.render(&spans, &input, &options), @r###"
+        <pre class="debug-span"><span style="color:#005cc5;"> --&gt; 1:4..1:7</span>
+        <span style="color:#005cc5;">  |</span>
+        <span style="color:#005cc5;">1 |</span> fn f&lt;T&gt;() {
+        <span style="color:#005cc5;">  |</span><span style="color:#d73a49;">     ^^^ &lt;T&gt;</span>
+        <span style="color:#005cc5;">  |</span>
+        </pre>
+        "###);
+        insta::assert_snapshot!(SvgRenderer.render(&spans, &input, &options), @r###"
+        <svg xmlns="http://www.w3.org/2000/svg" width="135" height="94" font-family="monospace" font-size="14">
+        <text x="0" y="18" xml:space="preserve"><tspan fill="#005cc5"> --&gt; 1:4..1:7</tspan></text>
+        <text x="0" y="36" xml:space="preserve"><tspan fill="#005cc5">  |</tspan></text>
+        <text x="0" y="54" xml:space="preserve"><tspan fill="#005cc5">1 |</tspan> fn f&lt;T&gt;() {</text>
+        <text x="0" y="72" xml:space="preserve"><tspan fill="#005cc5">  |</tspan><tspan fill="#d73a49">     ^^^ &lt;T&gt;</tspan></text>
+        <text x="0" y="90" xml:space="preserve"><tspan fill="#005cc5">  |</tspan></text>
+        </svg>
+        "###);
+
+        let spans = [
+            LineColumnSpan::new(1, 3, 1, 4),
+            LineColumnSpan::new(2, 4, 2, 5),
+        ];
+        let options = DebugSpanOptions::default();
+        insta::assert_snapshot!(HtmlRenderer.render(&spans, &input, &options), @r###"
+        <pre class="debug-span"> --&gt; 1:3..1:4, 2:4..2:5
+          |
+        1 | fn f&lt;T&gt;() {
+          |    ^
+        2 |     a: i32,
+          |     ^
+          |
+        </pre>
+        "###);
+    }
+
+    #[test]
+    fn test_html_links() {
+        let code = crate::NamedSource::new("./src\\foo.rs", "struct Foo {\n    a: i32,\n}");
+        let spans = [
+            LineColumnSpan::new(1, 7, 1, 10),
+            LineColumnSpan::new(2, 4, 2, 10),
+        ];
+        let options = DebugSpanOptions {
+            link: Some(SourceLink::new("https://github.com/owner/repo/", "v1.0")),
+            ..Default::default()
+        };
+        insta::assert_snapshot!(HtmlRenderer.render(&spans, &code, &options), @r###"
+        <pre class="debug-span"> --&gt; <a href="https://github.com/owner/repo/blob/v1.0/src/foo.rs#L1-L2">./src\foo.rs:1:7..1:10, 2:4..2:10</a>
+          |
+        <a href="https://github.com/owner/repo/blob/v1.0/src/foo.rs#L1">1</a> | struct Foo {
+          |        ^^^
+        <a href="https://github.com/owner/repo/blob/v1.0/src/foo.rs#L2">2</a> |     a: i32,
+          |     ^^^^^^
+          |
+        </pre>
+        "###);
+        let output = HtmlRenderer.render(&spans, &"struct Foo {\n    a: i32,\n}", &options);
+        assert!(!output.contains("<a "));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::render::{finish, TextVisitor};
+use crate::{Charset, DebugSpanOptions, Event, LineColumnSpan, SourceCode, Span, Visitor};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Render macro input and generated code side by side, with corresponding spans marked on both
+/// sides.
+///
+/// `pairs` are `(input span, output span)` pairs. The input spans are rendered on the left and
+/// the output spans on the right, each side like
+/// [`debug_spans_with_options`](crate::debug_spans_with_options). Every marker is
+/// labeled with the 1-based number of its pair, and both markers of a pair get the same color of
+/// the [theme](DebugSpanOptions::theme).
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{side_by_side, DebugSpanOptions, LineColumnSpan};
+///
+/// let input = "#[derive(Foo)]\nstruct Foo;";
+/// let output = "impl Foo for Foo {}";
+/// let pairs = [(LineColumnSpan::new(2, 7, 2, 10), LineColumnSpan::new(1, 13, 1, 16))];
+/// let rendering = side_by_side(&pairs, &input, &output, &DebugSpanOptions::default());
+/// assert_eq!(rendering.lines().nth(2), Some("2 | struct Foo;  │ 1 | impl Foo for Foo {}"));
+/// ```
+pub fn side_by_side(
+    pairs: &[(LineColumnSpan, LineColumnSpan)],
+    input: &(impl SourceCode + ?Sized),
+    output: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> String {
+    let left = render_side(pairs, |(span, _)| span, input, options);
+    let right = render_side(pairs, |(_, span)| span, output, options);
+    if left.is_empty() && right.is_empty() {
+        return String::new();
+    }
+    let left = left.lines().collect::<Vec<_>>();
+    let right = right.lines().collect::<Vec<_>>();
+    let width = left
+        .iter()
+        .map(|line| visible_width(line))
+        .max()
+        .unwrap_or(0);
+    let separator = match options.charset {
+        Charset::Unicode => '│',
+        Charset::Ascii => '|',
+    };
+    let mut rendering = String::new();
+    for row in 0..left.len().max(right.len()) {
+        let left = left.get(row).copied().unwrap_or("");
+        let right = right.get(row).copied().unwrap_or("");
+        let padding = " ".repeat(width - visible_width(left));
+        let line = format!("{}{} {} {}", left, padding, separator, right);
+        rendering.push_str(line.trim_end());
+        rendering.push('\n');
+    }
+    rendering
+}
+
+/// Renders the spans of one side, labeled with the numbers of their pairs.
+fn render_side(
+    pairs: &[(LineColumnSpan, LineColumnSpan)],
+    side: impl Fn(&(LineColumnSpan, LineColumnSpan)) -> &LineColumnSpan,
+    code: &(impl SourceCode + ?Sized),
+    options: &DebugSpanOptions,
+) -> String {
+    let spans = pairs.iter().map(&side).copied().collect::<Vec<_>>();
+    // the pair of each marker index, in the order `visit_spans` draws the spans
+    let mut pair_indices = (0..pairs.len())
+        .filter(|&index| !spans[index].is_empty())
+        .collect::<Vec<_>>();
+    if options.deterministic {
+        pair_indices.sort_by_key(|&index| spans[index]);
+    }
+    let numbers = (1..=pairs.len())
+        .map(|number| number.to_string())
+        .collect::<Vec<_>>();
+    let mut visitor = PairVisitor {
+        inner: TextVisitor::new(options),
+        pair_indices,
+        numbers,
+    };
+    crate::multi::visit_spans(&spans, code, options, &mut visitor);
+    finish(visitor.inner.output, options)
+}
+
+/// Labels the markers with the number of their pair before rendering them as text.
+struct PairVisitor<'a> {
+    inner: TextVisitor<'a>,
+    pair_indices: Vec<usize>,
+    numbers: Vec<String>,
+}
+
+impl Visitor for PairVisitor<'_> {
+    fn visit(&mut self, event: Event<'_>) {
+        match event {
+            Event::Marker {
+                kind,
+                column,
+                width,
+                index,
+                ..
+            } => {
+                let pair = self.pair_indices[index];
+                self.inner.visit(Event::Marker {
+                    kind,
+                    column,
+                    width,
+                    label: Some(&self.numbers[pair]),
+                    index: pair,
+                });
+            }
+            event => self.inner.visit(event),
+        }
+    }
+}
+
+/// Number of characters of `line` without its ANSI escapes.
+fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NamedSource;
+
+    #[test]
+    fn test_side_by_side() {
+        let input = NamedSource::new("lib.rs", "#[derive(Foo)]\nstruct Foo {\n    a: i32,\n}");
+        let output = NamedSource::new(
+            "<derive Foo>",
+            "impl Foo for Foo {\n    fn a(&self) -> &i32 {\n        &self.a\n    }\n}",
+        );
+        let pairs = [
+            (
+                LineColumnSpan::new(3, 4, 3, 5),
+                LineColumnSpan::new(2, 7, 2, 8),
+            ),
+            (
+                LineColumnSpan::new(3, 4, 3, 5),
+                LineColumnSpan::new(3, 14, 3, 15),
+            ),
+            (
+                LineColumnSpan::new(2, 7, 2, 10),
+                LineColumnSpan::new(1, 13, 1, 16),
+            ),
+        ];
+        let options = DebugSpanOptions {
+            deterministic: true,
+            ..Default::default()
+        };
+        let rendering = side_by_side(&pairs, &input, &output, &options);
+        insta::assert_snapshot!(rendering, @r###"
+         --> lib.rs:2:7..2:10, 3:4..3:5, 3:4..3:5 │  --> <derive Foo>:1:13..1:16, 2:7..2:8, 3:14..3:15
+          |                                       │   |
+        2 | struct Foo {                          │ 1 | impl Foo for Foo {
+          |        ^^^ 3                          │   |              ^^^ 3
+        3 |     a: i32,                           │ 2 |     fn a(&self) -> &i32 {
+          |     ^ 1                               │   |        ^ 1
+          |     ^ 2                               │ 3 |         &self.a
+          |                                       │   |               ^ 2
+                                                  │   |
+        "###);
+
+        let options = DebugSpanOptions {
+            charset: Charset::Ascii,
+            ..Default::default()
+        };
+        let pairs = [(LineColumnSpan::new(2, 7, 2, 10), LineColumnSpan::default())];
+        let rendering = side_by_side(&pairs, &input, &output, &options);
+        insta::assert_snapshot!(rendering, @r###"
+         --> lib.rs:2:7..2:10 |
+          |                   |
+        2 | struct Foo {      |
+          |        ^^^ 1      |
+          |                   |
+        "###);
+        assert_eq!(side_by_side(&[], &input, &output, &options), "");
+    }
+}
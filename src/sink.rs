@@ -0,0 +1,85 @@
+use std::ffi::OsString;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The environment variable redirecting the stderr output of the crate to a file.
+const OUT_VAR: &str = "DEBUG_SPAN_OUT";
+
+/// Print `output` to stderr, or append it to the file named by `DEBUG_SPAN_OUT` if it is set.
+///
+/// cargo hides the stderr of proc-macros in some situations, e.g. when the expansion succeeds
+/// in a dependency; the file keeps the output of every invocation. If the file can't be written,
+/// the output goes to stderr.
+pub fn eprint(output: &str) {
+    match out_path() {
+        Some(path) => {
+            if append(Path::new(&path), output).is_err() {
+                write_stderr(output);
+            }
+        }
+        None => write_stderr(output),
+    }
+}
+
+/// Whether `DEBUG_SPAN_OUT` redirects the output of [`eprint`] to a file.
+#[cfg(feature = "color")]
+pub fn is_redirected() -> bool {
+    out_path().is_some()
+}
+
+fn out_path() -> Option<OsString> {
+    std::env::var_os(OUT_VAR).filter(|path| !path.is_empty())
+}
+
+fn write_stderr(output: &str) {
+    #[cfg(feature = "windows-console")]
+    let output = crate::console::for_console(output);
+    eprint!("{}", output);
+}
+
+fn append(path: &Path, output: &str) -> io::Result<()> {
+    static INVOCATIONS: AtomicUsize = AtomicUsize::new(0);
+    let invocation = INVOCATIONS.fetch_add(1, Ordering::Relaxed) + 1;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let record = format!(
+        "--- #{} pid {} at {}.{:03} ---\n{}",
+        invocation,
+        std::process::id(),
+        timestamp.as_secs(),
+        timestamp.subsec_millis(),
+        output
+    );
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(record.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append() {
+        let path = std::env::temp_dir().join(format!("debug-span-out-{}.txt", std::process::id()));
+        append(&path, "first\n").unwrap();
+        append(&path, "second\n").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines = content.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        let header = format!(" pid {} at ", std::process::id());
+        assert!(
+            lines[0].starts_with("--- #") && lines[0].contains(&header),
+            "{}",
+            lines[0]
+        );
+        assert_eq!(lines[1], "first");
+        assert!(lines[2].ends_with(" ---"), "{}", lines[2]);
+        assert_eq!(lines[3], "second");
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{LineColumnSpan, SourceCode, Span};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A structured excerpt of the source code around a span, created by [`extract_snippet`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Snippet {
+    /// The included lines, without line terminators.
+    pub lines: Vec<String>,
+    /// The line number of the first included line in the source code.
+    pub first_line: usize,
+    /// The position of the span within the excerpt, where line `1` is the first included line.
+    pub span: LineColumnSpan,
+}
+
+impl Snippet {
+    /// The line number of the last included line in the source code.
+    pub fn last_line(&self) -> usize {
+        self.first_line + self.lines.len().saturating_sub(1)
+    }
+
+    /// Iterate over the included lines with their line number in the source code.
+    pub fn numbered_lines(&self) -> impl Iterator<Item = (usize, &str)> {
+        (self.first_line..).zip(self.lines.iter().map(String::as_str))
+    }
+
+    /// Returns the marked columns of the `index`-th included line, the end excluded.
+    #[cfg(any(feature = "ratatui", feature = "egui"))]
+    pub(crate) fn marked_columns(&self, index: usize) -> Option<(usize, usize)> {
+        let span = &self.span;
+        let line = index + 1;
+        if line < span.start_line || line > span.end_line {
+            return None;
+        }
+        let start = if line == span.start_line {
+            span.start_column
+        } else {
+            0
+        };
+        let end = if line == span.end_line {
+            span.end_column
+        } else {
+            self.lines.get(index).map_or(0, |line| line.chars().count())
+        };
+        Some((start, end))
+    }
+
+    /// The row shown under the last line of the span: its column and its text with the label.
+    #[cfg(any(feature = "ratatui", feature = "egui"))]
+    pub(crate) fn label_row(&self, label: &str) -> (usize, String) {
+        let span = &self.span;
+        if span.is_single_line() {
+            let marker = "^".repeat(span.end_column.saturating_sub(span.start_column).max(1));
+            (span.start_column, alloc::format!("{} {}", marker, label))
+        } else {
+            (
+                span.end_column.saturating_sub(1),
+                alloc::format!("^ {}", label),
+            )
+        }
+    }
+}
+
+/// Extract the lines covered by `span`, with `context` lines before and after it.
+///
+/// It does the line math of the built-in renderer, for custom renderers and editors.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{extract_snippet, LineColumnSpan};
+///
+/// let code = "struct Foo {\n    a: i32,\n    b: i32,\n}";
+/// let snippet = extract_snippet(LineColumnSpan::new(3, 4, 3, 10), code, 1);
+/// assert_eq!(snippet.lines, ["    a: i32,", "    b: i32,", "}"]);
+/// assert_eq!(snippet.first_line, 2);
+/// assert_eq!(snippet.span, LineColumnSpan::new(2, 4, 2, 10));
+/// ```
+pub fn extract_snippet(
+    span: impl Span,
+    code: &(impl SourceCode + ?Sized),
+    context: usize,
+) -> Snippet {
+    let line_count = code.line_count();
+    let first_line = span.start_line().saturating_sub(context).max(1);
+    let last_line = (span.end_line() + context).min(line_count);
+    let lines = (first_line..=last_line)
+        .filter_map(|line_number| code.line(line_number))
+        .map(|line| line.into_owned())
+        .collect();
+    let offset = first_line - 1;
+    Snippet {
+        lines,
+        first_line,
+        span: LineColumnSpan::new(
+            span.start_line().saturating_sub(offset),
+            span.start_column(),
+            span.end_line().saturating_sub(offset),
+            span.end_column(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_snippet() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        let snippet = extract_snippet(LineColumnSpan::new(1, 11, 3, 1), code, 2);
+        assert_eq!(snippet.first_line, 1);
+        assert_eq!(snippet.last_line(), 3);
+        assert_eq!(snippet.span, LineColumnSpan::new(1, 11, 3, 1));
+        assert_eq!(
+            snippet.numbered_lines().collect::<Vec<_>>(),
+            [(1, "struct Foo {"), (2, "    a: i32,"), (3, "}")]
+        );
+
+        let snippet = extract_snippet(LineColumnSpan::new(2, 4, 2, 5), code, 0);
+        assert_eq!(snippet.lines, ["    a: i32,"]);
+        assert_eq!(snippet.first_line, 2);
+        assert_eq!(snippet.span, LineColumnSpan::new(1, 4, 1, 5));
+    }
+}
@@ -0,0 +1,763 @@
+use crate::text;
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A source code that spans can be rendered against.
+///
+/// Renderers only look up lines, so the text doesn't need to be stored in a single `&str`: ropes,
+/// memory mapped files or database-backed sources can implement it too. Lines are 1-indexed and
+/// columns are 0-indexed and counted in chars, like in [`Span`](crate::Span).
+///
+/// It is implemented for `str`, `String`, [`SourceText`], [`NamedSource`] and references to any
+/// type implementing it.
+pub trait SourceCode {
+    /// The number of lines, as counted by [`str::lines`].
+    fn line_count(&self) -> usize;
+
+    /// Returns the text of a line without its line terminator, or `None` if it doesn't exist.
+    fn line(&self, line_number: usize) -> Option<Cow<'_, str>>;
+
+    /// Returns the byte offset of a line/column position.
+    ///
+    /// The position right after the last char of a line is valid and points to the line
+    /// terminator.
+    fn byte_offset(&self, line: usize, column: usize) -> Option<usize>;
+
+    /// Returns the line/column position of a byte offset, or `None` if it is out of range or not
+    /// on a char boundary.
+    fn line_column(&self, offset: usize) -> Option<(usize, usize)>;
+
+    /// The path displayed in the header, if any.
+    fn path(&self) -> Option<&str> {
+        None
+    }
+
+    /// The logical path and line number displayed for a line.
+    ///
+    /// It defaults to [`path`](Self::path) and the line number itself, and is overridden by
+    /// [`RemappedSource`] to render code embedded in another file.
+    fn line_origin(&self, line_number: usize) -> (Option<&str>, usize) {
+        (self.path(), line_number)
+    }
+
+    /// A short tag telling where a line comes from, e.g. user input or generated code.
+    ///
+    /// Tags are shown in a column left of the line numbers with
+    /// [`DebugSpanOptions::provenance`](crate::DebugSpanOptions::provenance), see
+    /// [`TaggedSource`].
+    fn line_tag(&self, line_number: usize) -> Option<&str> {
+        let _ = line_number;
+        None
+    }
+}
+
+impl SourceCode for str {
+    fn line_count(&self) -> usize {
+        self.lines().count()
+    }
+    fn line(&self, line_number: usize) -> Option<Cow<'_, str>> {
+        self.lines()
+            .nth(line_number.checked_sub(1)?)
+            .map(Cow::Borrowed)
+    }
+    fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        text::byte_offset(self, line, column)
+    }
+    fn line_column(&self, offset: usize) -> Option<(usize, usize)> {
+        if !self.is_char_boundary(offset) {
+            return None;
+        }
+        Some(text::line_column_at(self, offset))
+    }
+}
+
+impl SourceCode for String {
+    fn line_count(&self) -> usize {
+        self.as_str().line_count()
+    }
+    fn line(&self, line_number: usize) -> Option<Cow<'_, str>> {
+        self.as_str().line(line_number)
+    }
+    fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        self.as_str().byte_offset(line, column)
+    }
+    fn line_column(&self, offset: usize) -> Option<(usize, usize)> {
+        self.as_str().line_column(offset)
+    }
+}
+
+impl<T: SourceCode + ?Sized> SourceCode for &T {
+    fn line_count(&self) -> usize {
+        (**self).line_count()
+    }
+    fn line(&self, line_number: usize) -> Option<Cow<'_, str>> {
+        (**self).line(line_number)
+    }
+    fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        (**self).byte_offset(line, column)
+    }
+    fn line_column(&self, offset: usize) -> Option<(usize, usize)> {
+        (**self).line_column(offset)
+    }
+    fn path(&self) -> Option<&str> {
+        (**self).path()
+    }
+    fn line_origin(&self, line_number: usize) -> (Option<&str>, usize) {
+        (**self).line_origin(line_number)
+    }
+    fn line_tag(&self, line_number: usize) -> Option<&str> {
+        (**self).line_tag(line_number)
+    }
+}
+
+/// An owned source code with a line index.
+///
+/// Looking up a line of a `&str` scans the text from the start. `SourceText` indexes the line
+/// starts once, which makes repeated renderings over large sources cheaper.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span, LineColumnSpan, SourceCode, SourceText};
+///
+/// let code = SourceText::new("struct Foo {\n    a: i32,\n}");
+/// assert_eq!(code.line_count(), 3);
+/// assert_eq!(code.line(2).as_deref(), Some("    a: i32,"));
+/// assert_eq!(code.byte_offset(2, 4), Some(17));
+/// assert_eq!(code.line_column(17), Some((2, 4)));
+/// let output = debug_span(LineColumnSpan::new(2, 4, 2, 10), &code);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceText {
+    text: String,
+    /// Byte offset of the start of every `\n` separated line.
+    line_starts: Vec<usize>,
+    /// Offsets in `text` of the replacement characters inserted by `from_bytes_lossy`, with the
+    /// length of the invalid bytes they replace.
+    replacements: Vec<(usize, usize)>,
+}
+
+impl SourceText {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self::with_replacements(text.into(), Vec::new())
+    }
+
+    /// Decode a source which may contain invalid UTF-8, like [`String::from_utf8_lossy`].
+    ///
+    /// Every invalid sequence is displayed as a single `U+FFFD` replacement character, one
+    /// column wide. [`byte_offset`](SourceCode::byte_offset) and
+    /// [`line_column`](SourceCode::line_column) still use offsets in `bytes`, so spans computed
+    /// from byte ranges of the original source stay correct.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use debug_span::{debug_span, OffsetSpan, SourceCode, SourceText};
+    ///
+    /// let code = SourceText::from_bytes_lossy(b"let s = \"\xff\xfe\";\nlet t = 1;");
+    /// assert_eq!(code.line(1).as_deref(), Some("let s = \"\u{FFFD}\u{FFFD}\";"));
+    /// assert_eq!(code.byte_offset(2, 4), Some(18));
+    /// let span = OffsetSpan::new(18, 19).to_span(&code).unwrap();
+    /// assert!(debug_span(span, &code).contains("2 | let t = 1;\n  |     ^\n"));
+    /// ```
+    pub fn from_bytes_lossy(bytes: &[u8]) -> Self {
+        let mut text = String::with_capacity(bytes.len());
+        let mut replacements = Vec::new();
+        for chunk in bytes.utf8_chunks() {
+            text.push_str(chunk.valid());
+            if !chunk.invalid().is_empty() {
+                replacements.push((text.len(), chunk.invalid().len()));
+                text.push(char::REPLACEMENT_CHARACTER);
+            }
+        }
+        Self::with_replacements(text, replacements)
+    }
+
+    fn with_replacements(text: String, replacements: Vec<(usize, usize)>) -> Self {
+        let line_starts = core::iter::once(0)
+            .chain(text.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+        Self {
+            text,
+            line_starts,
+            replacements,
+        }
+    }
+
+    /// Converts an offset in `text` to an offset in the original bytes.
+    fn original_offset(&self, offset: usize) -> usize {
+        let replaced = char::REPLACEMENT_CHARACTER.len_utf8();
+        self.replacements
+            .iter()
+            .take_while(|(start, _)| *start < offset)
+            .fold(offset, |offset, (_, len)| offset + len - replaced)
+    }
+
+    /// Converts an offset in the original bytes to an offset in `text`, `None` inside an invalid
+    /// sequence.
+    fn text_offset(&self, original: usize) -> Option<usize> {
+        let replaced = char::REPLACEMENT_CHARACTER.len_utf8();
+        let mut offset = original;
+        for &(start, len) in &self.replacements {
+            let original_start = start + original - offset;
+            if original < original_start + len {
+                return (original <= original_start).then_some(offset);
+            }
+            offset = offset + replaced - len;
+        }
+        Some(offset)
+    }
+
+    /// Returns the full text.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the text of a line, including the `\r` of a `\r\n` terminator.
+    fn raw_line(&self, line_number: usize) -> Option<&str> {
+        let start = *self.line_starts.get(line_number.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line_number)
+            .map_or(self.text.len(), |next| next - 1);
+        Some(&self.text[start..end])
+    }
+}
+
+impl From<String> for SourceText {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<&str> for SourceText {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl SourceCode for SourceText {
+    fn line_count(&self) -> usize {
+        if self.text.ends_with('\n') {
+            self.line_starts.len() - 1
+        } else if self.text.is_empty() {
+            0
+        } else {
+            self.line_starts.len()
+        }
+    }
+    fn line(&self, line_number: usize) -> Option<Cow<'_, str>> {
+        if line_number > self.line_count() {
+            return None;
+        }
+        let line = self.raw_line(line_number)?;
+        let terminated = line_number < self.line_starts.len();
+        let line = if terminated {
+            line.strip_suffix('\r').unwrap_or(line)
+        } else {
+            line
+        };
+        Some(Cow::Borrowed(line))
+    }
+    fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        let line_text = self.raw_line(line)?;
+        let offset = match line_text.char_indices().nth(column) {
+            Some((offset, _)) => offset,
+            None if column == line_text.chars().count() => line_text.len(),
+            None => return None,
+        };
+        Some(self.original_offset(self.line_starts[line - 1] + offset))
+    }
+    fn line_column(&self, offset: usize) -> Option<(usize, usize)> {
+        let offset = self.text_offset(offset)?;
+        if !self.text.is_char_boundary(offset) {
+            return None;
+        }
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let column = self.text[self.line_starts[line - 1]..offset]
+            .chars()
+            .count();
+        Some((line, column))
+    }
+}
+
+/// A source code with a path, displayed in the header of the rendering.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span, LineColumnSpan, NamedSource};
+///
+/// let code = NamedSource::new("tests/fixtures/foo.rs", "struct Foo;");
+/// let output = debug_span(LineColumnSpan::new(1, 7, 1, 10), &code);
+/// assert_eq!(
+///     output,
+///     " --> tests/fixtures/foo.rs:1:7..1:10\n  |\n1 | struct Foo;\n  |        ^^^\n  |\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NamedSource<C = String> {
+    path: String,
+    source: C,
+}
+
+impl<C: SourceCode> NamedSource<C> {
+    pub fn new(path: impl Into<String>, source: C) -> Self {
+        Self {
+            path: path.into(),
+            source,
+        }
+    }
+
+    /// Returns the wrapped source.
+    pub fn inner(&self) -> &C {
+        &self.source
+    }
+}
+
+impl<C: SourceCode> SourceCode for NamedSource<C> {
+    fn line_count(&self) -> usize {
+        self.source.line_count()
+    }
+    fn line(&self, line_number: usize) -> Option<Cow<'_, str>> {
+        self.source.line(line_number)
+    }
+    fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        self.source.byte_offset(line, column)
+    }
+    fn line_column(&self, offset: usize) -> Option<(usize, usize)> {
+        self.source.line_column(offset)
+    }
+    fn path(&self) -> Option<&str> {
+        Some(&self.path)
+    }
+    fn line_origin(&self, line_number: usize) -> (Option<&str>, usize) {
+        let (path, line_number) = self.source.line_origin(line_number);
+        (path.or(Some(&self.path)), line_number)
+    }
+    fn line_tag(&self, line_number: usize) -> Option<&str> {
+        self.source.line_tag(line_number)
+    }
+}
+
+/// A source code whose regions correspond to other logical files or lines, like C's `#line`.
+///
+/// From the line of a directive on, the displayed line numbers continue from the logical line,
+/// and the header shows the logical path. It is useful when the rendered string is a snippet
+/// embedded in a larger user-facing file, e.g. a query string inside a Rust file.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span, LineColumnSpan, RemappedSource};
+///
+/// let query = "SELECT *\nFROM users\nWHERE id = ?";
+/// let code = RemappedSource::new(query).remap_file(1, "src/db.rs", 42);
+/// let output = debug_span(LineColumnSpan::new(2, 5, 2, 10), &code);
+/// assert_eq!(
+///     output,
+///     "  --> src/db.rs:43:5..43:10\n   |\n43 | FROM users\n   |      ^^^^^\n   |\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RemappedSource<C> {
+    source: C,
+    /// Line directives, sorted by the line they start at.
+    directives: Vec<LineDirective>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct LineDirective {
+    line_number: usize,
+    path: Option<String>,
+    logical_line: usize,
+}
+
+impl<C: SourceCode> RemappedSource<C> {
+    pub fn new(source: C) -> Self {
+        Self {
+            source,
+            directives: Vec::new(),
+        }
+    }
+
+    /// Display `line_number` and the following lines starting at `logical_line`.
+    pub fn remap_lines(self, line_number: usize, logical_line: usize) -> Self {
+        self.directive(line_number, None, logical_line)
+    }
+
+    /// Display `line_number` and the following lines as lines of `path`, starting at
+    /// `logical_line`.
+    pub fn remap_file(
+        self,
+        line_number: usize,
+        path: impl Into<String>,
+        logical_line: usize,
+    ) -> Self {
+        self.directive(line_number, Some(path.into()), logical_line)
+    }
+
+    fn directive(mut self, line_number: usize, path: Option<String>, logical_line: usize) -> Self {
+        let index = self
+            .directives
+            .partition_point(|directive| directive.line_number <= line_number);
+        self.directives.insert(
+            index,
+            LineDirective {
+                line_number,
+                path,
+                logical_line,
+            },
+        );
+        self
+    }
+
+    /// Returns the wrapped source.
+    pub fn inner(&self) -> &C {
+        &self.source
+    }
+}
+
+impl<C: SourceCode> SourceCode for RemappedSource<C> {
+    fn line_count(&self) -> usize {
+        self.source.line_count()
+    }
+    fn line(&self, line_number: usize) -> Option<Cow<'_, str>> {
+        self.source.line(line_number)
+    }
+    fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        self.source.byte_offset(line, column)
+    }
+    fn line_column(&self, offset: usize) -> Option<(usize, usize)> {
+        self.source.line_column(offset)
+    }
+    fn path(&self) -> Option<&str> {
+        self.source.path()
+    }
+    fn line_origin(&self, line_number: usize) -> (Option<&str>, usize) {
+        let (mut path, mut logical_line) = self.source.line_origin(line_number);
+        for directive in &self.directives {
+            if directive.line_number > line_number {
+                break;
+            }
+            if let Some(directive_path) = &directive.path {
+                path = Some(directive_path);
+            }
+            logical_line = directive.logical_line + (line_number - directive.line_number);
+        }
+        (path, logical_line)
+    }
+    fn line_tag(&self, line_number: usize) -> Option<&str> {
+        self.source.line_tag(line_number)
+    }
+}
+
+/// A source code with [provenance tags](SourceCode::line_tag) on its lines.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span_with_options, DebugSpanOptions, LineColumnSpan, TaggedSource};
+///
+/// let code = TaggedSource::new("#[derive(Foo)]\nstruct Foo;", "user").tag_lines(2..=2, "gen");
+/// let options = DebugSpanOptions {
+///     provenance: true,
+///     context: 1,
+///     ..Default::default()
+/// };
+/// let output = debug_span_with_options(LineColumnSpan::new(2, 7, 2, 10), &code, &options);
+/// assert_eq!(output.lines().nth(2), Some("user 1 | #[derive(Foo)]"));
+/// assert_eq!(output.lines().nth(3), Some("gen  2 | struct Foo;"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TaggedSource<C> {
+    source: C,
+    tag: String,
+    /// Tags of line ranges, overriding `tag`, the last matching one wins.
+    line_tags: Vec<(core::ops::RangeInclusive<usize>, String)>,
+}
+
+impl<C: SourceCode> TaggedSource<C> {
+    /// Tag all lines of `source` with `tag`.
+    pub fn new(source: C, tag: impl Into<String>) -> Self {
+        Self {
+            source,
+            tag: tag.into(),
+            line_tags: Vec::new(),
+        }
+    }
+
+    /// Tag the lines of `lines` with `tag` instead.
+    pub fn tag_lines(
+        mut self,
+        lines: core::ops::RangeInclusive<usize>,
+        tag: impl Into<String>,
+    ) -> Self {
+        self.line_tags.push((lines, tag.into()));
+        self
+    }
+
+    /// Returns the wrapped source.
+    pub fn inner(&self) -> &C {
+        &self.source
+    }
+}
+
+impl<C: SourceCode> SourceCode for TaggedSource<C> {
+    fn line_count(&self) -> usize {
+        self.source.line_count()
+    }
+    fn line(&self, line_number: usize) -> Option<Cow<'_, str>> {
+        self.source.line(line_number)
+    }
+    fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        self.source.byte_offset(line, column)
+    }
+    fn line_column(&self, offset: usize) -> Option<(usize, usize)> {
+        self.source.line_column(offset)
+    }
+    fn path(&self) -> Option<&str> {
+        self.source.path()
+    }
+    fn line_origin(&self, line_number: usize) -> (Option<&str>, usize) {
+        self.source.line_origin(line_number)
+    }
+    fn line_tag(&self, line_number: usize) -> Option<&str> {
+        let tag = self
+            .line_tags
+            .iter()
+            .rev()
+            .find(|(lines, _)| lines.contains(&line_number))
+            .map_or(&self.tag, |(_, tag)| tag);
+        Some(tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{debug_span, DebugSpanOptions, LineColumnSpan};
+    use alloc::vec::Vec;
+    use unindent::Unindent;
+
+    #[test]
+    fn test_source_text_matches_str() {
+        let inputs = [
+            "",
+            "a",
+            "a\n",
+            "a\n\n",
+            "a\r\nbé\r\n\nc\r",
+            "\n\nstruct Foo;",
+        ];
+        for input in inputs {
+            let source = SourceText::new(input);
+            assert_eq!(source.line_count(), input.line_count(), "{:?}", input);
+            for line_number in 0..=input.line_count() + 1 {
+                assert_eq!(
+                    source.line(line_number),
+                    input.line(line_number),
+                    "{:?}",
+                    input
+                );
+                for column in 0..4 {
+                    assert_eq!(
+                        source.byte_offset(line_number, column),
+                        input.byte_offset(line_number, column),
+                        "{:?} {}:{}",
+                        input,
+                        line_number,
+                        column
+                    );
+                }
+            }
+            for offset in 0..=input.len() + 1 {
+                assert_eq!(
+                    source.line_column(offset),
+                    input.line_column(offset),
+                    "{:?} {}",
+                    input,
+                    offset
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_source_text_from_bytes_lossy() {
+        let bytes = b"a\xffb\n\xf0\x9f\x92c\xe9\n";
+        let source = SourceText::from_bytes_lossy(bytes);
+        assert_eq!(source.line(1).as_deref(), Some("a\u{FFFD}b"));
+        assert_eq!(source.line(2).as_deref(), Some("\u{FFFD}c\u{FFFD}"));
+        let offsets = (1..=2)
+            .flat_map(|line| (0..4).map(move |column| (line, column)))
+            .map(|(line, column)| source.byte_offset(line, column))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            offsets,
+            [
+                Some(0),
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(4),
+                Some(7),
+                Some(8),
+                Some(9)
+            ]
+        );
+        let positions = (0..=bytes.len())
+            .map(|offset| source.line_column(offset))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            [
+                Some((1, 0)),
+                Some((1, 1)),
+                Some((1, 2)),
+                Some((1, 3)),
+                Some((2, 0)),
+                None,
+                None,
+                Some((2, 1)),
+                Some((2, 2)),
+                Some((2, 3)),
+                Some((3, 0)),
+            ]
+        );
+        assert_eq!(
+            SourceText::from_bytes_lossy(b"a\r\nb"),
+            SourceText::new("a\r\nb")
+        );
+        let span = LineColumnSpan::new(2, 0, 2, 2);
+        insta::assert_snapshot!(debug_span(span, &source), @r###"
+         --> 2:0..2:2
+          |
+        2 | �c�
+          | ^^
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_custom_source() {
+        /// A source stored as a list of lines.
+        struct Lines(Vec<String>);
+
+        impl SourceCode for Lines {
+            fn line_count(&self) -> usize {
+                self.0.len()
+            }
+            fn line(&self, line_number: usize) -> Option<Cow<'_, str>> {
+                let line = self.0.get(line_number.checked_sub(1)?)?;
+                Some(Cow::Borrowed(line))
+            }
+            fn byte_offset(&self, _line: usize, _column: usize) -> Option<usize> {
+                None
+            }
+            fn line_column(&self, _offset: usize) -> Option<(usize, usize)> {
+                None
+            }
+        }
+
+        let input = "struct Foo {\n    a: i32,\n}";
+        let lines = Lines(input.lines().map(String::from).collect());
+        for span in [
+            LineColumnSpan::new(1, 7, 1, 10),
+            LineColumnSpan::new(1, 11, 3, 1),
+        ] {
+            assert_eq!(debug_span(span, &lines), debug_span(span, input));
+        }
+        crate::SpanChecker::new(&lines)
+            .check(LineColumnSpan::new(1, 11, 3, 1))
+            .covers("{\n    a: i32,\n}");
+    }
+
+    #[test]
+    fn test_remapped_source() {
+        let input = r###"
+            fn query() {
+                sql!("
+                    SELECT *
+                    FROM users
+                ");
+            }
+        "###
+        .unindent();
+        let code = RemappedSource::new(input.as_str())
+            .remap_file(3, "queries/users.sql", 1)
+            .remap_lines(5, 98);
+        assert_eq!(code.line_origin(2), (None, 2));
+        assert_eq!(code.line_origin(4), (Some("queries/users.sql"), 2));
+        assert_eq!(code.line_origin(6), (Some("queries/users.sql"), 99));
+        let options = DebugSpanOptions {
+            context: 1,
+            ..Default::default()
+        };
+        let output =
+            crate::debug_span_with_options(LineColumnSpan::new(3, 8, 4, 12), &code, &options);
+        insta::assert_snapshot!(output, @r###"
+          --> queries/users.sql:1:8..2:12
+           |
+         2 |     sql!("
+           |         ┌─────────────╮
+         1 |         SELECT *      │
+         2 |         FROM users    │
+           |            └──────────╯
+        98 |     ");
+           |
+        "###);
+        let spans = [
+            LineColumnSpan::new(4, 8, 4, 12),
+            LineColumnSpan::new(6, 0, 6, 1),
+        ];
+        let output = crate::internal::debug_spans_with_options(&spans, &code, &Default::default());
+        insta::assert_snapshot!(output, @r###"
+          --> queries/users.sql:2:8..2:12, 99:0..99:1
+           |
+         2 |         FROM users
+           |         ^^^^
+          ...
+        99 | }
+           | ^
+           |
+        "###);
+    }
+
+    #[test]
+    fn test_named_source() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+            }
+        "###
+        .unindent();
+        let code = NamedSource::new("src/foo.rs", input.as_str());
+        assert_eq!(*code.inner(), input);
+        insta::assert_snapshot!(debug_span(LineColumnSpan::new(1, 11, 3, 1), &code), @r###"
+         --> src/foo.rs:1:11..3:1
+          |
+          |            ┌────╮
+        1 | struct Foo {    │
+        2 |     a: i32,     │
+        3 | }               │
+          | └───────────────╯
+          |
+        "###);
+        let spans = [
+            LineColumnSpan::new(1, 7, 1, 10),
+            LineColumnSpan::new(2, 4, 2, 5),
+        ];
+        let output = crate::internal::debug_spans_with_options(&spans, &code, &Default::default());
+        insta::assert_snapshot!(output, @r###"
+         --> src/foo.rs:1:7..1:10, 2:4..2:5
+          |
+        1 | struct Foo {
+          |        ^^^
+        2 |     a: i32,
+          |     ^
+          |
+        "###);
+    }
+}
@@ -0,0 +1,418 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{DebugSpanOptions, LineColumnSpan, NamedSource, SourceCode, Span};
+
+/// Identifies a source registered in a [`SourceMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(usize);
+
+/// A collection of named sources.
+///
+/// Multi-file macro inputs and multi-fixture tests can register every source once and render
+/// spans by [`FileId`], instead of keeping track of which string goes with which span.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{LineColumnSpan, SourceMap};
+///
+/// let mut source_map = SourceMap::new();
+/// let foo = source_map.add("foo.rs", "struct Foo;");
+/// let bar = source_map.add("bar.rs", "struct Bar {\n    a: i32,\n}");
+///
+/// let output = source_map.debug(bar, LineColumnSpan::new(2, 4, 2, 5));
+/// assert_eq!(source_map.name(foo), "foo.rs");
+/// assert_eq!(source_map.file_id("bar.rs"), Some(bar));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+#[derive(Debug, Clone)]
+struct SourceFile {
+    source: NamedSource,
+    /// The span the generated code originates from, for virtual files.
+    origin: Option<(FileId, LineColumnSpan)>,
+    /// The provenance tag of all lines.
+    tag: Option<String>,
+}
+
+impl SourceCode for SourceFile {
+    fn line_count(&self) -> usize {
+        self.source.line_count()
+    }
+    fn line(&self, line_number: usize) -> Option<Cow<'_, str>> {
+        self.source.line(line_number)
+    }
+    fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        self.source.byte_offset(line, column)
+    }
+    fn line_column(&self, offset: usize) -> Option<(usize, usize)> {
+        self.source.line_column(offset)
+    }
+    fn path(&self) -> Option<&str> {
+        self.source.path()
+    }
+    fn line_tag(&self, _line_number: usize) -> Option<&str> {
+        self.tag.as_deref()
+    }
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a source and return its id.
+    pub fn add(&mut self, name: impl Into<String>, code: impl Into<String>) -> FileId {
+        self.push(name.into(), code.into(), None)
+    }
+
+    /// Register generated code as a virtual file, originating from `origin_span` in `origin`.
+    ///
+    /// Renderings of spans in the virtual file are followed by the rendering of the originating
+    /// span, so diagnostics on macro-generated code also show the user code it comes from.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `origin` was not created by this source map.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use debug_span::{LineColumnSpan, SourceMap};
+    ///
+    /// let mut source_map = SourceMap::new();
+    /// let user = source_map.add("lib.rs", "#[derive(Foo)]\nstruct Foo;");
+    /// let generated = source_map.add_virtual(
+    ///     "<derive Foo>",
+    ///     "impl Foo for Foo {}",
+    ///     user,
+    ///     LineColumnSpan::new(1, 9, 1, 12),
+    /// );
+    /// let output = source_map.debug(generated, LineColumnSpan::new(1, 5, 1, 8));
+    /// assert_eq!(source_map.origin(generated), Some((user, LineColumnSpan::new(1, 9, 1, 12))));
+    /// ```
+    pub fn add_virtual(
+        &mut self,
+        name: impl Into<String>,
+        code: impl Into<String>,
+        origin: FileId,
+        origin_span: impl Span,
+    ) -> FileId {
+        self.file(origin);
+        let origin_span = LineColumnSpan::from_span(&origin_span);
+        self.push(name.into(), code.into(), Some((origin, origin_span)))
+    }
+
+    fn push(
+        &mut self,
+        name: String,
+        code: String,
+        origin: Option<(FileId, LineColumnSpan)>,
+    ) -> FileId {
+        self.files.push(SourceFile {
+            source: NamedSource::new(name, code),
+            origin,
+            tag: None,
+        });
+        FileId(self.files.len() - 1)
+    }
+
+    /// Returns the originating span of a virtual file, or `None` for a regular source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `file_id` was not created by this source map.
+    pub fn origin(&self, file_id: FileId) -> Option<(FileId, LineColumnSpan)> {
+        self.file(file_id).origin
+    }
+
+    /// Set the provenance tag of all lines of a source, e.g. `user` or `gen`.
+    ///
+    /// Tags are shown left of the line numbers with
+    /// [`DebugSpanOptions::provenance`](crate::DebugSpanOptions::provenance).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `file_id` was not created by this source map.
+    pub fn set_tag(&mut self, file_id: FileId, tag: impl Into<String>) {
+        self.files
+            .get_mut(file_id.0)
+            .expect("file id does not belong to this source map")
+            .tag = Some(tag.into());
+    }
+
+    /// Returns the provenance tag of a source, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `file_id` was not created by this source map.
+    pub fn tag(&self, file_id: FileId) -> Option<&str> {
+        self.file(file_id).tag.as_deref()
+    }
+
+    /// Returns the id of the first source registered with `name`.
+    pub fn file_id(&self, name: &str) -> Option<FileId> {
+        self.files
+            .iter()
+            .position(|file| file.source.path() == Some(name))
+            .map(FileId)
+    }
+
+    /// Returns the name of a source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `file_id` was not created by this source map.
+    pub fn name(&self, file_id: FileId) -> &str {
+        self.file(file_id).source.path().unwrap_or_default()
+    }
+
+    /// Returns the code of a source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `file_id` was not created by this source map.
+    pub fn code(&self, file_id: FileId) -> &str {
+        self.file(file_id).source.inner()
+    }
+
+    /// Iterate over the ids of all registered sources, in registration order.
+    pub fn file_ids(&self) -> impl Iterator<Item = FileId> {
+        (0..self.files.len()).map(FileId)
+    }
+
+    /// Generate a debug representation of a span in the given source.
+    ///
+    /// The header shows the name of the source. For virtual files, the originating spans are
+    /// rendered after it.
+    ///
+    /// see [`debug_span`](crate::debug_span) for more information.
+    pub fn debug(&self, file_id: FileId, span: impl Span) -> String {
+        self.debug_with_options(file_id, span, &DebugSpanOptions::default())
+    }
+
+    /// Generate a debug representation of a span in the given source with custom options.
+    pub fn debug_with_options(
+        &self,
+        file_id: FileId,
+        span: impl Span,
+        options: &DebugSpanOptions,
+    ) -> String {
+        let file = self.file(file_id);
+        let mut output = crate::internal::debug_span_with_options(&span, file, options);
+        let origin_options = DebugSpanOptions {
+            label: None,
+            ..options.clone()
+        };
+        let mut origin = file.origin;
+        while let Some((file_id, span)) = origin {
+            let file = self.file(file_id);
+            let rendering = crate::internal::debug_span_with_options(&span, file, &origin_options);
+            output.push_str(&format!("generated from:\n{}", rendering));
+            origin = file.origin;
+        }
+        output
+    }
+
+    /// Render spans of several sources in one combined report.
+    ///
+    /// Spans are grouped by source, and every source gets one frame with a `--> name:...` header.
+    /// Sources are ordered by registration and spans by position, so the report doesn't depend on
+    /// the order of `spans`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use debug_span::{LineColumnSpan, SourceMap};
+    ///
+    /// let mut source_map = SourceMap::new();
+    /// let a = source_map.add("a.rs", "struct A;");
+    /// let b = source_map.add("b.rs", "struct B;");
+    /// let output = source_map.debug_report([
+    ///     (b, LineColumnSpan::new(1, 7, 1, 8)),
+    ///     (a, LineColumnSpan::new(1, 7, 1, 8)),
+    /// ]);
+    /// assert!(output.starts_with(" --> a.rs:1:7..1:8\n"));
+    /// ```
+    pub fn debug_report<S: Span>(&self, spans: impl IntoIterator<Item = (FileId, S)>) -> String {
+        self.debug_report_with_options(spans, &DebugSpanOptions::default())
+    }
+
+    /// Render a combined report with custom options.
+    ///
+    /// see [`debug_report`](Self::debug_report) for more information.
+    pub fn debug_report_with_options<S: Span>(
+        &self,
+        spans: impl IntoIterator<Item = (FileId, S)>,
+        options: &DebugSpanOptions,
+    ) -> String {
+        let mut spans = spans
+            .into_iter()
+            .map(|(file_id, span)| (file_id, LineColumnSpan::from_span(&span)))
+            .collect::<Vec<_>>();
+        spans.sort();
+        spans
+            .chunk_by(|(left, _), (right, _)| left == right)
+            .map(|group| {
+                let file = self.file(group[0].0);
+                let spans = group.iter().map(|(_, span)| *span).collect::<Vec<_>>();
+                crate::internal::debug_spans_with_options(&spans, file, options)
+            })
+            .filter(|rendering| !rendering.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn file(&self, file_id: FileId) -> &SourceFile {
+        self.files
+            .get(file_id.0)
+            .expect("file id does not belong to this source map")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+
+    #[test]
+    fn test_source_map() {
+        let mut source_map = SourceMap::new();
+        let foo = source_map.add("foo.rs", "struct Foo;");
+        let bar = source_map.add("bar.rs", "struct Bar {\n    a: i32,\n}");
+        assert_eq!(source_map.file_ids().collect::<Vec<_>>(), vec![foo, bar]);
+        assert_eq!(source_map.file_id("foo.rs"), Some(foo));
+        assert_eq!(source_map.file_id("baz.rs"), None);
+        assert_eq!(source_map.code(foo), "struct Foo;");
+
+        let span = LineColumnSpan::new(1, 7, 1, 10);
+        insta::assert_snapshot!(source_map.debug(foo, span), @r###"
+         --> foo.rs:1:7..1:10
+          |
+        1 | struct Foo;
+          |        ^^^
+          |
+        "###);
+        insta::assert_snapshot!(source_map.debug(bar, span), @r###"
+         --> bar.rs:1:7..1:10
+          |
+        1 | struct Bar {
+          |        ^^^
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_virtual_file() {
+        let mut source_map = SourceMap::new();
+        let user = source_map.add("lib.rs", "#[derive(Foo)]\nstruct Foo;");
+        let derive = source_map.add_virtual(
+            "<derive Foo>",
+            "impl Foo for Foo {\n    fn foo() {}\n}",
+            user,
+            LineColumnSpan::new(1, 9, 1, 12),
+        );
+        let nested =
+            source_map.add_virtual("<foo!>", "42", derive, LineColumnSpan::new(2, 4, 2, 15));
+        assert_eq!(source_map.origin(user), None);
+        assert_eq!(
+            source_map.origin(nested),
+            Some((derive, LineColumnSpan::new(2, 4, 2, 15)))
+        );
+        insta::assert_snapshot!(source_map.debug(nested, LineColumnSpan::new(1, 0, 1, 2)), @r###"
+         --> <foo!>:1:0..1:2
+          |
+        1 | 42
+          | ^^
+          |
+        generated from:
+         --> <derive Foo>:2:4..2:15
+          |
+        2 |     fn foo() {}
+          |     ^^^^^^^^^^^
+          |
+        generated from:
+         --> lib.rs:1:9..1:12
+          |
+        1 | #[derive(Foo)]
+          |          ^^^
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_debug_report() {
+        let mut source_map = SourceMap::new();
+        let a = source_map.add("a.rs", "struct A {\n    b: B,\n}");
+        let b = source_map.add("b.rs", "struct B;");
+        let output = source_map.debug_report([
+            (b, LineColumnSpan::new(1, 7, 1, 8)),
+            (a, LineColumnSpan::new(2, 7, 2, 8)),
+            (a, LineColumnSpan::new(1, 7, 1, 8)),
+        ]);
+        insta::assert_snapshot!(output, @r###"
+         --> a.rs:1:7..1:8, 2:7..2:8
+          |
+        1 | struct A {
+          |        ^
+        2 |     b: B,
+          |        ^
+          |
+
+         --> b.rs:1:7..1:8
+          |
+        1 | struct B;
+          |        ^
+          |
+        "###);
+        assert_eq!(source_map.debug_report::<LineColumnSpan>([]), "");
+    }
+
+    #[test]
+    fn test_provenance() {
+        let mut source_map = SourceMap::new();
+        let user = source_map.add("lib.rs", "#[derive(Foo)]\nstruct Foo;");
+        let generated = source_map.add_virtual(
+            "<derive Foo>",
+            "impl Foo for Foo {}",
+            user,
+            LineColumnSpan::new(1, 9, 1, 12),
+        );
+        source_map.set_tag(user, "user");
+        source_map.set_tag(generated, "gen");
+        assert_eq!(source_map.tag(generated), Some("gen"));
+        let options = DebugSpanOptions {
+            provenance: true,
+            ..Default::default()
+        };
+        let output =
+            source_map.debug_with_options(generated, LineColumnSpan::new(1, 5, 1, 8), &options);
+        insta::assert_snapshot!(output, @r###"
+             --> <derive Foo>:1:5..1:8
+              |
+        gen 1 | impl Foo for Foo {}
+              |      ^^^
+              |
+        generated from:
+              --> lib.rs:1:9..1:12
+               |
+        user 1 | #[derive(Foo)]
+               |          ^^^
+               |
+        "###);
+    }
+
+    #[test]
+    #[should_panic(expected = "file id does not belong to this source map")]
+    fn test_source_map_unknown_file_id() {
+        let mut other = SourceMap::new();
+        let file_id = other.add("foo.rs", "struct Foo;");
+        SourceMap::new().code(file_id);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{LineColumnSpan, SourceCode, Span};
+
+/// Set-like operations on spans, working on line and column coordinates only.
+///
+/// Spans are half-open: the start position is covered, the end position is not. Positions are
+/// ordered by line, then by column.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{LineColumnSpan, SpanExt};
+///
+/// let fields = LineColumnSpan::new(1, 11, 3, 1);
+/// let field = LineColumnSpan::new(2, 4, 2, 10);
+/// assert!(fields.contains_span(&field));
+/// assert!(fields.contains_point(2, 4));
+/// assert!(!fields.contains_point(3, 1));
+/// assert_eq!(fields.intersection(&field), Some(field));
+/// ```
+pub trait SpanExt: Span {
+    /// Returns `true` if the position `line:column` is covered by the span.
+    fn contains_point(&self, line: usize, column: usize) -> bool {
+        start(self) <= (line, column) && (line, column) < end(self)
+    }
+
+    /// Returns `true` if `other` lies entirely within the span.
+    fn contains_span(&self, other: &(impl Span + ?Sized)) -> bool {
+        start(self) <= start(other) && end(other) <= end(self)
+    }
+
+    /// Returns `true` if the spans cover at least one common position. Adjacent spans don't
+    /// intersect.
+    fn intersects(&self, other: &(impl Span + ?Sized)) -> bool {
+        start(self) < end(other) && start(other) < end(self)
+    }
+
+    /// Returns the smallest span covering both spans, and whatever lies between them.
+    fn join(&self, other: &(impl Span + ?Sized)) -> LineColumnSpan {
+        let (start_line, start_column) = start(self).min(start(other));
+        let (end_line, end_column) = end(self).max(end(other));
+        LineColumnSpan::new(start_line, start_column, end_line, end_column)
+    }
+
+    /// Returns the positions covered by both spans, or `None` if they don't
+    /// [intersect](SpanExt::intersects).
+    fn intersection(&self, other: &(impl Span + ?Sized)) -> Option<LineColumnSpan> {
+        if !self.intersects(other) {
+            return None;
+        }
+        let (start_line, start_column) = start(self).max(start(other));
+        let (end_line, end_column) = end(self).min(end(other));
+        Some(LineColumnSpan::new(
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        ))
+    }
+
+    /// Returns the coordinates of the span relative to the start of `outer`, or `None` if `outer`
+    /// doesn't [contain](SpanExt::contains_span) it.
+    ///
+    /// The start of `outer` becomes `1:0`: lines are counted from the start line of `outer`, and
+    /// columns on that line from its start column. These are the coordinates of the span in the
+    /// text covered by `outer`, e.g. a sub-snippet extracted with [`span_text`](crate::span_text).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use debug_span::{find_span, span_text, LineColumnSpan, SpanExt};
+    ///
+    /// let code = "struct Foo {\n    a: i32,\n}";
+    /// let body = find_span("{\n    a: i32,\n}", code);
+    /// let field = find_span("a: i32", code);
+    /// let relative = field.relative_to(&body).unwrap();
+    /// assert_eq!(relative, LineColumnSpan::new(2, 4, 2, 10));
+    /// assert_eq!(span_text(relative, span_text(body, code)), "a: i32");
+    /// assert_eq!(relative.rebase(&body), field);
+    /// ```
+    fn relative_to(&self, outer: &(impl Span + ?Sized)) -> Option<LineColumnSpan> {
+        if !outer.contains_span(self) {
+            return None;
+        }
+        let (start_line, start_column) = relative_position(start(self), outer);
+        let (end_line, end_column) = relative_position(end(self), outer);
+        Some(LineColumnSpan::new(
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        ))
+    }
+
+    /// The inverse of [`relative_to`](SpanExt::relative_to), turning coordinates relative to the
+    /// start of `outer` into absolute ones.
+    fn rebase(&self, outer: &(impl Span + ?Sized)) -> LineColumnSpan {
+        let (start_line, start_column) = absolute_position(start(self), outer);
+        let (end_line, end_column) = absolute_position(end(self), outer);
+        LineColumnSpan::new(start_line, start_column, end_line, end_column)
+    }
+}
+
+impl<T: Span + ?Sized> SpanExt for T {}
+
+/// Returns the number of lines touched by both spans.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{overlap_lines, LineColumnSpan};
+///
+/// let a = LineColumnSpan::new(1, 0, 3, 1);
+/// let b = LineColumnSpan::new(2, 4, 5, 0);
+/// assert_eq!(overlap_lines(a, b), 2);
+/// ```
+pub fn overlap_lines(a: impl Span, b: impl Span) -> usize {
+    let start = a.start_line().max(b.start_line());
+    let end = a.end_line().min(b.end_line());
+    (end + 1).saturating_sub(start)
+}
+
+/// Returns the number of characters of `code` covered by both spans, counting line breaks as one
+/// character.
+///
+/// Returns `0` if the spans don't [intersect](SpanExt::intersects) or the common part is out of
+/// range of the code.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{find_span, overlap_chars};
+///
+/// let code = "struct Foo {\n    a: i32,\n}";
+/// let field = find_span("a: i32", code);
+/// assert_eq!(overlap_chars(field, find_span("i32,", code), code), 3);
+/// ```
+pub fn overlap_chars(a: impl Span, b: impl Span, code: &(impl SourceCode + ?Sized)) -> usize {
+    a.intersection(&b)
+        .and_then(|span| crate::text::get_source_span_text(&span, code))
+        .map_or(0, |text| text.chars().count())
+}
+
+/// Returns the number of lines between two spans, `0` if they share a line.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{distance, LineColumnSpan};
+///
+/// let attribute = LineColumnSpan::new(1, 0, 1, 16);
+/// let error = LineColumnSpan::new(2, 7, 2, 10);
+/// assert!(distance(attribute, error) <= 1);
+/// ```
+pub fn distance(a: impl Span, b: impl Span) -> usize {
+    if a.end_line() < b.start_line() {
+        b.start_line() - a.end_line()
+    } else {
+        a.start_line().saturating_sub(b.end_line())
+    }
+}
+
+fn relative_position(
+    (line, column): (usize, usize),
+    outer: &(impl Span + ?Sized),
+) -> (usize, usize) {
+    if line == outer.start_line() {
+        (1, column - outer.start_column())
+    } else {
+        (line - outer.start_line() + 1, column)
+    }
+}
+
+fn absolute_position(
+    (line, column): (usize, usize),
+    outer: &(impl Span + ?Sized),
+) -> (usize, usize) {
+    if line == 1 {
+        (outer.start_line(), column + outer.start_column())
+    } else {
+        (line + outer.start_line() - 1, column)
+    }
+}
+
+fn start(span: &(impl Span + ?Sized)) -> (usize, usize) {
+    (span.start_line(), span.start_column())
+}
+
+fn end(span: &(impl Span + ?Sized)) -> (usize, usize) {
+    (span.end_line(), span.end_column())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_ext() {
+        let a = LineColumnSpan::new(1, 4, 2, 3);
+        let b = LineColumnSpan::new(2, 0, 3, 1);
+        let c = LineColumnSpan::new(2, 3, 2, 5);
+
+        assert!(a.contains_point(1, 10));
+        assert!(a.contains_point(2, 2));
+        assert!(!a.contains_point(1, 3));
+        assert!(!a.contains_point(2, 3));
+
+        assert!(a.contains_span(&a));
+        assert!(a.contains_span(&LineColumnSpan::new(1, 10, 2, 0)));
+        assert!(!a.contains_span(&b));
+
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&c));
+        assert!(b.intersects(&c));
+
+        assert_eq!(a.join(&c), LineColumnSpan::new(1, 4, 2, 5));
+        assert_eq!(c.join(&a), LineColumnSpan::new(1, 4, 2, 5));
+
+        assert_eq!(a.intersection(&b), Some(LineColumnSpan::new(2, 0, 2, 3)));
+        assert_eq!(b.intersection(&c), Some(c));
+        assert_eq!(a.intersection(&c), None);
+    }
+
+    #[test]
+    fn test_relative_to() {
+        let outer = LineColumnSpan::new(2, 4, 4, 1);
+        let inner = LineColumnSpan::new(2, 6, 3, 2);
+        let relative = inner.relative_to(&outer).unwrap();
+        assert_eq!(relative, LineColumnSpan::new(1, 2, 2, 2));
+        assert_eq!(relative.rebase(&outer), inner);
+        assert_eq!(
+            outer.relative_to(&outer),
+            Some(LineColumnSpan::new(1, 0, 3, 1))
+        );
+        assert_eq!(LineColumnSpan::new(2, 0, 2, 5).relative_to(&outer), None);
+    }
+
+    #[test]
+    fn test_overlap_and_distance() {
+        let code = "struct Foo {\n    a: i32,\n    b: i32,\n}";
+        let a = LineColumnSpan::new(1, 11, 2, 5);
+        let b = LineColumnSpan::new(2, 4, 3, 5);
+        let c = LineColumnSpan::new(4, 0, 4, 1);
+
+        assert_eq!(overlap_lines(a, b), 1);
+        assert_eq!(overlap_lines(a, c), 0);
+        assert_eq!(overlap_chars(a, b, code), 1);
+        assert_eq!(overlap_chars(b, LineColumnSpan::new(2, 9, 3, 4), code), 7);
+        assert_eq!(overlap_chars(a, c, code), 0);
+
+        assert_eq!(distance(a, b), 0);
+        assert_eq!(distance(a, c), 2);
+        assert_eq!(distance(c, a), 2);
+    }
+}
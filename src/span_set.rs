@@ -0,0 +1,248 @@
+use crate::{trim_span, DebugSpanOptions, LineColumnSpan, SourceCode, Span, SpanExt};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A normalized set of spans.
+///
+/// Spans are kept sorted by position, and overlapping or adjacent spans are merged into a single
+/// span. Empty spans cover nothing and are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{LineColumnSpan, SpanSet};
+///
+/// let mut set = SpanSet::new();
+/// set.insert(LineColumnSpan::new(2, 4, 2, 10));
+/// set.insert(LineColumnSpan::new(1, 7, 1, 10));
+/// set.insert(LineColumnSpan::new(2, 8, 2, 12));
+/// assert_eq!(
+///     set.as_slice(),
+///     [LineColumnSpan::new(1, 7, 1, 10), LineColumnSpan::new(2, 4, 2, 12)]
+/// );
+/// assert!(set.contains_point(2, 11));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SpanSet {
+    spans: Vec<LineColumnSpan>,
+}
+
+impl SpanSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a span, merging it with the overlapping and adjacent spans of the set.
+    pub fn insert(&mut self, span: impl Span) {
+        let mut span = LineColumnSpan::from_span(&span);
+        if span.is_empty() {
+            return;
+        }
+        // the first span ending at or after the start of the new span, and the first span
+        // starting after its end: everything in between overlaps or touches it
+        let first = self
+            .spans
+            .partition_point(|other| (other.end_line, other.end_column) < start(&span));
+        let last = self
+            .spans
+            .partition_point(|other| (other.start_line, other.start_column) <= end(&span));
+        for other in &self.spans[first..last] {
+            span = span.join(other);
+        }
+        self.spans.splice(first..last, [span]);
+    }
+
+    /// Returns `true` if the position `line:column` is covered by a span of the set.
+    pub fn contains_point(&self, line: usize, column: usize) -> bool {
+        self.spans
+            .iter()
+            .any(|span| span.contains_point(line, column))
+    }
+
+    /// Returns `true` if `span` lies entirely within a span of the set.
+    pub fn contains_span(&self, span: &(impl Span + ?Sized)) -> bool {
+        self.spans.iter().any(|other| other.contains_span(span))
+    }
+
+    /// Returns `true` if `span` intersects a span of the set.
+    pub fn intersects(&self, span: &(impl Span + ?Sized)) -> bool {
+        self.spans.iter().any(|other| other.intersects(span))
+    }
+
+    /// The normalized spans, sorted by position.
+    pub fn as_slice(&self) -> &[LineColumnSpan] {
+        &self.spans
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, LineColumnSpan> {
+        self.spans.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns the regions of `code` not covered by any span of the set.
+    ///
+    /// Whitespace never needs a span: every gap is [trimmed](crate::trim_span), and gaps made
+    /// only of whitespace are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use debug_span::{find_span, LineColumnSpan, SpanSet};
+    ///
+    /// let code = "struct Foo {\n    a: i32,\n}";
+    /// let set = ["struct", "Foo", "{", "a", ":", "i32", "}"]
+    ///     .into_iter()
+    ///     .map(|token| find_span(token, code))
+    ///     .collect::<SpanSet>();
+    /// let gaps = set.gaps(code);
+    /// assert_eq!(gaps.as_slice(), [LineColumnSpan::new(2, 10, 2, 11)]);
+    /// ```
+    pub fn gaps(&self, code: &(impl SourceCode + ?Sized)) -> SpanSet {
+        let line_count = code.line_count();
+        if line_count == 0 {
+            return SpanSet::new();
+        }
+        let code_end = (
+            line_count,
+            code.line(line_count).map_or(0, |line| line.chars().count()),
+        );
+        let mut gaps = SpanSet::new();
+        let mut gap_start = (1, 0);
+        let boundaries = self.spans.iter().map(|span| (start(span), end(span)));
+        for (gap_end, next_gap_start) in boundaries.chain([(code_end, code_end)]) {
+            let gap = LineColumnSpan::new(gap_start.0, gap_start.1, gap_end.0, gap_end.1);
+            let gap = trim_span(gap, code);
+            if !gap.is_empty() {
+                gaps.spans.push(gap);
+            }
+            gap_start = next_gap_start;
+        }
+        gaps
+    }
+
+    /// Render all spans of the set in a single frame.
+    pub fn debug(&self, code: &(impl SourceCode + ?Sized)) -> String {
+        self.debug_with_options(code, &DebugSpanOptions::default())
+    }
+
+    /// Render all spans of the set in a single frame with custom [`DebugSpanOptions`].
+    pub fn debug_with_options(
+        &self,
+        code: &(impl SourceCode + ?Sized),
+        options: &DebugSpanOptions,
+    ) -> String {
+        crate::internal::debug_spans_with_options(&self.spans, code, options)
+    }
+}
+
+impl<S: Span> Extend<S> for SpanSet {
+    fn extend<T: IntoIterator<Item = S>>(&mut self, iter: T) {
+        for span in iter {
+            self.insert(span);
+        }
+    }
+}
+
+impl<S: Span> FromIterator<S> for SpanSet {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<'a> IntoIterator for &'a SpanSet {
+    type Item = &'a LineColumnSpan;
+    type IntoIter = core::slice::Iter<'a, LineColumnSpan>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.spans.iter()
+    }
+}
+
+fn start(span: &LineColumnSpan) -> (usize, usize) {
+    (span.start_line, span.start_column)
+}
+
+fn end(span: &LineColumnSpan) -> (usize, usize) {
+    (span.end_line, span.end_column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_set() {
+        let code = "struct Foo {\n    a: i32,\n    b: i32,\n}";
+        let set = [
+            LineColumnSpan::new(3, 4, 3, 10),
+            LineColumnSpan::new(2, 4, 2, 5),
+            LineColumnSpan::new(2, 5, 2, 6),
+            LineColumnSpan::new(1, 0, 1, 0),
+            LineColumnSpan::new(2, 7, 2, 10),
+            LineColumnSpan::new(3, 7, 3, 8),
+        ]
+        .into_iter()
+        .collect::<SpanSet>();
+        assert_eq!(
+            set.as_slice(),
+            [
+                LineColumnSpan::new(2, 4, 2, 6),
+                LineColumnSpan::new(2, 7, 2, 10),
+                LineColumnSpan::new(3, 4, 3, 10),
+            ]
+        );
+        assert!(set.contains_span(&LineColumnSpan::new(3, 5, 3, 9)));
+        assert!(!set.contains_span(&LineColumnSpan::new(2, 4, 2, 10)));
+        assert!(set.intersects(&LineColumnSpan::new(2, 0, 2, 5)));
+        assert!(!set.contains_point(2, 6));
+        insta::assert_snapshot!(set.debug(code), @r###"
+         --> 2:4..2:6, 2:7..2:10, 3:4..3:10
+          |
+        2 |     a: i32,
+          |     ^^
+          |        ^^^
+        3 |     b: i32,
+          |     ^^^^^^
+          |
+        "###);
+
+        let mut set = set;
+        set.insert(LineColumnSpan::new(2, 0, 3, 5));
+        assert_eq!(set.as_slice(), [LineColumnSpan::new(2, 0, 3, 10)]);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let code = "struct Foo {\n    a: i32,\n    b: i32,\n}";
+        let set = [
+            LineColumnSpan::new(1, 0, 1, 12),
+            LineColumnSpan::new(2, 4, 2, 11),
+            LineColumnSpan::new(4, 0, 4, 1),
+        ]
+        .into_iter()
+        .collect::<SpanSet>();
+        let gaps = set.gaps(code);
+        assert_eq!(gaps.as_slice(), [LineColumnSpan::new(3, 4, 3, 11)]);
+        insta::assert_snapshot!(gaps.debug(code), @r###"
+         --> 3:4..3:11
+          |
+        3 |     b: i32,
+          |     ^^^^^^^
+          |
+        "###);
+
+        let set = SpanSet::new();
+        assert_eq!(set.gaps(code).as_slice(), [LineColumnSpan::new(1, 0, 4, 1)]);
+        assert!(set.gaps("").is_empty());
+        assert!(set.gaps("\n  \n").is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::text::get_source_span_text;
+use crate::{LineColumnSpan, SourceCode, Span, SpanSet};
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+/// Aggregate statistics over a set of spans, to audit the spans of a macro output at a glance.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{find_span, LineColumnSpan, SpanStats};
+///
+/// let code = "struct Foo {\n    a: i32,\n}";
+/// let spans = [
+///     find_span("Foo", code),
+///     find_span("a: i32", code),
+///     LineColumnSpan::new(2, 4, 2, 4),
+/// ];
+/// let stats = SpanStats::compute(&spans, code);
+/// assert_eq!(stats.zero_width, 1);
+/// assert_eq!(stats.widest, Some((find_span("a: i32", code), 6)));
+/// assert_eq!(stats.per_line[&2], 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpanStats {
+    /// Number of spans.
+    pub count: usize,
+    /// Number of spans touching each line, for the lines touched by at least one span.
+    pub per_line: BTreeMap<usize, usize>,
+    /// The widest span and its width in characters, the first one in case of a tie.
+    pub widest: Option<(LineColumnSpan, usize)>,
+    /// The narrowest non-empty span and its width in characters, the first one in case of a tie.
+    pub narrowest: Option<(LineColumnSpan, usize)>,
+    /// Number of empty spans.
+    pub zero_width: usize,
+    /// Percentage of the characters of the code covered by at least one span, line breaks
+    /// included.
+    pub coverage: f64,
+}
+
+impl SpanStats {
+    /// Computes the statistics of `spans` over `code`.
+    ///
+    /// Widths count line breaks as one character. Spans out of range of the code have no width.
+    pub fn compute<S: Span>(spans: &[S], code: &(impl SourceCode + ?Sized)) -> Self {
+        let mut stats = Self {
+            count: spans.len(),
+            ..Self::default()
+        };
+        for span in spans {
+            let span = LineColumnSpan::from_span(span);
+            for line_number in span.start_line..=span.end_line {
+                *stats.per_line.entry(line_number).or_default() += 1;
+            }
+            if span.is_empty() {
+                stats.zero_width += 1;
+                continue;
+            }
+            let width = width(&span, code);
+            if stats.widest.is_none_or(|(_, widest)| width > widest) {
+                stats.widest = Some((span, width));
+            }
+            if stats
+                .narrowest
+                .is_none_or(|(_, narrowest)| width < narrowest)
+            {
+                stats.narrowest = Some((span, width));
+            }
+        }
+
+        let total = (1..=code.line_count())
+            .map(|line_number| {
+                code.line(line_number)
+                    .map_or(0, |line| line.chars().count())
+            })
+            .sum::<usize>()
+            + code.line_count().saturating_sub(1);
+        let covered = spans
+            .iter()
+            .map(LineColumnSpan::from_span)
+            .collect::<SpanSet>()
+            .iter()
+            .map(|span| width(span, code))
+            .sum::<usize>();
+        if total > 0 {
+            stats.coverage = covered as f64 * 100.0 / total as f64;
+        }
+        stats
+    }
+}
+
+fn width(span: &LineColumnSpan, code: &(impl SourceCode + ?Sized)) -> usize {
+    get_source_span_text(span, code).map_or(0, |text| text.chars().count())
+}
+
+impl fmt::Display for SpanStats {
+    /// A summary of the statistics, one per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "spans: {} ({} zero-width)", self.count, self.zero_width)?;
+        for (name, span) in [("widest", self.widest), ("narrowest", self.narrowest)] {
+            if let Some((span, width)) = span {
+                let unit = if width == 1 { "char" } else { "chars" };
+                writeln!(f, "{}: {} ({} {})", name, span.to_range(), width, unit)?;
+            }
+        }
+        writeln!(f, "coverage: {:.1}%", self.coverage)?;
+        write!(f, "spans per line:")?;
+        for (index, (line_number, count)) in self.per_line.iter().enumerate() {
+            let separator = if index == 0 { " " } else { ", " };
+            write!(f, "{}{}: {}", separator, line_number, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unindent::Unindent;
+
+    #[test]
+    fn test_span_stats() {
+        let input = r###"
+            struct Foo {
+                a: i32,
+            }
+        "###
+        .unindent();
+        let spans = [
+            LineColumnSpan::new(1, 7, 1, 10),
+            LineColumnSpan::new(1, 11, 3, 1),
+            LineColumnSpan::new(2, 4, 2, 5),
+            LineColumnSpan::new(2, 7, 2, 7),
+        ];
+        let stats = SpanStats::compute(&spans, &input);
+        insta::assert_snapshot!(stats, @r###"
+        spans: 4 (1 zero-width)
+        widest: 1:11..3:1 (15 chars)
+        narrowest: 2:4..2:5 (1 char)
+        coverage: 69.2%
+        spans per line: 1: 2, 2: 3, 3: 1
+        "###);
+
+        let stats = SpanStats::compute::<LineColumnSpan>(&[], &input);
+        assert_eq!(stats.coverage, 0.0);
+        assert_eq!(stats.widest, None);
+    }
+}
@@ -0,0 +1,524 @@
+use crate::render::{finish, TextVisitor};
+use crate::{DebugSpanOptions, Event, LineColumnSpan, SourceCode, Span, SpanExt, Visitor};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+/// Extension methods for [`syn::Result`] that render the error span when unwrapping.
+///
+/// # Example
+///
+/// ```rust,should_panic
+/// use debug_span::ResultExt;
+///
+/// let input = "struct Foo { a: i32 b: i32 }";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap_or_render(input);
+/// ```
+///
+/// Panics with:
+///
+/// ```text
+/// called `ResultExt::unwrap_or_render()` on an `Err` value: expected `,`
+///  --> 1:20..1:21
+///   |
+/// 1 | struct Foo { a: i32 b: i32 }
+///   |                     ^
+///   |
+/// ```
+pub trait ResultExt<T> {
+    /// Returns the contained `Ok` value, or panics with the error message and the rendered
+    /// snippet of every error span.
+    fn unwrap_or_render(self, code: &(impl SourceCode + ?Sized)) -> T;
+
+    /// Same as [`unwrap_or_render`](Self::unwrap_or_render), with a custom message preceding
+    /// the error.
+    fn expect_spanned(self, code: &(impl SourceCode + ?Sized), msg: &str) -> T;
+}
+
+impl<T> ResultExt<T> for syn::Result<T> {
+    #[track_caller]
+    fn unwrap_or_render(self, code: &(impl SourceCode + ?Sized)) -> T {
+        match self {
+            Ok(value) => value,
+            Err(error) => panic!(
+                "called `ResultExt::unwrap_or_render()` on an `Err` value: {}",
+                render_error(&error, code)
+            ),
+        }
+    }
+
+    #[track_caller]
+    fn expect_spanned(self, code: &(impl SourceCode + ?Sized), msg: &str) -> T {
+        match self {
+            Ok(value) => value,
+            Err(error) => panic!("{}: {}", msg, render_error(&error, code)),
+        }
+    }
+}
+
+/// Extension methods for any [`syn`] AST node, rendering its span without the `.span()` detour.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::SpannedExt;
+///
+/// let input = "struct Foo;";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+/// assert_eq!(derive_input.ident.span_range(), "1:7..1:10");
+/// let output = derive_input.ident.debug_span(input);
+/// ```
+pub trait SpannedExt {
+    /// Generate a debug representation of the node span and the source code it points to.
+    ///
+    /// see [`debug_span`](crate::debug_span) for more information.
+    fn debug_span(&self, code: &(impl SourceCode + ?Sized)) -> String;
+
+    /// Returns the node span in the format `start_line:start_column..end_line:end_column`.
+    ///
+    /// see [`Span::to_range`] for more information.
+    fn span_range(&self) -> String;
+}
+
+impl<T: Spanned + ?Sized> SpannedExt for T {
+    fn debug_span(&self, code: &(impl SourceCode + ?Sized)) -> String {
+        crate::debug_span(self.span(), code)
+    }
+
+    fn span_range(&self) -> String {
+        self.span().to_range()
+    }
+}
+
+/// Render the spans of all items in a single frame.
+///
+/// Lines shared by several items are shown once, with one marker row per item.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::debug_all;
+///
+/// let input = "struct Foo {\n    a: i32, b: i32,\n}";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+/// let fields = match derive_input.data {
+///     syn::Data::Struct(s) => s.fields,
+///     _ => panic!("expected struct"),
+/// };
+/// let output = debug_all(&fields, input);
+/// assert_eq!(
+///     output,
+///     " --> 2:4..2:10, 2:12..2:18\n  |\n2 |     a: i32, b: i32,\n  |     ^^^^^^\n  |             ^^^^^^\n  |\n"
+/// );
+/// ```
+pub fn debug_all<'a, T: Spanned + 'a>(
+    items: impl IntoIterator<Item = &'a T>,
+    code: &(impl SourceCode + ?Sized),
+) -> String {
+    let spans = items
+        .into_iter()
+        .map(|item| LineColumnSpan::from_span(&item.span()))
+        .collect::<Vec<_>>();
+    crate::internal::debug_spans_with_options(&spans, code, &DebugSpanOptions::default())
+}
+
+/// Returns the span of the smallest item or expression of `code` enclosing `span`.
+///
+/// The code is parsed as a Rust file. A node with exactly the same location as `span` doesn't
+/// enclose it, e.g. the literal expression of a literal token. Returns `None` if the code doesn't
+/// parse or no node encloses the span. Useful to show an error together with its surrounding item.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{enclosing_item_span, find_span, LineColumnSpan};
+///
+/// let code = "fn foo() {\n    let a = 1 + 2;\n}\nstruct Bar;";
+/// let span = enclosing_item_span(find_span("2", code), code);
+/// assert_eq!(span, Some(LineColumnSpan::new(2, 12, 2, 17)));
+/// let span = enclosing_item_span(find_span("let", code), code);
+/// assert_eq!(span, Some(LineColumnSpan::new(1, 0, 3, 1)));
+/// ```
+pub fn enclosing_item_span(span: impl Span, code: &str) -> Option<LineColumnSpan> {
+    let file = syn::parse_file(code).ok()?;
+    let mut finder = EnclosingFinder {
+        span: LineColumnSpan::from_span(&span),
+        enclosing: None,
+    };
+    finder.visit_file(&file);
+    finder.enclosing
+}
+
+/// Keeps the last visited node enclosing the span. Nodes are visited before their children,
+/// and siblings don't overlap, so it is the smallest one.
+struct EnclosingFinder {
+    span: LineColumnSpan,
+    enclosing: Option<LineColumnSpan>,
+}
+
+impl EnclosingFinder {
+    fn check(&mut self, node: &impl Spanned) {
+        let node = LineColumnSpan::from_span(&node.span());
+        if node.contains_span(&self.span) && node != self.span {
+            self.enclosing = Some(node);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for EnclosingFinder {
+    fn visit_item(&mut self, node: &'ast syn::Item) {
+        self.check(node);
+        visit::visit_item(self, node);
+    }
+
+    fn visit_expr(&mut self, node: &'ast syn::Expr) {
+        self.check(node);
+        visit::visit_expr(self, node);
+    }
+}
+
+/// Render a span preceded by the signature lines of its enclosing items, e.g. `impl Foo {` and
+/// `fn bar(&self) {`, with the lines in between elided.
+///
+/// The code is parsed as a Rust file, the enclosing items are the functions, impls, traits,
+/// modules, structs, enums and unions around the span. Signatures already shown as context are
+/// not repeated. Without a parsable file, the span is rendered alone.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{debug_span_with_structure, find_span, DebugSpanOptions};
+///
+/// let code = "impl Foo {\n    fn bar(&self) {\n        let a = 1;\n\n        a + 2\n    }\n}";
+/// let span = find_span("a + 2", code);
+/// let output = debug_span_with_structure(span, code, &DebugSpanOptions::default());
+/// assert_eq!(
+///     output,
+///     " --> 5:8..5:13\n  |\n1 | impl Foo {\n2 |     fn bar(&self) {\n ...\n5 |         a + 2\n  |         ^^^^^\n  |\n"
+/// );
+/// ```
+pub fn debug_span_with_structure(
+    span: impl Span,
+    code: &str,
+    options: &DebugSpanOptions,
+) -> String {
+    let span = LineColumnSpan::from_span(&span);
+    let mut finder = SignatureFinder {
+        span,
+        lines: Vec::new(),
+    };
+    if let Ok(file) = syn::parse_file(code) {
+        finder.visit_file(&file);
+    }
+    let first_line = span.start_line.saturating_sub(options.context).max(1);
+    let mut lines = finder.lines;
+    lines.retain(|line| *line < first_line);
+    lines.dedup();
+    let mut visitor = StructureVisitor {
+        inner: TextVisitor::new(options),
+        code,
+        options,
+        lines: &lines,
+        first_line,
+        done: false,
+    };
+    crate::render::visit_span(&span, code, options, &mut visitor);
+    finish(visitor.inner.output, options)
+}
+
+/// Collects the line of the keyword of every item enclosing the span, outermost first.
+struct SignatureFinder {
+    span: LineColumnSpan,
+    lines: Vec<usize>,
+}
+
+impl SignatureFinder {
+    fn check(&mut self, node: &impl Spanned, keyword: proc_macro2::Span) {
+        let node = LineColumnSpan::from_span(&node.span());
+        if node.contains_span(&self.span) && node != self.span {
+            self.lines.push(LineColumnSpan::from(keyword).start_line);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for SignatureFinder {
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.check(node, node.sig.fn_token.span);
+        visit::visit_item_fn(self, node);
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        self.check(node, node.sig.fn_token.span);
+        visit::visit_impl_item_fn(self, node);
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast syn::TraitItemFn) {
+        self.check(node, node.sig.fn_token.span);
+        visit::visit_trait_item_fn(self, node);
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        self.check(node, node.impl_token.span);
+        visit::visit_item_impl(self, node);
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast syn::ItemTrait) {
+        self.check(node, node.trait_token.span);
+        visit::visit_item_trait(self, node);
+    }
+
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        self.check(node, node.mod_token.span);
+        visit::visit_item_mod(self, node);
+    }
+
+    fn visit_item_struct(&mut self, node: &'ast syn::ItemStruct) {
+        self.check(node, node.struct_token.span);
+        visit::visit_item_struct(self, node);
+    }
+
+    fn visit_item_enum(&mut self, node: &'ast syn::ItemEnum) {
+        self.check(node, node.enum_token.span);
+        visit::visit_item_enum(self, node);
+    }
+
+    fn visit_item_union(&mut self, node: &'ast syn::ItemUnion) {
+        self.check(node, node.union_token.span);
+        visit::visit_item_union(self, node);
+    }
+}
+
+/// Inserts the signature lines after the top gutter line of the frame, separated by ellipses.
+struct StructureVisitor<'a> {
+    inner: TextVisitor<'a>,
+    code: &'a str,
+    options: &'a DebugSpanOptions,
+    lines: &'a [usize],
+    /// The first line of the rendering of the span, context included.
+    first_line: usize,
+    done: bool,
+}
+
+impl Visitor for StructureVisitor<'_> {
+    fn visit(&mut self, event: Event<'_>) {
+        if let Event::BeginSnippet {
+            path,
+            ranges,
+            gutter_width,
+            tag_width,
+        } = event
+        {
+            let signature_tag_width =
+                crate::render::tag_width(self.code, self.lines.iter().copied(), self.options);
+            self.inner.visit(Event::BeginSnippet {
+                path,
+                ranges,
+                gutter_width,
+                tag_width: tag_width.max(signature_tag_width),
+            });
+            return;
+        }
+        self.inner.visit(event);
+        if event != Event::GutterLine || self.done {
+            return;
+        }
+        self.done = true;
+        let mut previous = None;
+        for &line_number in self.lines.iter().chain([&self.first_line]) {
+            if previous.is_some_and(|previous| previous + 1 < line_number) {
+                self.inner.visit(Event::Ellipsis);
+            }
+            if line_number != self.first_line {
+                let inner = &mut self.inner;
+                crate::render::visit_code_line(
+                    line_number,
+                    self.code,
+                    self.options,
+                    None,
+                    None,
+                    inner,
+                );
+            }
+            previous = Some(line_number);
+        }
+    }
+}
+
+fn render_error(error: &syn::Error, code: &(impl SourceCode + ?Sized)) -> String {
+    error
+        .clone()
+        .into_iter()
+        .map(|error| format!("{}\n{}", error, crate::debug_span(error.span(), code)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unwrap_or_render_ok() {
+        let input = "struct Foo;";
+        let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap_or_render(input);
+        assert_eq!(derive_input.ident, "Foo");
+    }
+
+    #[test]
+    fn test_render_error() {
+        let input = "struct Foo { a: i32 b: i32 }";
+        let error = match syn::parse_str::<syn::DeriveInput>(input) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        let mut combined = error.clone();
+        combined.combine(syn::Error::new(error.span(), "another error"));
+        insta::assert_snapshot!(render_error(&combined, input), @r###"
+        expected `,`
+         --> 1:20..1:21
+          |
+        1 | struct Foo { a: i32 b: i32 }
+          |                     ^
+          |
+
+        another error
+         --> 1:20..1:21
+          |
+        1 | struct Foo { a: i32 b: i32 }
+          |                     ^
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_spanned_ext() {
+        let input = "struct Foo { a: i32 }";
+        let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+        let fields = match derive_input.data {
+            syn::Data::Struct(s) => s.fields,
+            _ => panic!("expected struct"),
+        };
+        assert_eq!(fields.span_range(), "1:11..1:21");
+        insta::assert_snapshot!(fields.debug_span(input), @r###"
+         --> 1:11..1:21
+          |
+        1 | struct Foo { a: i32 }
+          |            ^^^^^^^^^^
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_debug_all() {
+        let input = "struct Foo {\n    a: i32, b: i32,\n    c: i32,\n}";
+        let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+        let fields = match derive_input.data {
+            syn::Data::Struct(s) => s.fields,
+            _ => panic!("expected struct"),
+        };
+        insta::assert_snapshot!(debug_all(&fields, input), @r###"
+         --> 2:4..2:10, 2:12..2:18, 3:4..3:10
+          |
+        2 |     a: i32, b: i32,
+          |     ^^^^^^
+          |             ^^^^^^
+        3 |     c: i32,
+          |     ^^^^^^
+          |
+        "###);
+    }
+
+    #[test]
+    fn test_enclosing_item_span() {
+        let code = "fn foo() {\n    let a = bar(1, 2);\n}\n\nstruct Baz;";
+        let span = enclosing_item_span(crate::find_span("1", code), code).unwrap();
+        insta::assert_snapshot!(crate::debug_span(span, code), @r###"
+         --> 2:12..2:21
+          |
+        2 |     let a = bar(1, 2);
+          |             ^^^^^^^^^
+          |
+        "###);
+        let span = enclosing_item_span(crate::find_span("Baz", code), code);
+        assert_eq!(span, Some(LineColumnSpan::new(5, 0, 5, 11)));
+        assert_eq!(
+            enclosing_item_span(LineColumnSpan::new(4, 0, 4, 0), code),
+            None
+        );
+        assert_eq!(
+            enclosing_item_span(LineColumnSpan::new(1, 0, 1, 1), "fn"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_debug_span_with_structure() {
+        let code = unindent::unindent(
+            "
+            mod foo {
+                #[derive(Debug)]
+                struct Bar;
+
+                impl Bar {
+                    fn baz(&self) -> i32 {
+                        let a = 1;
+                        a + 2
+                    }
+                }
+            }",
+        );
+        let span = crate::find_span("a + 2", &code);
+        let options = DebugSpanOptions::default();
+        insta::assert_snapshot!(debug_span_with_structure(span, &code, &options), @r###"
+         --> 8:12..8:17
+          |
+        1 | mod foo {
+         ...
+        5 |     impl Bar {
+        6 |         fn baz(&self) -> i32 {
+         ...
+        8 |             a + 2
+          |             ^^^^^
+          |
+        "###);
+        let options = DebugSpanOptions {
+            context: 2,
+            ..Default::default()
+        };
+        insta::assert_snapshot!(debug_span_with_structure(span, &code, &options), @r###"
+          --> 8:12..8:17
+           |
+         1 | mod foo {
+          ...
+         5 |     impl Bar {
+         6 |         fn baz(&self) -> i32 {
+         7 |             let a = 1;
+         8 |             a + 2
+           |             ^^^^^
+         9 |         }
+        10 |     }
+           |
+        "###);
+        let span = crate::find_span("Bar", &code);
+        insta::assert_snapshot!(debug_span_with_structure(span, &code, &options), @r###"
+         --> 3:11..3:14
+          |
+        1 | mod foo {
+        2 |     #[derive(Debug)]
+        3 |     struct Bar;
+          |            ^^^
+        4 |
+        5 |     impl Bar {
+          |
+        "###);
+    }
+
+    #[test]
+    #[should_panic(expected = "parse Foo: expected `,`\n --> 1:20..1:21")]
+    fn test_expect_spanned() {
+        let input = "struct Foo { a: i32 b: i32 }";
+        let _: syn::DeriveInput = syn::parse_str(input).expect_spanned(input, "parse Foo");
+    }
+}
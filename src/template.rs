@@ -0,0 +1,230 @@
+use crate::{DebugSpanOptions, LineColumnSpan, Renderer, SourceCode, Span, TextRenderer};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Write;
+use core::str::FromStr;
+
+/// A [`Renderer`] customizing the text around the rendering with a format string.
+///
+/// The placeholders are:
+///
+/// - `{path}`: the path of the code, `<unknown>` if it has none.
+/// - `{start_line}`, `{start_col}`, `{end_line}` and `{end_col}`: the coordinates of the first
+///   span, with the displayed line numbers. Columns are 0-indexed.
+/// - `{range}`: the ranges of all spans, like in the header of the rendering.
+/// - `{message}`: the [label](DebugSpanOptions::label), empty if there is none.
+/// - `{snippet}`: the plain text rendering of the spans, without the label.
+///
+/// `{{` and `}}` are literal braces.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{DebugSpanOptions, LineColumnSpan, NamedSource, Renderer, Template};
+///
+/// let template = "{path}:{start_line}:{start_col}: {message}\n{snippet}";
+/// let template = template.parse::<Template>().unwrap();
+/// let code = NamedSource::new("src/lib.rs", "struct Foo;");
+/// let options = DebugSpanOptions {
+///     label: Some("expected enum".to_string()),
+///     ..Default::default()
+/// };
+/// let output = template.render(&[LineColumnSpan::new(1, 0, 1, 6)], &code, &options);
+/// assert!(output.starts_with("src/lib.rs:1:0: expected enum\n --> src/lib.rs:1:0..1:6\n"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Part {
+    Text(String),
+    Path,
+    StartLine,
+    StartColumn,
+    EndLine,
+    EndColumn,
+    Range,
+    Message,
+    Snippet,
+}
+
+impl Template {
+    /// Parse a template, see [`Template`] for the placeholders.
+    pub fn parse(template: &str) -> Result<Self, TemplateError> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c);
+                    }
+                    if !closed {
+                        return Err(TemplateError::Unclosed(name));
+                    }
+                    let part = match name.as_str() {
+                        "path" => Part::Path,
+                        "start_line" => Part::StartLine,
+                        "start_col" | "start_column" => Part::StartColumn,
+                        "end_line" => Part::EndLine,
+                        "end_col" | "end_column" => Part::EndColumn,
+                        "range" => Part::Range,
+                        "message" => Part::Message,
+                        "snippet" => Part::Snippet,
+                        _ => return Err(TemplateError::Unknown(name)),
+                    };
+                    if !text.is_empty() {
+                        parts.push(Part::Text(core::mem::take(&mut text)));
+                    }
+                    parts.push(part);
+                }
+                '}' => return Err(TemplateError::Unmatched),
+                _ => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Self { parts })
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        Self::parse(template)
+    }
+}
+
+impl Renderer for Template {
+    fn render(
+        &self,
+        spans: &[LineColumnSpan],
+        code: &dyn SourceCode,
+        options: &DebugSpanOptions,
+    ) -> String {
+        let first = spans.first().copied().unwrap_or_default();
+        let (path, start_line) = code.line_origin(first.start_line());
+        let end_line = code.line_origin(first.end_line()).1;
+        let mut output = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => output.push_str(text),
+                Part::Path => output.push_str(path.unwrap_or("<unknown>")),
+                Part::StartLine => write!(output, "{}", start_line).unwrap(),
+                Part::StartColumn => write!(output, "{}", first.start_column()).unwrap(),
+                Part::EndLine => write!(output, "{}", end_line).unwrap(),
+                Part::EndColumn => write!(output, "{}", first.end_column()).unwrap(),
+                Part::Range => {
+                    let ranges = spans
+                        .iter()
+                        .map(|span| crate::internal::display_range(span, code))
+                        .collect::<Vec<_>>();
+                    output.push_str(&ranges.join(", "));
+                }
+                Part::Message => output.push_str(options.label.as_deref().unwrap_or("")),
+                Part::Snippet => {
+                    let options = DebugSpanOptions {
+                        label: None,
+                        ..options.clone()
+                    };
+                    output.push_str(&TextRenderer.render(spans, code, &options));
+                }
+            }
+        }
+        output
+    }
+}
+
+/// The error returned when parsing a malformed [`Template`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TemplateError {
+    /// A placeholder with an unknown name.
+    Unknown(String),
+    /// A `{` without its closing `}`.
+    Unclosed(String),
+    /// A `}` without its opening `{`.
+    Unmatched,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Unknown(name) => {
+                write!(f, "unknown placeholder `{{{}}}` in template", name)
+            }
+            TemplateError::Unclosed(name) => {
+                write!(f, "unclosed placeholder `{{{}` in template", name)
+            }
+            TemplateError::Unmatched => {
+                write!(
+                    f,
+                    "unmatched `}}` in template, use `}}}}` for a literal brace"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TemplateError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NamedSource;
+
+    #[test]
+    fn test_template() {
+        let code = NamedSource::new("src/lib.rs", "struct Foo {\n    a: i32,\n}");
+        let options = DebugSpanOptions {
+            label: Some("expected ident".to_string()),
+            ..Default::default()
+        };
+        let spans = [LineColumnSpan::new(1, 7, 1, 10)];
+        let template = Template::parse("{path}:{start_line}:{start_col}: {message}\n{snippet}");
+        let output = template.unwrap().render(&spans, &code, &options);
+        insta::assert_snapshot!(output, @r###"
+        src/lib.rs:1:7: expected ident
+         --> src/lib.rs:1:7..1:10
+          |
+        1 | struct Foo {
+          |        ^^^
+          |
+        "###);
+
+        let spans = [
+            LineColumnSpan::new(1, 7, 1, 10),
+            LineColumnSpan::new(2, 4, 2, 5),
+        ];
+        let template = "{{{range}}} {end_line}:{end_column}".parse::<Template>();
+        let output = template.unwrap().render(&spans, &code, &options);
+        assert_eq!(output, "{1:7..1:10, 2:4..2:5} 1:10");
+
+        let error = Template::parse("{file}").unwrap_err();
+        insta::assert_snapshot!(error, @"unknown placeholder `{file}` in template");
+        let error = Template::parse("a}b").unwrap_err();
+        insta::assert_snapshot!(error, @"unmatched `}` in template, use `}}` for a literal brace");
+        let error = Template::parse("{path").unwrap_err();
+        insta::assert_snapshot!(error, @"unclosed placeholder `{path` in template");
+    }
+}
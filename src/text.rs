@@ -0,0 +1,262 @@
+use crate::{LineColumnSpan, SourceCode, Span};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Returns the byte offset of a line/column position in `code`.
+///
+/// Lines are 1-indexed and columns are 0-indexed and counted in chars. The position right after
+/// the last char of a line is valid and points to the line terminator.
+pub(crate) fn byte_offset(code: &str, line: usize, column: usize) -> Option<usize> {
+    let line_start = line_start(code, line)?;
+    let line_text = code[line_start..].split('\n').next().unwrap_or("");
+    let offset = match line_text.char_indices().nth(column) {
+        Some((offset, _)) => offset,
+        None if column == line_text.chars().count() => line_text.len(),
+        None => return None,
+    };
+    Some(line_start + offset)
+}
+
+/// Returns the line/column position of a byte offset in `code`.
+///
+/// `offset` must be on a char boundary and at most `code.len()`.
+pub(crate) fn line_column_at(code: &str, offset: usize) -> (usize, usize) {
+    let before = &code[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |offset| offset + 1);
+    let column = before[line_start..].chars().count();
+    (line, column)
+}
+
+fn line_start(code: &str, line: usize) -> Option<usize> {
+    if line == 0 {
+        return None;
+    }
+    if line == 1 {
+        return Some(0);
+    }
+    code.match_indices('\n')
+        .nth(line - 2)
+        .map(|(offset, _)| offset + 1)
+}
+
+/// Returns the exact source text covered by the span.
+///
+/// Multi-line spans include the line terminators in between. Columns are counted in chars, so
+/// multi-byte characters are handled correctly.
+///
+/// # Panics
+///
+/// Panics if the span points outside of `code`.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{span_text, LineColumnSpan};
+///
+/// let code = "struct Foo {\n    a: i32,\n}";
+/// assert_eq!(span_text(LineColumnSpan::new(1, 7, 1, 10), code), "Foo");
+/// assert_eq!(span_text(LineColumnSpan::new(1, 11, 3, 1), code), "{\n    a: i32,\n}");
+/// ```
+#[track_caller]
+pub fn span_text(span: impl Span, code: &str) -> &str {
+    match get_span_text(&span, code) {
+        Some(text) => text,
+        None => panic!("span {} is out of range of the code", span.to_range()),
+    }
+}
+
+/// Returns the source text covered by the span, or `None` if it points outside of `code`.
+pub(crate) fn get_span_text<'a>(span: &(impl Span + ?Sized), code: &'a str) -> Option<&'a str> {
+    code.get(get_byte_range(span, code)?)
+}
+
+/// Returns the source text covered by the span, looking up every line of `source`.
+///
+/// Multi-line spans are joined with `\n`.
+pub(crate) fn get_source_span_text(
+    span: &(impl Span + ?Sized),
+    source: &(impl SourceCode + ?Sized),
+) -> Option<String> {
+    let mut lines = Vec::new();
+    for line_number in span.start_line()..=span.end_line() {
+        let line = source.line(line_number)?;
+        let start = if line_number == span.start_line() {
+            span.start_column()
+        } else {
+            0
+        };
+        let end = if line_number == span.end_line() {
+            span.end_column()
+        } else {
+            line.chars().count()
+        };
+        if start > end || end > line.chars().count() {
+            return None;
+        }
+        lines.push(
+            line.chars()
+                .skip(start)
+                .take(end - start)
+                .collect::<String>(),
+        );
+    }
+    Some(lines.join("\n"))
+}
+
+/// Converts the line/column span into a range of byte offsets in `code`.
+///
+/// It can be used to feed debug-span coordinates into byte based APIs, like string slicing.
+///
+/// # Panics
+///
+/// Panics if the span points outside of `code`.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{to_byte_range, LineColumnSpan};
+///
+/// let code = "struct Foo {\n    a: i32,\n}";
+/// assert_eq!(to_byte_range(LineColumnSpan::new(1, 7, 1, 10), code), 7..10);
+/// assert_eq!(to_byte_range(LineColumnSpan::new(2, 4, 2, 10), code), 17..23);
+/// ```
+#[track_caller]
+pub fn to_byte_range(span: impl Span, code: &str) -> Range<usize> {
+    match get_byte_range(&span, code) {
+        Some(range) => range,
+        None => panic!("span {} is out of range of the code", span.to_range()),
+    }
+}
+
+/// Returns the span covering the first occurrence of `needle` in `code`.
+///
+/// It is useful for building expected spans declaratively instead of hard-coding line and column
+/// numbers.
+///
+/// # Panics
+///
+/// Panics if `needle` is not found in `code`.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{assert_span_eq, find_span};
+///
+/// let input = "struct Foo;";
+/// let derive_input: syn::DeriveInput = syn::parse_str(input).unwrap();
+/// assert_span_eq!(derive_input.ident.span(), find_span("Foo", input), input);
+/// ```
+#[track_caller]
+pub fn find_span(needle: &str, code: &str) -> LineColumnSpan {
+    find_nth_span(needle, code, 0)
+}
+
+/// Returns the span covering the `n`th (0-indexed) occurrence of `needle` in `code`.
+///
+/// see [`find_span`] for more information.
+///
+/// # Panics
+///
+/// Panics if `code` contains less than `n + 1` occurrences of `needle`.
+#[track_caller]
+pub fn find_nth_span(needle: &str, code: &str, n: usize) -> LineColumnSpan {
+    let start = match code.match_indices(needle).nth(n) {
+        Some((start, _)) => start,
+        None => panic!("occurrence {} of {:?} not found in the code", n, needle),
+    };
+    let (start_line, start_column) = line_column_at(code, start);
+    let (end_line, end_column) = line_column_at(code, start + needle.len());
+    LineColumnSpan::new(start_line, start_column, end_line, end_column)
+}
+
+/// Returns the byte range of the span, or `None` if it points outside of `code`.
+pub(crate) fn get_byte_range(span: &(impl Span + ?Sized), code: &str) -> Option<Range<usize>> {
+    let start = byte_offset(code, span.start_line(), span.start_column())?;
+    let end = byte_offset(code, span.end_line(), span.end_column())?;
+    (start <= end).then_some(start..end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+
+    #[test]
+    fn test_byte_offset() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        assert_eq!(byte_offset(code, 1, 0), Some(0));
+        assert_eq!(byte_offset(code, 1, 12), Some(12));
+        assert_eq!(byte_offset(code, 2, 4), Some(17));
+        assert_eq!(byte_offset(code, 3, 1), Some(26));
+        assert_eq!(byte_offset(code, 1, 13), None);
+        assert_eq!(byte_offset(code, 4, 0), None);
+        assert_eq!(byte_offset(code, 0, 0), None);
+    }
+
+    #[test]
+    fn test_span_text() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        let span = LineColumnSpan::new(1, 7, 1, 10);
+        assert_eq!(get_span_text(&span, code), Some("Foo"));
+        let span = LineColumnSpan::new(1, 11, 3, 1);
+        assert_eq!(get_span_text(&span, code), Some("{\n    a: i32,\n}"));
+        let span = LineColumnSpan::new(1, 7, 5, 1);
+        assert_eq!(get_span_text(&span, code), None);
+    }
+
+    #[test]
+    fn test_byte_range() {
+        let code = "let s = \"héllo\";\nlet t = s;";
+        let span = LineColumnSpan::new(1, 8, 1, 15);
+        assert_eq!(get_byte_range(&span, code), Some(8..16));
+        let span = LineColumnSpan::new(1, 15, 2, 3);
+        assert_eq!(get_byte_range(&span, code), Some(16..21));
+        let span = LineColumnSpan::new(2, 3, 1, 15);
+        assert_eq!(get_byte_range(&span, code), None);
+    }
+
+    #[test]
+    fn test_line_column_at() {
+        let code = "struct Foo {\n    é: i32,\n}";
+        assert_eq!(line_column_at(code, 0), (1, 0));
+        assert_eq!(line_column_at(code, 12), (1, 12));
+        assert_eq!(line_column_at(code, 13), (2, 0));
+        assert_eq!(line_column_at(code, 19), (2, 5));
+        assert_eq!(line_column_at(code, code.len()), (3, 1));
+    }
+
+    #[test]
+    fn test_find_span() {
+        let code = "struct Foo {\n    a: i32,\n    b: i32,\n}";
+        assert_eq!(find_span("Foo", code), LineColumnSpan::new(1, 7, 1, 10));
+        assert_eq!(
+            find_nth_span("i32", code, 1),
+            LineColumnSpan::new(3, 7, 3, 10)
+        );
+        assert_eq!(
+            find_span("{\n    a", code),
+            LineColumnSpan::new(1, 11, 2, 5)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "occurrence 2 of \"i32\" not found in the code")]
+    fn test_find_nth_span_not_found() {
+        find_nth_span("i32", "a: i32, b: i32", 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "span 1:7..5:1 is out of range of the code")]
+    fn test_span_text_out_of_range() {
+        span_text(LineColumnSpan::new(1, 7, 5, 1), "struct Foo;");
+    }
+
+    #[test]
+    fn test_span_text_multi_byte() {
+        let code = "let s = \"héllo\";";
+        let span = LineColumnSpan::new(1, 8, 1, 15);
+        assert_eq!(get_span_text(&span, code), Some("\"héllo\""));
+    }
+}
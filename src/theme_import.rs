@@ -0,0 +1,170 @@
+use crate::{Color, Palette, Theme};
+#[cfg(feature = "miette")]
+use alloc::vec::Vec;
+
+impl Theme {
+    /// The colors of a miette theme, behind the `miette` feature.
+    ///
+    /// The gutter uses the color of the line numbers, or of the links when line numbers are
+    /// not colored, and the labels cycle through the highlight colors. Returns `None` for a
+    /// theme without colors, like `GraphicalTheme::none()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use debug_span::{Color, Theme};
+    /// use miette::GraphicalTheme;
+    ///
+    /// let Some(Theme::Custom(palette)) = Theme::from_miette(&GraphicalTheme::unicode()) else {
+    ///     panic!("expected a custom theme");
+    /// };
+    /// assert_eq!(palette.gutter, Color::from_xterm(6));
+    /// assert_eq!(palette.label(0), Color::from_xterm(5));
+    /// assert_eq!(Theme::from_miette(&GraphicalTheme::none()), None);
+    /// ```
+    #[cfg(feature = "miette")]
+    pub fn from_miette(theme: &miette::GraphicalTheme) -> Option<Theme> {
+        let styles = &theme.styles;
+        let gutter = miette_color(&styles.linum).or_else(|| miette_color(&styles.link));
+        let labels = styles
+            .highlights
+            .iter()
+            .filter_map(miette_color)
+            .collect::<Vec<_>>();
+        let labels = match labels.is_empty() {
+            true => miette_color(&styles.error).into_iter().collect(),
+            false => labels,
+        };
+        custom_theme(gutter, &labels)
+    }
+
+    /// The colors of ariadne reports, behind the `ariadne` feature.
+    ///
+    /// The gutter uses the margin color of ariadne, and the labels its error, warning, advice
+    /// and note colors. Returns `None` if colors are disabled in `config`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use debug_span::{Color, Theme};
+    ///
+    /// let Some(Theme::Custom(palette)) = Theme::from_ariadne(&ariadne::Config::default()) else {
+    ///     panic!("expected a custom theme");
+    /// };
+    /// assert_eq!(palette.gutter, Color::from_xterm(246));
+    /// let config = ariadne::Config::default().with_color(false);
+    /// assert_eq!(Theme::from_ariadne(&config), None);
+    /// ```
+    #[cfg(feature = "ariadne")]
+    pub fn from_ariadne(config: &ariadne::Config) -> Option<Theme> {
+        if *config != config.with_color(true) {
+            return None;
+        }
+        // the colors are fixed by ariadne, see `Config::margin_color` and the others
+        let labels = [
+            Color::from_xterm(1),
+            Color::from_xterm(3),
+            Color::from_xterm(147),
+            Color::from_xterm(115),
+        ];
+        custom_theme(Some(Color::from_xterm(246)), &labels)
+    }
+}
+
+/// A custom theme with the given colors, falling back to the default theme for the missing
+/// ones. Returns `None` without any color.
+fn custom_theme(gutter: Option<Color>, labels: &[Color]) -> Option<Theme> {
+    if gutter.is_none() && labels.is_empty() {
+        return None;
+    }
+    let default = Theme::Default.palette();
+    let labels = match labels.is_empty() {
+        true => default.labels,
+        false => core::array::from_fn(|index| labels[index % labels.len()]),
+    };
+    Some(Theme::Custom(Palette {
+        gutter: gutter.unwrap_or(default.gutter),
+        line_numbers: gutter.unwrap_or(default.line_numbers),
+        labels,
+    }))
+}
+
+/// The foreground color of a miette style.
+///
+/// The style doesn't expose its colors, they are read back from the escapes it paints.
+#[cfg(feature = "miette")]
+fn miette_color(style: &owo_colors::Style) -> Option<Color> {
+    let painted = alloc::format!("{}", style.style(""));
+    sgr_foreground(&painted)
+}
+
+/// Parses the foreground color of the SGR escapes of `text`, e.g. `\x1b[1m\x1b[35m`.
+#[cfg(feature = "miette")]
+fn sgr_foreground(text: &str) -> Option<Color> {
+    let mut color = None;
+    for escape in text
+        .split('\x1b')
+        .filter_map(|escape| escape.strip_prefix('['))
+    {
+        let Some(params) = escape.split('m').next() else {
+            continue;
+        };
+        let params = params
+            .split(';')
+            .map(|param| param.parse::<u8>().ok())
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default();
+        let mut params = params.into_iter();
+        while let Some(param) = params.next() {
+            color = match param {
+                30..=37 => Some(Color::from_xterm(param - 30)),
+                90..=97 => Some(Color::from_xterm(param - 90 + 8)),
+                38 => match params.next() {
+                    Some(5) => params.next().map(Color::from_xterm),
+                    Some(2) => match (params.next(), params.next(), params.next()) {
+                        (Some(r), Some(g), Some(b)) => Some(Color::from_rgb((r, g, b))),
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                _ => continue,
+            };
+        }
+    }
+    color
+}
+
+#[cfg(all(test, feature = "miette"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sgr_foreground() {
+        assert_eq!(
+            sgr_foreground("\x1b[1m\x1b[35m"),
+            Some(Color::from_xterm(5))
+        );
+        assert_eq!(sgr_foreground("\x1b[91m"), Some(Color::from_xterm(9)));
+        assert_eq!(
+            sgr_foreground("\x1b[38;5;147m"),
+            Some(Color::from_xterm(147))
+        );
+        assert_eq!(
+            sgr_foreground("\x1b[38;2;246;87;248m"),
+            Some(Color::from_rgb((246, 87, 248)))
+        );
+        assert_eq!(sgr_foreground("\x1b[2m"), None);
+        assert_eq!(sgr_foreground(""), None);
+
+        let theme = miette::GraphicalTheme {
+            characters: miette::ThemeCharacters::unicode(),
+            styles: miette::ThemeStyles::rgb(),
+        };
+        let Some(Theme::Custom(palette)) = Theme::from_miette(&theme) else {
+            panic!("expected a custom theme");
+        };
+        assert_eq!(palette.gutter, Color::from_rgb((92, 157, 255)));
+        assert_eq!(palette.label(0), Color::from_rgb((246, 87, 248)));
+        assert_eq!(palette.label(3), Color::from_rgb((246, 87, 248)));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{LineColumnSpan, Span, SpanExt};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use proc_macro2::{token_stream, Delimiter, TokenStream, TokenTree};
+
+/// Extension methods for [`proc_macro2::TokenStream`].
+pub trait TokenStreamExt {
+    /// Flattens the token tree and yields the text and the owned span of every token.
+    ///
+    /// Groups yield their open and close delimiters as separate tokens around their content.
+    /// Invisible delimiters yield nothing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use debug_span::{LineColumnSpan, TokenStreamExt};
+    ///
+    /// let tokens: proc_macro2::TokenStream = "foo(a)".parse().unwrap();
+    /// let spans = tokens.iter_spans().collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     spans,
+    ///     vec![
+    ///         ("foo".to_string(), LineColumnSpan::new(1, 0, 1, 3)),
+    ///         ("(".to_string(), LineColumnSpan::new(1, 3, 1, 4)),
+    ///         ("a".to_string(), LineColumnSpan::new(1, 4, 1, 5)),
+    ///         (")".to_string(), LineColumnSpan::new(1, 5, 1, 6)),
+    ///     ]
+    /// );
+    /// ```
+    fn iter_spans(&self) -> TokenSpans;
+}
+
+impl TokenStreamExt for TokenStream {
+    fn iter_spans(&self) -> TokenSpans {
+        TokenSpans {
+            stack: vec![(self.clone().into_iter(), None)],
+        }
+    }
+}
+
+/// An iterator over the tokens of a [`TokenStream`] and their spans, created by
+/// [`TokenStreamExt::iter_spans`].
+#[derive(Debug, Clone)]
+pub struct TokenSpans {
+    stack: Vec<(token_stream::IntoIter, Option<(String, LineColumnSpan)>)>,
+}
+
+impl Iterator for TokenSpans {
+    type Item = (String, LineColumnSpan);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (tokens, _) = self.stack.last_mut()?;
+            match tokens.next() {
+                Some(TokenTree::Group(group)) => {
+                    let delimiters = match group.delimiter() {
+                        Delimiter::Parenthesis => Some(("(", ")")),
+                        Delimiter::Brace => Some(("{", "}")),
+                        Delimiter::Bracket => Some(("[", "]")),
+                        Delimiter::None => None,
+                    };
+                    let close = delimiters.map(|(_, close)| {
+                        let span = LineColumnSpan::from_span(&group.span_close());
+                        (close.to_string(), span)
+                    });
+                    self.stack.push((group.stream().into_iter(), close));
+                    if let Some((open, _)) = delimiters {
+                        let span = LineColumnSpan::from_span(&group.span_open());
+                        return Some((open.to_string(), span));
+                    }
+                }
+                Some(token) => {
+                    let span = LineColumnSpan::from_span(&token.span());
+                    return Some((token.to_string(), span));
+                }
+                None => {
+                    let (_, close) = self.stack.pop()?;
+                    if close.is_some() {
+                        return close;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Snap a span to the boundaries of the Rust tokens it touches.
+///
+/// The code is lexed with proc-macro2. A span starting or ending inside a token is expanded to
+/// cover the whole token, whitespace and comments at the edges are dropped. An empty span snaps
+/// to the token containing it. Returns `None` if the code can't be lexed or the span doesn't
+/// touch any token.
+///
+/// Useful to turn byte ranges reported by external tools into sensible spans.
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{snap_to_tokens, LineColumnSpan};
+///
+/// let code = "struct Foo {\n    a: i32,\n}";
+/// let span = snap_to_tokens(LineColumnSpan::new(1, 9, 2, 1), code);
+/// assert_eq!(span, Some(LineColumnSpan::new(1, 7, 1, 12)));
+/// ```
+pub fn snap_to_tokens(span: impl Span, code: &str) -> Option<LineColumnSpan> {
+    let tokens = code.parse::<TokenStream>().ok()?;
+    let mut touched = tokens.iter_spans().map(|(_, token)| token).filter(|token| {
+        if span.is_empty() {
+            token.contains_point(span.start_line(), span.start_column())
+        } else {
+            token.intersects(&span)
+        }
+    });
+    let first = touched.next()?;
+    Some(touched.fold(first, |joined, token| joined.join(&token)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unindent::Unindent;
+
+    #[test]
+    fn test_iter_spans() {
+        let input = r###"
+            struct Foo {
+                a: Vec<i32>,
+            }
+        "###
+        .unindent();
+        let tokens: TokenStream = input.parse().unwrap();
+        let output = tokens
+            .iter_spans()
+            .map(|(text, span)| format!("{:>5} {}", text, crate::Span::to_range(&span)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(output, @r###"
+        struct 1:0..1:6
+          Foo 1:7..1:10
+            { 1:11..1:12
+            a 2:4..2:5
+            : 2:5..2:6
+          Vec 2:7..2:10
+            < 2:10..2:11
+          i32 2:11..2:14
+            > 2:14..2:15
+            , 2:15..2:16
+            } 3:0..3:1
+        "###);
+    }
+
+    #[test]
+    fn test_iter_spans_empty() {
+        let tokens = TokenStream::new();
+        assert_eq!(tokens.iter_spans().count(), 0);
+    }
+
+    #[test]
+    fn test_snap_to_tokens() {
+        let code = "struct Foo {\n    a: Vec<i32>, // comment\n}";
+        let span = LineColumnSpan::new(2, 2, 2, 12);
+        assert_eq!(
+            snap_to_tokens(span, code),
+            Some(LineColumnSpan::new(2, 4, 2, 14))
+        );
+        let span = LineColumnSpan::new(2, 16, 2, 20);
+        assert_eq!(snap_to_tokens(span, code), None);
+        let span = LineColumnSpan::new(1, 8, 1, 8);
+        assert_eq!(
+            snap_to_tokens(span, code),
+            Some(LineColumnSpan::new(1, 7, 1, 10))
+        );
+        assert_eq!(snap_to_tokens(span, "\"unterminated"), None);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::{
+    audit_respanning, DebugSpanOptions, LineColumnSpan, NamedSource, SourceCode, SourceText,
+    SpanExt, TokenStreamExt,
+};
+use proc_macro2::TokenStream;
+
+/// The environment variable enabling the traces of `#[debug_spans]`.
+const TRACE_VAR: &str = "DEBUG_SPANS";
+
+/// The input of a proc-macro entry point instrumented by `#[debug_spans]`, captured when tracing
+/// is enabled for it.
+pub struct ProcMacroTrace {
+    name: &'static str,
+    input: TokenStream,
+}
+
+impl ProcMacroTrace {
+    /// Starts tracing the entry point `name` if `DEBUG_SPANS` is `1` or a comma separated list
+    /// containing `name`.
+    pub fn start<T: Clone + Into<TokenStream>>(name: &'static str, input: &T) -> Option<Self> {
+        let value = std::env::var(TRACE_VAR).ok()?;
+        let enabled = value == "1" || value.split(',').any(|entry| entry.trim() == name);
+        enabled.then(|| Self {
+            name,
+            input: input.clone().into(),
+        })
+    }
+
+    /// Prints the trace of the entry point with its `output`, like `print_span!`.
+    pub fn finish<T: Clone + Into<TokenStream>>(self, output: &T) {
+        let output = output.clone().into();
+        let rendering = match input_source(&self.input) {
+            Some(code) => render_trace(self.name, &self.input, &output, &code),
+            None => render_ranges(self.name, &self.input, &output),
+        };
+        crate::internal::eprint(&rendering);
+    }
+}
+
+/// Reads the source file of the input, if the compiler tells where it is.
+fn input_source(input: &TokenStream) -> Option<NamedSource<SourceText>> {
+    let span = input.clone().into_iter().next()?.span();
+    let path = span.local_file()?;
+    let code = std::fs::read(&path).ok()?;
+    Some(NamedSource::new(
+        span.file(),
+        SourceText::from_bytes_lossy(&code),
+    ))
+}
+
+/// Renders the input, the spans of the input tokens and the spans of the output tokens.
+///
+/// Tokens from `macro_rules!` expansions or other files may not fit the source file of the
+/// first token, their ranges are listed instead.
+fn render_trace(
+    name: &str,
+    input: &TokenStream,
+    output: &TokenStream,
+    code: &(impl SourceCode + ?Sized),
+) -> String {
+    let options = DebugSpanOptions {
+        deterministic: true,
+        ..Default::default()
+    };
+    let (tokens, outside) = input
+        .iter_spans()
+        .partition::<Vec<_>, _>(|(_, span)| crate::error::check_span(span, code).is_ok());
+    if tokens.is_empty() {
+        return render_ranges(name, input, output);
+    }
+    let mut spans = tokens.into_iter().map(|(_, span)| span).collect::<Vec<_>>();
+    let input_span = spans
+        .iter()
+        .copied()
+        .reduce(|joined, span| joined.join(&span))
+        .unwrap_or_else(LineColumnSpan::default);
+    crate::dedup_spans(&mut spans);
+    let outside = match outside.is_empty() {
+        true => String::new(),
+        false => format!(
+            "input tokens outside the source file:\n{}",
+            token_ranges(outside)
+        ),
+    };
+    format!(
+        "[debug_spans] {}\ninput:\n{}\ninput tokens:\n{}\n{}output tokens:\n{}",
+        name,
+        crate::internal::debug_span_with_options(&input_span, code, &options),
+        crate::internal::debug_spans_with_options(&spans, code, &options),
+        outside,
+        audit_respanning(input, output, code),
+    )
+}
+
+/// Lists the tokens and their ranges, when the source file is unknown.
+fn render_ranges(name: &str, input: &TokenStream, output: &TokenStream) -> String {
+    format!(
+        "[debug_spans] {}\nsource file unavailable\ninput tokens:\n{}output tokens:\n{}",
+        name,
+        token_ranges(input.iter_spans()),
+        token_ranges(output.iter_spans()),
+    )
+}
+
+fn token_ranges(tokens: impl IntoIterator<Item = (String, LineColumnSpan)>) -> String {
+    tokens
+        .into_iter()
+        .map(|(text, span)| format!("{} {}\n", text, crate::Span::to_range(&span)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_trace() {
+        let code = "struct Foo {\n    a: i32,\n}";
+        let input = code.parse::<TokenStream>().unwrap();
+        let name = input.clone().into_iter().nth(1).unwrap();
+        let output = TokenStream::from_iter([name]);
+        let rendering = render_trace("derive_foo", &input, &output, code);
+        insta::assert_snapshot!(rendering, @r###"
+        [debug_spans] derive_foo
+        input:
+         --> 1:0..3:1
+          |
+          | ┌───────────────╮
+        1 | struct Foo {    │
+        2 |     a: i32,     │
+        3 | }               │
+          | └───────────────╯
+          |
+
+        input tokens:
+         --> 1:0..1:6, 1:7..1:10, 1:11..1:12, 2:4..2:5, 2:5..2:6, 2:7..2:10, 2:10..2:11, 3:0..3:1
+          |
+        1 | struct Foo {
+          | ^^^^^^
+          |        ^^^
+          |            ^
+        2 |     a: i32,
+          |     ^
+          |      ^
+          |        ^^^
+          |           ^
+        3 | }
+          | ^
+          |
+
+        output tokens:
+        token  span       origin
+        Foo    1:7..1:10  input
+        1 of 1 tokens point into the input
+
+         --> 1:7..1:10
+          |
+        1 | struct Foo {
+          |        ^^^
+          |
+        "###);
+        let rendering = render_ranges("derive_foo", &input, &output);
+        insta::assert_snapshot!(rendering, @r###"
+        [debug_spans] derive_foo
+        source file unavailable
+        input tokens:
+        struct 1:0..1:6
+        Foo 1:7..1:10
+        { 1:11..1:12
+        a 2:4..2:5
+        : 2:5..2:6
+        i32 2:7..2:10
+        , 2:10..2:11
+        } 3:0..3:1
+        output tokens:
+        Foo 1:7..1:10
+        "###);
+    }
+
+    #[test]
+    fn test_render_trace_outside_source() {
+        let input = "struct Foo {\n    a: i32,\n}"
+            .parse::<TokenStream>()
+            .unwrap();
+        let name = input.clone().into_iter().nth(1).unwrap();
+        let output = TokenStream::from_iter([name]);
+        let rendering = render_trace("derive_foo", &input, &output, "struct Foo {");
+        insta::assert_snapshot!(rendering, @r###"
+        [debug_spans] derive_foo
+        input:
+         --> 1:0..1:12
+          |
+        1 | struct Foo {
+          | ^^^^^^^^^^^^
+          |
+
+        input tokens:
+         --> 1:0..1:6, 1:7..1:10, 1:11..1:12
+          |
+        1 | struct Foo {
+          | ^^^^^^
+          |        ^^^
+          |            ^
+          |
+
+        input tokens outside the source file:
+        a 2:4..2:5
+        : 2:5..2:6
+        i32 2:7..2:10
+        , 2:10..2:11
+        } 3:0..3:1
+        output tokens:
+        token  span       origin
+        Foo    1:7..1:10  input
+        1 of 1 tokens point into the input
+
+         --> 1:7..1:10
+          |
+        1 | struct Foo {
+          |        ^^^
+          |
+        "###);
+        let body = TokenStream::from_iter(input.clone().into_iter().nth(2));
+        let rendering = render_trace("derive_foo", &input, &body, "struct Foo {");
+        assert!(
+            rendering.contains("a      2:4..2:5    input"),
+            "{}",
+            rendering
+        );
+        let rendering = render_trace("derive_foo", &input, &output, "");
+        assert_eq!(rendering, render_ranges("derive_foo", &input, &output));
+    }
+}
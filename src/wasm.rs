@@ -0,0 +1,77 @@
+use crate::{DebugSpanOptions, HtmlRenderer, LineColumnSpan, Renderer};
+use serde::Deserialize;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// The JSON form of a span accepted by [`render_span`].
+#[derive(Debug, Deserialize)]
+struct RangeJson {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+/// Render a span of `code`, for web based playgrounds.
+///
+/// `range_json` is an object with `start_line`, `start_column`, `end_line` and `end_column`,
+/// with the same conventions as [`LineColumnSpan`]. The output is the same as
+/// [`debug_span`](crate::debug_span).
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::render_span;
+///
+/// let range = r#"{"start_line": 1, "start_column": 7, "end_line": 1, "end_column": 10}"#;
+/// let output = render_span(range, "struct Foo;").unwrap();
+/// assert_eq!(output, " --> 1:7..1:10\n  |\n1 | struct Foo;\n  |        ^^^\n  |\n");
+/// ```
+#[wasm_bindgen]
+pub fn render_span(range_json: &str, code: &str) -> Result<String, String> {
+    let span = parse_range_json(range_json)?;
+    Ok(crate::debug_span_with_options(
+        span,
+        code,
+        &DebugSpanOptions::default(),
+    ))
+}
+
+/// Render a span of `code` as an HTML `<pre class="debug-span">` element.
+///
+/// See [`render_span`] for more information.
+#[wasm_bindgen]
+pub fn render_span_html(range_json: &str, code: &str) -> Result<String, String> {
+    let span = parse_range_json(range_json)?;
+    Ok(HtmlRenderer.render(&[span], &code, &DebugSpanOptions::default()))
+}
+
+fn parse_range_json(range_json: &str) -> Result<LineColumnSpan, String> {
+    let range = serde_json::from_str::<RangeJson>(range_json)
+        .map_err(|error| format!("invalid range: {}", error))?;
+    Ok(LineColumnSpan::new(
+        range.start_line,
+        range.start_column,
+        range.end_line,
+        range.end_column,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_span_html() {
+        let range = r#"{"start_line": 1, "start_column": 4, "end_line": 1, "end_column": 7}"#;
+        let output = render_span_html(range, "fn f<T>() {}").unwrap();
+        insta::assert_snapshot!(output, @r###"
+        <pre class="debug-span"> --&gt; 1:4..1:7
+          |
+        1 | fn f&lt;T&gt;() {}
+          |     ^^^
+          |
+        </pre>
+        "###);
+        insta::assert_snapshot!(render_span("{}", "").unwrap_err(), @"invalid range: missing field `start_line` at line 1 column 2");
+    }
+}
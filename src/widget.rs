@@ -0,0 +1,189 @@
+use crate::{extract_snippet, ColorDepth, DebugSpanOptions, Snippet, SourceCode, Span};
+use alloc::format;
+use alloc::string::{String, ToString};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::Widget;
+
+/// A ratatui widget rendering the snippet of a span, behind the `ratatui` feature.
+///
+/// The code of the span is drawn in the first color of the [theme](DebugSpanOptions::theme),
+/// and the [label](DebugSpanOptions::label) is shown under its last line. Spans longer than the
+/// area are scrolled with [`SpanWidget::scroll`].
+///
+/// # Example
+///
+/// ```rust
+/// use debug_span::{DebugSpanOptions, LineColumnSpan, SpanWidget};
+/// use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+///
+/// let code = "struct Foo {\n    a: i32,\n}";
+/// let options = DebugSpanOptions::default();
+/// let widget = SpanWidget::new(LineColumnSpan::new(2, 4, 2, 5), code, &options);
+/// let mut buffer = Buffer::empty(Rect::new(0, 0, 20, 2));
+/// widget.render(buffer.area, &mut buffer);
+/// assert_eq!(buffer[(8, 0)].symbol(), "a");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpanWidget {
+    snippet: Snippet,
+    label: Option<String>,
+    gutter: Style,
+    marked: Style,
+    scroll: usize,
+}
+
+impl SpanWidget {
+    /// Create a widget for `span`, using the `context`, `label` and `theme` of `options`.
+    pub fn new(
+        span: impl Span,
+        code: &(impl SourceCode + ?Sized),
+        options: &DebugSpanOptions,
+    ) -> Self {
+        let palette = options.theme.unwrap_or_default().palette();
+        let color = |color: crate::Color| match options.color_depth {
+            ColorDepth::TrueColor => Color::Rgb(color.rgb.0, color.rgb.1, color.rgb.2),
+            ColorDepth::Ansi16 => Color::Indexed(ansi_index(color.ansi)),
+        };
+        Self {
+            snippet: extract_snippet(span, code, options.context),
+            label: options.label.clone(),
+            gutter: Style::new().fg(color(palette.gutter)),
+            marked: Style::new()
+                .fg(color(palette.label(0)))
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            scroll: 0,
+        }
+    }
+
+    /// Skip the first `rows` rows.
+    pub fn scroll(mut self, rows: usize) -> Self {
+        self.scroll = rows;
+        self
+    }
+
+    /// Number of rows of the rendering, to bound the scrolling.
+    pub fn row_count(&self) -> usize {
+        self.snippet.lines.len() + usize::from(self.label.is_some())
+    }
+}
+
+impl Widget for SpanWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = self.snippet.last_line().to_string().len();
+        let label_after = self.snippet.span.end_line;
+        let mut rows = (0..self.row_count()).skip(self.scroll);
+        for y in area.top()..area.bottom() {
+            let Some(row) = rows.next() else {
+                break;
+            };
+            let (index, is_label) = match (row.checked_sub(label_after), &self.label) {
+                (Some(0), Some(_)) => (label_after - 1, true),
+                (Some(_), Some(_)) => (row - 1, false),
+                _ => (row, false),
+            };
+            let gutter = if is_label {
+                format!("{:width$} | ", "", width = width)
+            } else {
+                format!(
+                    "{: >width$} | ",
+                    self.snippet.first_line + index,
+                    width = width
+                )
+            };
+            let (x, _) = buf.set_stringn(area.x, y, &gutter, area.width.into(), self.gutter);
+            let remaining = usize::from(area.right().saturating_sub(x));
+            if let (true, Some(label)) = (is_label, &self.label) {
+                let (column, text) = self.snippet.label_row(label);
+                let column = column.min(remaining) as u16;
+                buf.set_stringn(
+                    x + column,
+                    y,
+                    text,
+                    remaining - usize::from(column),
+                    self.marked,
+                );
+                continue;
+            }
+            let line = &self.snippet.lines[index];
+            let marked = self.snippet.marked_columns(index);
+            for (column, c) in line.chars().enumerate().take(remaining) {
+                let style = match marked {
+                    Some((start, end)) if start <= column && column < end => self.marked,
+                    _ => Style::new(),
+                };
+                buf[(x + column as u16, y)].set_char(c).set_style(style);
+            }
+        }
+    }
+}
+
+/// Converts an SGR foreground code to a 16-color palette index.
+fn ansi_index(code: u8) -> u8 {
+    match code {
+        30..=37 => code - 30,
+        90..=97 => code - 90 + 8,
+        _ => 7,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineColumnSpan;
+    use alloc::vec::Vec;
+
+    fn buffer_text(buffer: &Buffer) -> String {
+        let area = buffer.area;
+        (area.top()..area.bottom())
+            .map(|y| {
+                let line = (area.left()..area.right())
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_span_widget() {
+        let code = "struct Foo {\n    a: i32,\n    b: i32,\n}";
+        let options = DebugSpanOptions {
+            label: Some("expected ident".to_string()),
+            context: 1,
+            ..Default::default()
+        };
+        let widget = SpanWidget::new(LineColumnSpan::new(2, 4, 2, 5), code, &options);
+        assert_eq!(widget.row_count(), 4);
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 24, 5));
+        widget.clone().render(buffer.area, &mut buffer);
+        insta::assert_snapshot!(buffer_text(&buffer), @r###"
+        1 | struct Foo {
+        2 |     a: i32,
+          |     ^ expected ident
+        3 |     b: i32,
+        "###);
+        assert_eq!(buffer[(8, 1)].style().fg, Some(Color::Rgb(224, 108, 117)));
+        assert_eq!(buffer[(9, 1)].style().fg, Some(Color::Reset));
+
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 12, 2));
+        widget.scroll(2).render(buffer.area, &mut buffer);
+        insta::assert_snapshot!(buffer_text(&buffer), @r###"
+          |     ^ ex
+        3 |     b: i
+        "###);
+
+        let widget = SpanWidget::new(LineColumnSpan::new(1, 11, 4, 1), code, &options);
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 24, 5));
+        widget.render(buffer.area, &mut buffer);
+        insta::assert_snapshot!(buffer_text(&buffer), @r###"
+        1 | struct Foo {
+        2 |     a: i32,
+        3 |     b: i32,
+        4 | }
+          | ^ expected ident
+        "###);
+    }
+}